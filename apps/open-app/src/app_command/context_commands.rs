@@ -0,0 +1,51 @@
+use crate::app_service::{
+    ContextPack, ContextPackFormat, ContextSelection, assemble_context_pack, collect_context_items,
+};
+use crate::app_state::AppState;
+use std::path::PathBuf;
+
+use super::dto::BuildContextPackDto;
+
+#[tauri::command]
+pub fn build_context_pack(
+    dto: BuildContextPackDto,
+    state: tauri::State<AppState>,
+) -> Result<ContextPack, String> {
+    let db = state.db();
+
+    if dto.token_budget == 0 {
+        return Err("Token budget must be greater than zero".to_string());
+    }
+
+    let selection = ContextSelection {
+        paths: dto.paths.unwrap_or_default(),
+        symbol_ids: dto.symbol_ids.unwrap_or_default(),
+        note_ids: dto.note_ids.unwrap_or_default(),
+        link_ids: dto.link_ids.unwrap_or_default(),
+        conversations: dto.conversations.unwrap_or_default(),
+    };
+
+    let items = collect_context_items(&db, &selection)?;
+    let title = dto.title.unwrap_or_else(|| "Untitled".to_string());
+    let format = dto.format.unwrap_or(ContextPackFormat::Markdown);
+    let mut pack = assemble_context_pack(&title, items, dto.token_budget, format);
+
+    if let Some(output_path) = dto.output_path {
+        let path = PathBuf::from(&output_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create output directory: {}", e))?;
+        }
+        std::fs::write(&path, &pack.content)
+            .map_err(|e| format!("Failed to write context pack: {}", e))?;
+        pack.output_path = Some(output_path);
+    }
+
+    log::info!(
+        "Context pack built: {} entries, ~{} tokens (budget {})",
+        pack.entries.len(),
+        pack.token_count,
+        pack.token_budget
+    );
+    Ok(pack)
+}
@@ -1,4 +1,10 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::app_service::{
+    ContextPackFormat, ContextPathSelection, ConversationExcerpt, GraphFormat, GraphGranularity,
+};
+use crate::app_state::{GitCredentialKind, IndexJobType, TemplateField};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -36,6 +42,42 @@ pub struct UpdateNoteDto {
     pub tags: Option<Vec<String>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateNoteTemplateDto {
+    pub workspace_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub note_type: String,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub fields: Option<Vec<TemplateField>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNoteTemplateDto {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub note_type: Option<String>,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub fields: Option<Vec<TemplateField>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateNoteFromTemplateDto {
+    pub template_id: String,
+    pub workspace_id: String,
+    /// 为空时使用模板标题
+    pub title: Option<String>,
+    /// 模板字段的取值
+    pub values: Option<HashMap<String, String>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateFileDto {
@@ -74,18 +116,138 @@ pub struct UpdateRepositoryDto {
     pub branch: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportLocalRepositoryDto {
+    pub workspace_id: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportLocalRepositoriesDto {
+    pub workspace_id: String,
+    pub root_path: String,
+    pub max_depth: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchDependenciesDto {
+    pub workspace_id: String,
+    pub name: String,
+    /// 版本范围，如 `1.x`、`^18`
+    pub version: Option<String>,
+    /// cargo / npm / python / go
+    pub project_type: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CloneRepositoryTaskDto {
     pub workspace_id: String,
     pub url: String,
     pub branch: Option<String>,
+    /// 浅克隆深度
+    pub depth: Option<u32>,
+    pub single_branch: Option<bool>,
+    /// 稀疏检出的路径模式
+    pub sparse_paths: Option<Vec<String>>,
+    /// 递归克隆子模块，并登记为子仓库
+    pub recursive_submodules: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteRepositoryTaskDto {
+    pub repository_id: String,
+    /// 同时删除本地克隆目录（仅限工作空间目录下由应用克隆的仓库）
+    pub delete_local_clone: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct IndexRepositoryTaskDto {
     pub repository_id: String,
+    pub job_type: Option<IndexJobType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncWorkspaceRepositoriesTaskDto {
+    pub workspace_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitLogDto {
+    pub repository_id: String,
+    pub rev: Option<String>,
+    pub path: Option<String>,
+    pub author: Option<String>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryAnalyticsDto {
+    pub repository_id: String,
+    pub max_commits: Option<usize>,
+    /// 只统计该时间（毫秒）之后的提交
+    pub since: Option<i64>,
+    pub top: Option<usize>,
+    /// 忽略缓存重新计算
+    pub refresh: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitPathsDto {
+    pub repository_id: String,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutBranchDto {
+    pub repository_id: String,
+    pub branch: String,
+    pub create: Option<bool>,
+    pub start_point: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddWorktreeDto {
+    pub repository_id: String,
+    pub name: String,
+    pub path: Option<String>,
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateGitCredentialDto {
+    pub remote_pattern: String,
+    pub kind: GitCredentialKind,
+    pub username: Option<String>,
+    pub ssh_key_path: Option<String>,
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateGitCredentialDto {
+    pub remote_pattern: Option<String>,
+    pub kind: Option<GitCredentialKind>,
+    pub username: Option<String>,
+    pub ssh_key_path: Option<String>,
+    /// 为空时保留原有口令 / 令牌
+    pub secret: Option<String>,
+    /// 清除已保存的口令 / 令牌，同时传入 secret 时以 secret 为准
+    #[serde(default)]
+    pub clear_secret: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -95,6 +257,61 @@ pub struct ImportFilesTaskDto {
     pub paths: Vec<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportMarkdownVaultTaskDto {
+    pub workspace_id: String,
+    /// Obsidian 仓库或 Markdown 目录
+    pub vault_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportNotionExportTaskDto {
+    pub workspace_id: String,
+    /// Notion 导出的 Markdown & CSV 压缩包
+    pub zip_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportNotesTaskDto {
+    pub workspace_id: String,
+    /// 只导出该笔记及其子笔记，为空时导出整个工作区
+    pub note_id: Option<String>,
+    /// markdown 或 html
+    pub format: String,
+    /// 导出的 zip 文件路径
+    pub output_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportDependencyGraphDto {
+    pub repository_id: String,
+    pub format: GraphFormat,
+    pub granularity: Option<GraphGranularity>,
+    pub root_path: Option<String>,
+    pub collapse_depth: Option<usize>,
+    pub max_depth: Option<usize>,
+    pub edge_types: Option<Vec<String>>,
+    pub include_external: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildContextPackDto {
+    pub title: Option<String>,
+    pub paths: Option<Vec<ContextPathSelection>>,
+    pub symbol_ids: Option<Vec<String>>,
+    pub note_ids: Option<Vec<String>>,
+    pub link_ids: Option<Vec<String>>,
+    pub conversations: Option<Vec<ConversationExcerpt>>,
+    pub token_budget: usize,
+    pub format: Option<ContextPackFormat>,
+    pub output_path: Option<String>,
+}
+
 // Chat DTOs
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,407 @@
+use crate::app_service::{
+    AnalyticsOptions, BlameLine, CheckoutResult, CommitDetail, CommitLogPage, CommitLogQuery,
+    CommitSummary, GitBranch, GitTag, GitWorktree, RepositoryAnalytics, SyncReport, TaskManager,
+    WorkingTreeEntry, add_worktree, blame_file, checkout_branch, clear_dir_cache, commit_staged,
+    discard_paths, get_commit_detail, get_working_tree_status, list_branches, list_commits,
+    list_tags, list_worktrees, open_repository, remove_worktree, repository_analytics, stage_paths,
+    sync_git_repository, unstage_paths,
+};
+use crate::app_state::{
+    AppState, CloneStatus, DatabaseManager, GitCredential, GitCredentialKind, GitRepository,
+    IndexJobType, normalize_remote,
+};
+
+use super::dto::{
+    AddWorktreeDto, CheckoutBranchDto, CreateGitCredentialDto, GitLogDto, GitPathsDto,
+    RepositoryAnalyticsDto, UpdateGitCredentialDto,
+};
+use super::task_commands::spawn_index_task;
+use std::path::PathBuf;
+
+/// 默认每页提交数
+const DEFAULT_LOG_LIMIT: usize = 50;
+
+/// 读取已完成克隆的仓库记录
+fn load_cloned_repository(db: &DatabaseManager, id: &str) -> Result<GitRepository, String> {
+    let repo = match db.get_git_repository(id) {
+        Ok(Some(repo)) => repo,
+        Ok(None) => return Err(format!("Repository not found: {}", id)),
+        Err(e) => return Err(format!("Failed to fetch repository: {}", e)),
+    };
+    if repo.clone_status != CloneStatus::Completed {
+        return Err(format!("Repository is not cloned yet: {}", repo.name));
+    }
+    Ok(repo)
+}
+
+#[tauri::command]
+pub async fn sync_repository(
+    app: tauri::AppHandle,
+    repository_id: String,
+    state: tauri::State<'_, AppState>,
+    task_manager: tauri::State<'_, TaskManager>,
+) -> Result<SyncReport, String> {
+    let db = state.db();
+    let repo = load_cloned_repository(&db, &repository_id)?;
+
+    let sync_db = db.clone();
+    let sync_repo = repo.clone();
+    let report =
+        tauri::async_runtime::spawn_blocking(move || sync_git_repository(&sync_db, &sync_repo))
+            .await
+            .map_err(|e| format!("Failed to sync repository: {}", e))??;
+
+    log::info!(
+        "Repository synced: {} ({:?}, ahead {}, behind {})",
+        repo.name,
+        report.state,
+        report.ahead,
+        report.behind
+    );
+
+    if report.head_moved {
+        spawn_index_task(
+            app,
+            task_manager.inner().clone(),
+            db,
+            repo.id,
+            IndexJobType::Incremental,
+        );
+    }
+
+    Ok(report)
+}
+
+#[tauri::command]
+pub fn get_git_log(dto: GitLogDto, state: tauri::State<AppState>) -> Result<CommitLogPage, String> {
+    let db = state.db();
+    let repo = load_cloned_repository(&db, &dto.repository_id)?;
+    let git_repo = open_repository(&repo.local_path)?;
+
+    let query = CommitLogQuery {
+        rev: dto.rev,
+        path: dto.path,
+        author: dto.author,
+        offset: dto.offset.unwrap_or(0),
+        limit: dto.limit.unwrap_or(DEFAULT_LOG_LIMIT),
+    };
+    list_commits(&git_repo, &query)
+}
+
+#[tauri::command]
+pub fn get_git_commit(
+    repository_id: String,
+    commit_id: String,
+    state: tauri::State<AppState>,
+) -> Result<CommitDetail, String> {
+    let db = state.db();
+    let repo = load_cloned_repository(&db, &repository_id)?;
+    let git_repo = open_repository(&repo.local_path)?;
+    get_commit_detail(&git_repo, &commit_id)
+}
+
+#[tauri::command]
+pub fn get_git_blame(
+    repository_id: String,
+    path: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<BlameLine>, String> {
+    let db = state.db();
+    let repo = load_cloned_repository(&db, &repository_id)?;
+    let git_repo = open_repository(&repo.local_path)?;
+    blame_file(&git_repo, &path)
+}
+
+#[tauri::command]
+pub async fn get_repository_analytics(
+    dto: RepositoryAnalyticsDto,
+    state: tauri::State<'_, AppState>,
+) -> Result<RepositoryAnalytics, String> {
+    let db = state.db();
+    let repo = load_cloned_repository(&db, &dto.repository_id)?;
+
+    let defaults = AnalyticsOptions::default();
+    let options = AnalyticsOptions {
+        max_commits: dto.max_commits.unwrap_or(defaults.max_commits),
+        since: dto.since,
+        top: dto.top.unwrap_or(defaults.top),
+    };
+    let refresh = dto.refresh.unwrap_or(false);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        repository_analytics(&db, &repo, &options, refresh)
+    })
+    .await
+    .map_err(|e| format!("Failed to analyze repository: {}", e))?
+}
+
+#[tauri::command]
+pub fn get_git_status(
+    repository_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<WorkingTreeEntry>, String> {
+    let db = state.db();
+    let repo = load_cloned_repository(&db, &repository_id)?;
+    let git_repo = open_repository(&repo.local_path)?;
+    get_working_tree_status(&git_repo)
+}
+
+#[tauri::command]
+pub fn stage_git_paths(dto: GitPathsDto, state: tauri::State<AppState>) -> Result<(), String> {
+    let db = state.db();
+    let repo = load_cloned_repository(&db, &dto.repository_id)?;
+    let git_repo = open_repository(&repo.local_path)?;
+    stage_paths(&git_repo, &dto.paths)?;
+
+    clear_dir_cache(None);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn unstage_git_paths(dto: GitPathsDto, state: tauri::State<AppState>) -> Result<(), String> {
+    let db = state.db();
+    let repo = load_cloned_repository(&db, &dto.repository_id)?;
+    let git_repo = open_repository(&repo.local_path)?;
+    unstage_paths(&git_repo, &dto.paths)?;
+
+    clear_dir_cache(None);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn discard_git_changes(dto: GitPathsDto, state: tauri::State<AppState>) -> Result<(), String> {
+    let db = state.db();
+    let repo = load_cloned_repository(&db, &dto.repository_id)?;
+    let git_repo = open_repository(&repo.local_path)?;
+    discard_paths(&git_repo, &dto.paths)?;
+
+    // 文件树缓存中的 git 装饰已过期
+    clear_dir_cache(None);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn commit_git_changes(
+    repository_id: String,
+    message: String,
+    state: tauri::State<AppState>,
+) -> Result<CommitSummary, String> {
+    let db = state.db();
+    let repo = load_cloned_repository(&db, &repository_id)?;
+    let git_repo = open_repository(&repo.local_path)?;
+    let commit = commit_staged(&git_repo, &message)?;
+
+    clear_dir_cache(None);
+    log::info!("Committed {} in repository {}", commit.short_id, repo.name);
+    Ok(commit)
+}
+
+#[tauri::command]
+pub fn list_git_branches(
+    repository_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<GitBranch>, String> {
+    let db = state.db();
+    let repo = load_cloned_repository(&db, &repository_id)?;
+    let git_repo = open_repository(&repo.local_path)?;
+    list_branches(&git_repo)
+}
+
+#[tauri::command]
+pub fn list_git_tags(
+    repository_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<GitTag>, String> {
+    let db = state.db();
+    let repo = load_cloned_repository(&db, &repository_id)?;
+    let git_repo = open_repository(&repo.local_path)?;
+    list_tags(&git_repo)
+}
+
+#[tauri::command]
+pub fn checkout_git_branch(
+    app: tauri::AppHandle,
+    dto: CheckoutBranchDto,
+    state: tauri::State<AppState>,
+    task_manager: tauri::State<TaskManager>,
+) -> Result<CheckoutResult, String> {
+    let db = state.db();
+    let mut repo = load_cloned_repository(&db, &dto.repository_id)?;
+    let git_repo = open_repository(&repo.local_path)?;
+
+    let result = checkout_branch(
+        &git_repo,
+        &dto.branch,
+        dto.create.unwrap_or(false),
+        dto.start_point.as_deref(),
+    )?;
+
+    repo.branch = result.branch.clone();
+    db.update_git_repository(&repo)
+        .map_err(|e| format!("Failed to update repository: {}", e))?;
+    log::info!("Repository {} switched to {}", repo.name, repo.branch);
+
+    // 保持索引与当前检出一致
+    if result.head_moved {
+        spawn_index_task(
+            app,
+            task_manager.inner().clone(),
+            db,
+            repo.id,
+            IndexJobType::Incremental,
+        );
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn list_git_worktrees(
+    repository_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<GitWorktree>, String> {
+    let db = state.db();
+    let repo = load_cloned_repository(&db, &repository_id)?;
+    let git_repo = open_repository(&repo.local_path)?;
+    list_worktrees(&git_repo)
+}
+
+#[tauri::command]
+pub fn add_git_worktree(
+    dto: AddWorktreeDto,
+    state: tauri::State<AppState>,
+) -> Result<GitWorktree, String> {
+    let db = state.db();
+    let repo = load_cloned_repository(&db, &dto.repository_id)?;
+    let git_repo = open_repository(&repo.local_path)?;
+
+    // 默认放在克隆目录旁的 `<目录名>.worktrees/<name>` 下
+    let path = match dto.path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let dir_name = repo
+                .local_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| repo.name.clone());
+            repo.local_path
+                .with_file_name(format!("{}.worktrees", dir_name))
+                .join(&dto.name)
+        }
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create worktree directory: {}", e))?;
+    }
+
+    add_worktree(&git_repo, &dto.name, &path, dto.branch.as_deref())
+}
+
+#[tauri::command]
+pub fn remove_git_worktree(
+    repository_id: String,
+    name: String,
+    force: Option<bool>,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    let db = state.db();
+    let repo = load_cloned_repository(&db, &repository_id)?;
+    let git_repo = open_repository(&repo.local_path)?;
+    remove_worktree(&git_repo, &name, force.unwrap_or(false))
+}
+
+/// 校验凭据配置是否完整
+fn validate_credential(credential: &GitCredential) -> Result<(), String> {
+    if credential.remote_pattern.is_empty() {
+        return Err("Remote pattern cannot be empty".to_string());
+    }
+    match credential.kind {
+        GitCredentialKind::SshKey if credential.ssh_key_path.is_none() => {
+            Err("SSH key path is required".to_string())
+        }
+        GitCredentialKind::HttpsToken if credential.secret.is_none() => {
+            Err("HTTPS token is required".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn list_git_credentials(state: tauri::State<AppState>) -> Result<Vec<GitCredential>, String> {
+    let db = state.db();
+    db.list_git_credentials()
+        .map_err(|e| format!("Failed to fetch git credentials: {}", e))
+}
+
+#[tauri::command]
+pub fn create_git_credential(
+    dto: CreateGitCredentialDto,
+    state: tauri::State<AppState>,
+) -> Result<GitCredential, String> {
+    let db = state.db();
+
+    let mut credential = GitCredential::new(dto.remote_pattern, dto.kind);
+    credential.username = dto.username;
+    credential.ssh_key_path = dto.ssh_key_path;
+    credential.secret = dto.secret;
+    validate_credential(&credential)?;
+
+    match db.create_git_credential(&credential) {
+        Ok(_) => {
+            log::info!("Git credential created for {}", credential.remote_pattern);
+            Ok(credential)
+        }
+        Err(e) => Err(format!("Failed to create git credential: {}", e)),
+    }
+}
+
+#[tauri::command]
+pub fn update_git_credential(
+    id: String,
+    dto: UpdateGitCredentialDto,
+    state: tauri::State<AppState>,
+) -> Result<GitCredential, String> {
+    let db = state.db();
+
+    let mut credential = match db.get_git_credential(&id) {
+        Ok(Some(credential)) => credential,
+        Ok(None) => return Err(format!("Git credential not found: {}", id)),
+        Err(e) => return Err(format!("Failed to fetch git credential: {}", e)),
+    };
+
+    if let Some(pattern) = dto.remote_pattern {
+        credential.remote_pattern = normalize_remote(&pattern);
+    }
+    if let Some(kind) = dto.kind {
+        credential.kind = kind;
+    }
+    if let Some(username) = dto.username {
+        credential.username = Some(username);
+    }
+    if let Some(path) = dto.ssh_key_path {
+        credential.ssh_key_path = Some(path);
+    }
+    // 未传入时保留原有口令 / 令牌
+    if dto.clear_secret {
+        credential.secret = None;
+    }
+    if let Some(secret) = dto.secret {
+        credential.secret = Some(secret);
+    }
+    validate_credential(&credential)?;
+
+    match db.update_git_credential(&credential) {
+        Ok(_) => Ok(credential),
+        Err(e) => Err(format!("Failed to update git credential: {}", e)),
+    }
+}
+
+#[tauri::command]
+pub fn delete_git_credential(id: String, state: tauri::State<AppState>) -> Result<bool, String> {
+    let db = state.db();
+
+    match db.delete_git_credential(&id) {
+        Ok(_) => Ok(true),
+        Err(e) => Err(format!("Failed to delete git credential: {}", e)),
+    }
+}
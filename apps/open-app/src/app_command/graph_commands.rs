@@ -0,0 +1,45 @@
+use crate::app_service::{
+    GraphExportOptions, GraphGranularity, build_dependency_graph, load_repo_edges,
+};
+use crate::app_state::{AppConfig, AppState};
+
+use super::dto::ExportDependencyGraphDto;
+
+#[tauri::command]
+pub fn export_dependency_graph(
+    dto: ExportDependencyGraphDto,
+    state: tauri::State<AppState>,
+) -> Result<String, String> {
+    let db = state.db();
+
+    let repo = match db.get_git_repository(&dto.repository_id) {
+        Ok(Some(repo)) => repo,
+        Ok(None) => return Err(format!("Repository not found: {}", dto.repository_id)),
+        Err(e) => return Err(format!("Failed to fetch repository: {}", e)),
+    };
+
+    let options = GraphExportOptions {
+        granularity: dto.granularity.unwrap_or(GraphGranularity::Module),
+        format: dto.format,
+        root_path: dto.root_path,
+        collapse_depth: dto.collapse_depth,
+        max_depth: dto.max_depth,
+        edge_types: dto.edge_types,
+        include_external: dto.include_external.unwrap_or(false),
+    };
+
+    let edges = load_repo_edges(
+        &AppConfig::sqlite_edge_db_path(),
+        &repo.workspace_id,
+        &repo.id,
+    )?;
+    let graph = build_dependency_graph(&edges, &repo.workspace_id, &repo.id, &options);
+
+    log::info!(
+        "Dependency graph exported for {}: {} nodes, {} edges",
+        repo.name,
+        graph.nodes.len(),
+        graph.edges.len()
+    );
+    graph.render(options.format)
+}
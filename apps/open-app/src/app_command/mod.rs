@@ -1,14 +1,19 @@
 mod chat_commands;
+mod context_commands;
 mod directory_commands;
 mod dto;
 mod file_commands;
 mod file_tree_commands;
+mod git_commands;
+mod graph_commands;
 mod link_commands;
 mod note_commands;
 mod repository_commands;
 mod system_commands;
+mod tag_commands;
 mod task_commands;
 mod terminal_commands;
+mod todo_commands;
 mod webview_commands;
 mod workspace_commands;
 
@@ -47,16 +52,69 @@ pub fn setup_invoke_handler(builder: TauriBuilder) -> TauriBuilder {
         note_commands::update_note,
         note_commands::delete_note,
         note_commands::search_notes,
+        note_commands::list_note_revisions,
+        note_commands::diff_note_revisions,
+        note_commands::restore_note_revision,
+        note_commands::get_backlinks,
+        note_commands::get_unresolved_links,
+        note_commands::get_note_attachments,
+        note_commands::get_note_tree,
+        note_commands::move_note,
+        note_commands::move_notes,
+        note_commands::start_note_sync,
+        note_commands::stop_note_sync,
+        note_commands::sync_note_files,
+        note_commands::get_note_sync_conflicts,
+        note_commands::resolve_note_sync_conflict,
         note_commands::get_notes_by_type,
         note_commands::toggle_note_favorite,
         note_commands::set_note_favorite,
         note_commands::get_favorited_notes,
+        note_commands::get_note_templates,
+        note_commands::create_note_template,
+        note_commands::update_note_template,
+        note_commands::delete_note_template,
+        note_commands::create_note_from_template,
+        note_commands::get_or_create_daily_note,
+        note_commands::get_note_calendar,
+        note_commands::get_notes_on_day,
+        note_commands::get_note_insight,
         // repository
         repository_commands::get_all_repositories,
         repository_commands::get_repository,
         repository_commands::create_repository,
         repository_commands::update_repository,
         repository_commands::delete_repository,
+        repository_commands::import_local_repository,
+        repository_commands::import_local_repositories,
+        repository_commands::get_repository_manifests,
+        repository_commands::search_dependencies,
+        // git
+        git_commands::sync_repository,
+        git_commands::get_git_log,
+        git_commands::get_git_commit,
+        git_commands::get_git_blame,
+        git_commands::get_repository_analytics,
+        git_commands::get_git_status,
+        git_commands::stage_git_paths,
+        git_commands::unstage_git_paths,
+        git_commands::discard_git_changes,
+        git_commands::commit_git_changes,
+        git_commands::list_git_branches,
+        git_commands::list_git_tags,
+        git_commands::checkout_git_branch,
+        git_commands::list_git_worktrees,
+        git_commands::add_git_worktree,
+        git_commands::remove_git_worktree,
+        // git credential
+        git_commands::list_git_credentials,
+        git_commands::create_git_credential,
+        git_commands::update_git_credential,
+        git_commands::delete_git_credential,
+        // graph
+        graph_commands::export_dependency_graph,
+        // context
+        context_commands::build_context_pack,
         // task
         task_commands::get_task,
         task_commands::list_tasks,
@@ -64,7 +122,12 @@ pub fn setup_invoke_handler(builder: TauriBuilder) -> TauriBuilder {
         task_commands::cleanup_tasks,
         task_commands::clone_repository_task,
         task_commands::index_repository_task,
+        task_commands::sync_workspace_repositories_task,
+        task_commands::delete_repository_task,
         task_commands::import_files_task,
+        task_commands::import_markdown_vault_task,
+        task_commands::import_notion_export_task,
+        task_commands::export_notes_task,
         // chat
         chat_commands::get_all_chats,
         chat_commands::get_chat,
@@ -110,6 +173,18 @@ pub fn setup_invoke_handler(builder: TauriBuilder) -> TauriBuilder {
         link_commands::toggle_link_favorite,
         link_commands::archive_link,
         link_commands::get_favorite_links,
+        // tag
+        tag_commands::get_tags,
+        tag_commands::get_entity_tags,
+        tag_commands::set_file_tags,
+        tag_commands::rename_tag,
+        tag_commands::merge_tags,
+        tag_commands::delete_tag,
+        tag_commands::set_tag_color,
+        tag_commands::get_tagged_entities,
+        // todo
+        todo_commands::get_todos,
+        todo_commands::toggle_todo,
         // directory
         directory_commands::get_all_directories,
         directory_commands::get_directory,
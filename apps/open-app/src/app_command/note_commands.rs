@@ -1,8 +1,77 @@
-use crate::app_state::{AppState, Note, NoteType};
-use chrono::Utc;
+use crate::app_service::{
+    self, NoteCalendarDay, NoteDayActivity, NoteRevisionDiff, NoteSyncConflict, NoteSyncOutcome,
+    NoteSyncReport, NoteSyncSide, build_note_from_template, compare_note_revisions,
+    find_note_template, list_note_templates, note_calendar, note_sync_conflicts, notebook_root,
+    notes_on_day, notify_note_saved, parse_day, record_note_revision, refresh_note_insight,
+    remove_note_file, rename_note_links, resolve_note_file_conflict, restore_note_to_revision,
+    start_markdown_sync, stop_note_file_watcher, sync_note_file, sync_note_links,
+    sync_workspace_note_files, validate_and_move_notes, workspace_note_tree,
+};
+use crate::app_state::{
+    AppState, ImportedFile, Note, NoteBacklink, NoteInsight, NoteMove, NoteRevision,
+    NoteSearchResult, NoteTemplate, NoteTreeNode, NoteType,
+};
+use chrono::{Local, Utc};
 use std::path::PathBuf;
+use tauri::AppHandle;
 
-use super::dto::{CreateNoteDto, UpdateNoteDto};
+use super::dto::{
+    CreateNoteDto, CreateNoteFromTemplateDto, CreateNoteTemplateDto, UpdateNoteDto,
+    UpdateNoteTemplateDto,
+};
+
+/// 笔记检索默认返回的结果数
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/// 笔记保存后的收尾：记录修订版本，更新双链，开启 Markdown 同步时将笔记写入文件，
+/// 最后唤醒后台分析。返回带有新文件路径的笔记
+///
+/// `workspace` 为 true 时同步整个工作空间，用于改名后其他笔记的链接也被改写的情况。
+pub(super) fn after_note_saved(state: &AppState, note: Note, workspace: bool) -> Note {
+    let db = state.db();
+    let config = state.config().notes.unwrap_or_default();
+    if let Err(e) = record_note_revision(&db, &config, &note) {
+        log::warn!("{}", e);
+    }
+    if let Err(e) = sync_note_links(&db, &note) {
+        log::warn!("{}", e);
+    }
+    let note = mirror_note_file(state, note, workspace);
+    notify_note_saved();
+    note
+}
+
+/// 开启 Markdown 同步时将笔记写入文件，返回带有新文件路径的笔记
+fn mirror_note_file(state: &AppState, note: Note, workspace: bool) -> Note {
+    let config = state.config();
+    let notes_config = config.notes.unwrap_or_default();
+    if !notes_config.markdown_sync {
+        return note;
+    }
+    let db = state.db();
+
+    let result = notebook_root(&config.notebook_dir).and_then(|root| {
+        if workspace {
+            sync_workspace_note_files(&db, &root, &notes_config, &note.workspace_id)
+                .map(|report| report.conflicts > 0)
+        } else {
+            sync_note_file(&db, &root, &notes_config, &note)
+                .map(|outcome| outcome == NoteSyncOutcome::Conflict)
+        }
+    });
+    match result {
+        Ok(true) => log::warn!("Note file sync has conflicts: {}", note.id),
+        Ok(false) => {}
+        Err(e) => {
+            log::warn!("{}", e);
+            return note;
+        }
+    }
+    match db.get_note(&note.id) {
+        Ok(Some(updated)) => updated,
+        _ => note,
+    }
+}
 
 #[tauri::command]
 pub fn get_all_notes(
@@ -39,23 +108,19 @@ pub fn create_note(dto: CreateNoteDto, state: tauri::State<AppState>) -> Result<
     let default_path = format!("/notes/{}.md", dto.title);
     let file_path = dto.file_path.unwrap_or(default_path).into();
 
-    let note = Note::new(
+    let mut note = Note::new(
         dto.workspace_id.clone(),
         dto.title,
         note_type,
         dto.content.unwrap_or_default(),
         file_path,
     );
+    note.tags = dto.tags.unwrap_or_default();
 
     match db.create_note(&note) {
         Ok(_) => {
-            if let Some(tags) = dto.tags {
-                for tag in tags {
-                    let _ = db.add_note_tag(&note.id, &tag);
-                }
-            }
             log::info!("Note created: {}", note.title);
-            Ok(note)
+            Ok(after_note_saved(&state, note, false))
         }
         Err(e) => Err(format!("Failed to create note: {}", e)),
     }
@@ -71,6 +136,7 @@ pub fn update_note(
 
     match db.get_note(&id) {
         Ok(Some(mut note)) => {
+            let old_title = note.title.clone();
             if let Some(title) = dto.title {
                 note.title = title;
             }
@@ -86,18 +152,29 @@ pub fn update_note(
                 note.file_path = PathBuf::from(file_path);
             }
             if let Some(tags) = dto.tags {
-                for existing_tag in &note.tags {
-                    let _ = db.remove_note_tag(&note.id, existing_tag);
-                }
-                for tag in &tags {
-                    let _ = db.add_note_tag(&note.id, tag);
-                }
                 note.tags = tags;
             }
             note.updated_at = Utc::now().timestamp_millis();
 
             match db.update_note(&note) {
-                Ok(_) => Ok(note),
+                Ok(_) => {
+                    let mut links_rewritten = false;
+                    if note.title != old_title {
+                        let config = state.config().notes.unwrap_or_default();
+                        match rename_note_links(&db, &config, &note, &old_title) {
+                            // 笔记可能链接了自身，改写后重新读取
+                            Ok(count) if count > 0 => {
+                                links_rewritten = true;
+                                if let Ok(Some(updated)) = db.get_note(&note.id) {
+                                    note.content = updated.content;
+                                }
+                            }
+                            Ok(_) => {}
+                            Err(e) => log::warn!("{}", e),
+                        }
+                    }
+                    Ok(after_note_saved(&state, note, links_rewritten))
+                }
                 Err(e) => Err(format!("Failed to update note: {}", e)),
             }
         }
@@ -110,6 +187,12 @@ pub fn update_note(
 pub fn delete_note(id: String, state: tauri::State<AppState>) -> Result<bool, String> {
     let db = state.db();
 
+    if state.config().notes.unwrap_or_default().markdown_sync
+        && let Err(e) = remove_note_file(&db, &id)
+    {
+        log::warn!("{}", e);
+    }
+
     match db.delete_note(&id) {
         Ok(_) => {
             log::info!("Note deleted: {}", id);
@@ -123,11 +206,12 @@ pub fn delete_note(id: String, state: tauri::State<AppState>) -> Result<bool, St
 pub fn search_notes(
     workspace_id: String,
     query: String,
+    limit: Option<usize>,
     state: tauri::State<AppState>,
-) -> Result<Vec<Note>, String> {
+) -> Result<Vec<NoteSearchResult>, String> {
     let db = state.db();
 
-    match db.search_notes(&workspace_id, &query) {
+    match db.search_notes(&workspace_id, &query, limit.unwrap_or(DEFAULT_SEARCH_LIMIT)) {
         Ok(notes) => Ok(notes),
         Err(e) => Err(format!("Failed to search notes: {}", e)),
     }
@@ -192,3 +276,391 @@ pub fn get_favorited_notes(
         Err(e) => Err(format!("Failed to fetch favorited notes: {}", e)),
     }
 }
+
+#[tauri::command]
+pub fn list_note_revisions(
+    note_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<NoteRevision>, String> {
+    let db = state.db();
+
+    match db.list_note_revisions(&note_id) {
+        Ok(revisions) => Ok(revisions),
+        Err(e) => Err(format!("Failed to fetch note revisions: {}", e)),
+    }
+}
+
+#[tauri::command]
+pub fn diff_note_revisions(
+    from_revision_id: String,
+    to_revision_id: String,
+    state: tauri::State<AppState>,
+) -> Result<NoteRevisionDiff, String> {
+    let db = state.db();
+    compare_note_revisions(&db, &from_revision_id, &to_revision_id)
+}
+
+#[tauri::command]
+pub fn restore_note_revision(
+    revision_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Note, String> {
+    let db = state.db();
+    let config = state.config().notes.unwrap_or_default();
+
+    let note = restore_note_to_revision(&db, &config, &revision_id)?;
+    log::info!("Note {} restored to revision {}", note.id, revision_id);
+    Ok(after_note_saved(&state, note, false))
+}
+
+#[tauri::command]
+pub fn get_backlinks(
+    note_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<NoteBacklink>, String> {
+    let db = state.db();
+
+    match db.list_backlinks(&note_id) {
+        Ok(backlinks) => Ok(backlinks),
+        Err(e) => Err(format!("Failed to fetch backlinks: {}", e)),
+    }
+}
+
+#[tauri::command]
+pub fn get_unresolved_links(
+    workspace_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<NoteBacklink>, String> {
+    let db = state.db();
+
+    match db.list_unresolved_links(&workspace_id) {
+        Ok(links) => Ok(links),
+        Err(e) => Err(format!("Failed to fetch unresolved links: {}", e)),
+    }
+}
+
+#[tauri::command]
+pub fn get_note_attachments(
+    note_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<ImportedFile>, String> {
+    let db = state.db();
+
+    match db.list_note_attachments(&note_id) {
+        Ok(files) => Ok(files),
+        Err(e) => Err(format!("Failed to fetch note attachments: {}", e)),
+    }
+}
+
+#[tauri::command]
+pub fn get_note_tree(
+    workspace_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<NoteTreeNode>, String> {
+    let db = state.db();
+    workspace_note_tree(&db, &workspace_id)
+}
+
+#[tauri::command]
+pub fn move_note(
+    id: String,
+    parent_id: Option<String>,
+    index: usize,
+    state: tauri::State<AppState>,
+) -> Result<bool, String> {
+    let db = state.db();
+    let note_move = NoteMove {
+        note_id: id,
+        parent_id,
+        index,
+    };
+
+    validate_and_move_notes(&db, std::slice::from_ref(&note_move))?;
+    log::info!("Note moved: {}", note_move.note_id);
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn move_notes(moves: Vec<NoteMove>, state: tauri::State<AppState>) -> Result<bool, String> {
+    let db = state.db();
+
+    validate_and_move_notes(&db, &moves)?;
+    log::info!("Notes moved: {}", moves.len());
+    Ok(true)
+}
+
+/// 开启 Markdown 同步：全量同步所有工作空间并监听 notebook_dir 中的外部修改
+#[tauri::command]
+pub async fn start_note_sync(
+    app_handle: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<NoteSyncReport, String> {
+    state
+        .update_config(|config| {
+            config
+                .notes
+                .get_or_insert_with(Default::default)
+                .markdown_sync = true;
+            Ok(())
+        })
+        .map_err(|e| format!("Failed to update config: {}", e))?;
+
+    let config = state.config();
+    let db = state.db();
+    tauri::async_runtime::spawn_blocking(move || start_markdown_sync(app_handle, db, &config))
+        .await
+        .map_err(|e| format!("Failed to start note sync: {}", e))?
+}
+
+#[tauri::command]
+pub fn stop_note_sync(state: tauri::State<AppState>) -> Result<(), String> {
+    stop_note_file_watcher();
+    state
+        .update_config(|config| {
+            config
+                .notes
+                .get_or_insert_with(Default::default)
+                .markdown_sync = false;
+            Ok(())
+        })
+        .map_err(|e| format!("Failed to update config: {}", e))?;
+    log::info!("Note sync stopped");
+    Ok(())
+}
+
+#[tauri::command]
+pub fn sync_note_files(
+    workspace_id: String,
+    state: tauri::State<AppState>,
+) -> Result<NoteSyncReport, String> {
+    let config = state.config();
+    let root = notebook_root(&config.notebook_dir)?;
+    let db = state.db();
+    sync_workspace_note_files(&db, &root, &config.notes.unwrap_or_default(), &workspace_id)
+}
+
+#[tauri::command]
+pub fn get_note_sync_conflicts(
+    workspace_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<NoteSyncConflict>, String> {
+    let db = state.db();
+    note_sync_conflicts(&db, &workspace_id)
+}
+
+#[tauri::command]
+pub fn resolve_note_sync_conflict(
+    note_id: String,
+    keep: NoteSyncSide,
+    state: tauri::State<AppState>,
+) -> Result<Note, String> {
+    let db = state.db();
+    let config = state.config().notes.unwrap_or_default();
+
+    let note = resolve_note_file_conflict(&db, &config, &note_id, keep)?;
+    log::info!("Note sync conflict resolved: {}", note_id);
+    Ok(note)
+}
+
+#[tauri::command]
+pub fn get_note_templates(
+    workspace_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<NoteTemplate>, String> {
+    let db = state.db();
+    list_note_templates(&db, &state.config().templates_dir, &workspace_id)
+}
+
+#[tauri::command]
+pub fn create_note_template(
+    dto: CreateNoteTemplateDto,
+    state: tauri::State<AppState>,
+) -> Result<NoteTemplate, String> {
+    let db = state.db();
+
+    let note_type = NoteType::parse(&dto.note_type).unwrap_or(NoteType::Markdown);
+    let title = dto.title.unwrap_or_else(|| "{{title}}".to_string());
+    let mut template = NoteTemplate::new(
+        dto.workspace_id,
+        dto.name,
+        note_type,
+        title,
+        dto.content.unwrap_or_default(),
+    );
+    template.description = dto.description;
+    template.tags = dto.tags.unwrap_or_default();
+    template.fields = dto.fields.unwrap_or_default();
+
+    match db.create_note_template(&template) {
+        Ok(_) => {
+            log::info!("Note template created: {}", template.name);
+            Ok(template)
+        }
+        Err(e) => Err(format!("Failed to create template: {}", e)),
+    }
+}
+
+/// 只能修改工作空间模板，文件模板直接编辑 `templates_dir` 下的文件
+#[tauri::command]
+pub fn update_note_template(
+    id: String,
+    dto: UpdateNoteTemplateDto,
+    state: tauri::State<AppState>,
+) -> Result<NoteTemplate, String> {
+    let db = state.db();
+
+    let mut template = match db.get_note_template(&id) {
+        Ok(Some(template)) => template,
+        Ok(None) => return Err(format!("Template not found: {}", id)),
+        Err(e) => return Err(format!("Failed to fetch template: {}", e)),
+    };
+    if let Some(name) = dto.name {
+        template.name = name;
+    }
+    if dto.description.is_some() {
+        template.description = dto.description;
+    }
+    if let Some(note_type) = dto.note_type
+        && let Some(nt) = NoteType::parse(&note_type)
+    {
+        template.note_type = nt;
+    }
+    if let Some(title) = dto.title {
+        template.title = title;
+    }
+    if let Some(content) = dto.content {
+        template.content = content;
+    }
+    if let Some(tags) = dto.tags {
+        template.tags = tags;
+    }
+    if let Some(fields) = dto.fields {
+        template.fields = fields;
+    }
+    template.updated_at = Utc::now().timestamp_millis();
+
+    db.update_note_template(&template)
+        .map_err(|e| format!("Failed to update template: {}", e))?;
+    Ok(template)
+}
+
+#[tauri::command]
+pub fn delete_note_template(id: String, state: tauri::State<AppState>) -> Result<(), String> {
+    let db = state.db();
+    db.delete_note_template(&id)
+        .map_err(|e| format!("Failed to delete template: {}", e))
+}
+
+/// 渲染模板并创建笔记，`values` 为模板字段的取值
+#[tauri::command]
+pub fn create_note_from_template(
+    dto: CreateNoteFromTemplateDto,
+    state: tauri::State<AppState>,
+) -> Result<Note, String> {
+    let db = state.db();
+
+    let template = find_note_template(&db, &state.config().templates_dir, &dto.template_id)?;
+    if let Some(workspace_id) = &template.workspace_id
+        && workspace_id != &dto.workspace_id
+    {
+        return Err(format!(
+            "Template {} does not belong to workspace {}",
+            template.id, dto.workspace_id
+        ));
+    }
+    let workspace = db
+        .get_workspace(&dto.workspace_id)
+        .map_err(|e| format!("Failed to fetch workspace: {}", e))?
+        .ok_or_else(|| format!("Workspace not found: {}", dto.workspace_id))?;
+
+    let note = build_note_from_template(
+        &template,
+        &workspace.id,
+        &workspace.name,
+        dto.title.as_deref(),
+        &dto.values.unwrap_or_default(),
+        Local::now(),
+    );
+    match db.create_note(&note) {
+        Ok(_) => {
+            log::info!(
+                "Note created from template {}: {}",
+                template.name,
+                note.title
+            );
+            Ok(after_note_saved(&state, note, false))
+        }
+        Err(e) => Err(format!("Failed to create note: {}", e)),
+    }
+}
+
+/// 返回指定日期（`YYYY-MM-DD`，默认今天）的日记，不存在时创建
+#[tauri::command]
+pub fn get_or_create_daily_note(
+    workspace_id: String,
+    date: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<Note, String> {
+    let date = match date {
+        Some(date) => parse_day(&date)?,
+        None => Local::now().date_naive(),
+    };
+    let config = state.config();
+    let notes_config = config.notes.clone().unwrap_or_default();
+    let db = state.db();
+
+    let daily = app_service::get_or_create_daily_note(
+        &db,
+        &notes_config,
+        &config.templates_dir,
+        &workspace_id,
+        date,
+    )?;
+    if !daily.created {
+        return Ok(daily.note);
+    }
+    log::info!("Daily note created: {}", daily.note.title);
+    Ok(after_note_saved(&state, daily.note, daily.parent_created))
+}
+
+/// `start` 到 `end`（含）之间每天创建和修改的笔记数
+#[tauri::command]
+pub fn get_note_calendar(
+    workspace_id: String,
+    start: String,
+    end: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<NoteCalendarDay>, String> {
+    let db = state.db();
+    note_calendar(&db, &workspace_id, parse_day(&start)?, parse_day(&end)?)
+}
+
+#[tauri::command]
+pub fn get_notes_on_day(
+    workspace_id: String,
+    date: String,
+    state: tauri::State<AppState>,
+) -> Result<NoteDayActivity, String> {
+    let db = state.db();
+    notes_on_day(&db, &workspace_id, parse_day(&date)?)
+}
+
+/// 笔记的大纲、字数和摘要，分析结果过期时立即重新计算
+#[tauri::command]
+pub fn get_note_insight(
+    note_id: String,
+    state: tauri::State<AppState>,
+) -> Result<NoteInsight, String> {
+    let db = state.db();
+    let note = match db.get_note(&note_id) {
+        Ok(Some(note)) => note,
+        Ok(None) => return Err(format!("Note not found: {}", note_id)),
+        Err(e) => return Err(format!("Failed to fetch note: {}", e)),
+    };
+
+    match db.get_note_insight(&note_id) {
+        Ok(Some(insight)) if insight.source_updated_at == note.updated_at => Ok(insight),
+        Ok(_) => refresh_note_insight(&db, &note),
+        Err(e) => Err(format!("Failed to fetch note insight: {}", e)),
+    }
+}
@@ -1,7 +1,17 @@
-use crate::app_state::{AppState, GitRepository};
-use std::path::PathBuf;
+use crate::app_service::{
+    LocalRepositoryImport, SkippedRepository, find_git_repositories, register_local_repository,
+    search_workspace_dependencies,
+};
+use crate::app_state::{AppState, DependencyMatch, GitRepository, ProjectManifest, ProjectType};
+use std::path::{Path, PathBuf};
 
-use super::dto::{CreateRepositoryDto, UpdateRepositoryDto};
+use super::dto::{
+    CreateRepositoryDto, ImportLocalRepositoriesDto, ImportLocalRepositoryDto,
+    SearchDependenciesDto, UpdateRepositoryDto,
+};
+
+/// 扫描父目录时的默认深度
+const DEFAULT_SCAN_DEPTH: usize = 3;
 
 #[tauri::command]
 pub fn get_all_repositories(
@@ -93,3 +103,81 @@ pub fn delete_repository(id: String, state: tauri::State<AppState>) -> Result<bo
         Err(e) => Err(format!("Failed to delete repository: {}", e)),
     }
 }
+
+#[tauri::command]
+pub fn import_local_repository(
+    dto: ImportLocalRepositoryDto,
+    state: tauri::State<AppState>,
+) -> Result<GitRepository, String> {
+    let db = state.db();
+    let repo = register_local_repository(&db, &dto.workspace_id, Path::new(&dto.path))?;
+    log::info!(
+        "Local repository imported: {} ({})",
+        repo.name,
+        repo.local_path.display()
+    );
+    Ok(repo)
+}
+
+#[tauri::command]
+pub fn import_local_repositories(
+    dto: ImportLocalRepositoriesDto,
+    state: tauri::State<AppState>,
+) -> Result<LocalRepositoryImport, String> {
+    let db = state.db();
+    let root = PathBuf::from(&dto.root_path);
+    if !root.is_dir() {
+        return Err(format!("Directory not found: {}", dto.root_path));
+    }
+
+    let mut result = LocalRepositoryImport::default();
+    for path in find_git_repositories(&root, dto.max_depth.unwrap_or(DEFAULT_SCAN_DEPTH)) {
+        match register_local_repository(&db, &dto.workspace_id, &path) {
+            Ok(repo) => result.imported.push(repo),
+            Err(reason) => result.skipped.push(SkippedRepository { path, reason }),
+        }
+    }
+
+    log::info!(
+        "Local repositories imported from {}: {} imported, {} skipped",
+        dto.root_path,
+        result.imported.len(),
+        result.skipped.len()
+    );
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn get_repository_manifests(
+    repository_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<ProjectManifest>, String> {
+    let db = state.db();
+
+    match db.list_project_manifests(&repository_id) {
+        Ok(manifests) => Ok(manifests),
+        Err(e) => Err(format!("Failed to get manifests: {}", e)),
+    }
+}
+
+#[tauri::command]
+pub fn search_dependencies(
+    dto: SearchDependenciesDto,
+    state: tauri::State<AppState>,
+) -> Result<Vec<DependencyMatch>, String> {
+    let db = state.db();
+    let project_type = match dto.project_type.as_deref() {
+        Some(value) => Some(
+            ProjectType::parse(value).ok_or_else(|| format!("Unknown project type: {}", value))?,
+        ),
+        None => None,
+    };
+
+    search_workspace_dependencies(
+        &db,
+        &dto.workspace_id,
+        &dto.name,
+        dto.version.as_deref(),
+        project_type,
+    )
+}
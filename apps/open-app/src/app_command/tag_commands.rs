@@ -0,0 +1,158 @@
+//! 标签相关的 Tauri 命令
+//!
+//! 笔记和网页链接的标签随实体一起保存，这里提供跨类型的标签列表、改名、合并、删除，
+//! 以及导入文件的标签设置。
+
+use crate::app_service::{notebook_root, sync_workspace_note_files};
+use crate::app_state::{
+    AppState, Tag, TagEntityType, TagUsage, TaggedEntity, is_tag_or_descendant, normalize_tag_name,
+};
+
+/// 标签变更会改写笔记的 tags，开启 Markdown 同步时重新导出 front matter
+fn sync_tagged_note_files(state: &AppState, workspace_id: &str) {
+    let config = state.config();
+    let notes_config = config.notes.unwrap_or_default();
+    if !notes_config.markdown_sync {
+        return;
+    }
+    let db = state.db();
+    if let Err(e) = notebook_root(&config.notebook_dir)
+        .and_then(|root| sync_workspace_note_files(&db, &root, &notes_config, workspace_id))
+    {
+        log::warn!("{}", e);
+    }
+}
+
+fn find_tag(state: &AppState, id: &str) -> Result<Tag, String> {
+    match state.db().get_tag(id) {
+        Ok(Some(tag)) => Ok(tag),
+        Ok(None) => Err(format!("Tag not found: {}", id)),
+        Err(e) => Err(format!("Failed to fetch tag: {}", e)),
+    }
+}
+
+#[tauri::command]
+pub fn get_tags(
+    workspace_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<TagUsage>, String> {
+    let db = state.db();
+    db.list_tags(&workspace_id)
+        .map_err(|e| format!("Failed to fetch tags: {}", e))
+}
+
+#[tauri::command]
+pub fn get_entity_tags(
+    entity_type: String,
+    entity_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<Tag>, String> {
+    let entity_type = TagEntityType::parse(&entity_type)
+        .ok_or_else(|| format!("Invalid entity type: {}", entity_type))?;
+    let db = state.db();
+    db.get_entity_tags(&entity_type, &entity_id)
+        .map_err(|e| format!("Failed to fetch tags: {}", e))
+}
+
+/// 设置导入文件的标签，返回规范化后的标签名
+#[tauri::command]
+pub fn set_file_tags(
+    file_id: String,
+    tags: Vec<String>,
+    state: tauri::State<AppState>,
+) -> Result<Vec<String>, String> {
+    let db = state.db();
+    let file = db
+        .get_imported_file(&file_id)
+        .map_err(|e| format!("Failed to fetch file: {}", e))?
+        .ok_or_else(|| format!("File not found: {}", file_id))?;
+    db.set_entity_tags(&TagEntityType::File, &file.id, &file.workspace_id, &tags)
+        .map_err(|e| format!("Failed to set file tags: {}", e))
+}
+
+/// 改名标签，子标签随之改名；新名称已存在时合并
+#[tauri::command]
+pub fn rename_tag(id: String, name: String, state: tauri::State<AppState>) -> Result<Tag, String> {
+    let tag = find_tag(&state, &id)?;
+    let name = normalize_tag_name(&name).ok_or_else(|| "Tag name is empty".to_string())?;
+    if is_tag_or_descendant(&name, &tag.name) && !name.eq_ignore_ascii_case(&tag.name) {
+        return Err(format!(
+            "Cannot move tag {} under itself: {}",
+            tag.name, name
+        ));
+    }
+
+    let db = state.db();
+    let renamed = db
+        .rename_tag(&id, &name)
+        .map_err(|e| format!("Failed to rename tag: {}", e))?
+        .ok_or_else(|| format!("Tag not found: {}", id))?;
+    log::info!("Tag renamed: {} -> {}", tag.name, renamed.name);
+    sync_tagged_note_files(&state, &tag.workspace_id);
+    Ok(renamed)
+}
+
+/// 将 `source_id` 合并到 `target_id`，来源标签的子标签移到目标标签下
+#[tauri::command]
+pub fn merge_tags(
+    source_id: String,
+    target_id: String,
+    state: tauri::State<AppState>,
+) -> Result<Tag, String> {
+    let source = find_tag(&state, &source_id)?;
+    let target = find_tag(&state, &target_id)?;
+    if source.workspace_id != target.workspace_id {
+        return Err("Cannot merge tags from different workspaces".to_string());
+    }
+    if is_tag_or_descendant(&target.name, &source.name) {
+        return Err(format!(
+            "Cannot merge tag {} into its own child {}",
+            source.name, target.name
+        ));
+    }
+
+    let db = state.db();
+    let merged = db
+        .rename_tag(&source_id, &target.name)
+        .map_err(|e| format!("Failed to merge tags: {}", e))?
+        .ok_or_else(|| format!("Tag not found: {}", source_id))?;
+    log::info!("Tag merged: {} -> {}", source.name, merged.name);
+    sync_tagged_note_files(&state, &source.workspace_id);
+    Ok(merged)
+}
+
+/// 删除标签及其子标签，并从所有实体上移除
+#[tauri::command]
+pub fn delete_tag(id: String, state: tauri::State<AppState>) -> Result<(), String> {
+    let tag = find_tag(&state, &id)?;
+    let db = state.db();
+    db.delete_tag(&id)
+        .map_err(|e| format!("Failed to delete tag: {}", e))?;
+    log::info!("Tag deleted: {}", tag.name);
+    sync_tagged_note_files(&state, &tag.workspace_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_tag_color(
+    id: String,
+    color: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<Tag, String> {
+    let db = state.db();
+    db.set_tag_color(&id, color.as_deref())
+        .map_err(|e| format!("Failed to update tag: {}", e))?;
+    find_tag(&state, &id)
+}
+
+/// 使用标签的笔记、链接和文件，`include_descendants` 为 true 时包含子标签
+#[tauri::command]
+pub fn get_tagged_entities(
+    tag_id: String,
+    include_descendants: Option<bool>,
+    state: tauri::State<AppState>,
+) -> Result<Vec<TaggedEntity>, String> {
+    let db = state.db();
+    db.list_tagged_entities(&tag_id, include_descendants.unwrap_or(true))
+        .map_err(|e| format!("Failed to fetch tagged entities: {}", e))
+}
@@ -1,7 +1,24 @@
-use crate::app_service::{TaskHandle, TaskInfo, TaskManager, TaskStatus};
+use crate::app_service::{
+    CloneOptions, ClonedSubmodule, NoteExportFormat, RepositoryPurgeReport, SyncState, TaskHandle,
+    TaskInfo, TaskManager, TaskStatus, clone_repository, detect_manifests, dir_size, export_notes,
+    import_markdown_vault, import_notion_export, is_managed_clone, notebook_root,
+    purge_repository_full_text, purge_repository_vectors, purge_symbol_stores, remove_dir_tracked,
+    repository_name_from_url, sync_git_repository, sync_workspace_note_files, update_submodules,
+};
+use crate::app_state::{
+    AppState, CloneStatus, DatabaseManager, GitRepository, IndexJob, IndexJobStatus, IndexJobType,
+    IndexStatus, select_git_credential,
+};
+use chrono::Utc;
+use std::path::PathBuf;
+use std::sync::Arc;
 use tauri::Emitter;
 
-use super::dto::{CloneRepositoryTaskDto, ImportFilesTaskDto, IndexRepositoryTaskDto};
+use super::dto::{
+    CloneRepositoryTaskDto, DeleteRepositoryTaskDto, ExportNotesTaskDto, ImportFilesTaskDto,
+    ImportMarkdownVaultTaskDto, ImportNotionExportTaskDto, IndexRepositoryTaskDto,
+    SyncWorkspaceRepositoriesTaskDto,
+};
 
 #[tauri::command]
 pub fn get_task(
@@ -50,8 +67,37 @@ pub fn cleanup_tasks(
 pub async fn clone_repository_task(
     app: tauri::AppHandle,
     dto: CloneRepositoryTaskDto,
+    state: tauri::State<'_, AppState>,
     task_manager: tauri::State<'_, TaskManager>,
 ) -> Result<TaskHandle, String> {
+    let db = state.db();
+    let config = state.config();
+
+    let name = repository_name_from_url(&dto.url);
+    let local_path = PathBuf::from(&config.workspace_dir)
+        .join(&dto.workspace_id)
+        .join(&name);
+    if local_path.exists()
+        && std::fs::read_dir(&local_path).is_ok_and(|mut entries| entries.next().is_some())
+    {
+        return Err(format!(
+            "Target directory already exists: {}",
+            local_path.display()
+        ));
+    }
+
+    // 未指定分支时使用远程默认分支，克隆完成后回写
+    let mut repo = GitRepository::new(
+        dto.workspace_id.clone(),
+        name,
+        dto.url.clone(),
+        local_path.clone(),
+        dto.branch.clone().unwrap_or_default(),
+    );
+    repo.default_branch = None;
+    db.create_git_repository(&repo)
+        .map_err(|e| format!("Failed to create repository: {}", e))?;
+
     let task = task_manager.create_task("clone_repository");
     let task_id = task.id.clone();
     let task_type = task.task_type.clone();
@@ -64,34 +110,95 @@ pub async fn clone_repository_task(
 
     let manager = task_manager.inner().clone();
     let url = dto.url.clone();
-    let branch = dto.branch.clone().unwrap_or_else(|| "main".to_string());
+    let options = CloneOptions {
+        branch: dto.branch.clone(),
+        depth: dto.depth,
+        single_branch: dto.single_branch.unwrap_or(false),
+        sparse_paths: dto.sparse_paths.clone().unwrap_or_default(),
+    };
+    let recursive_submodules = dto.recursive_submodules.unwrap_or(false);
 
     tauri::async_runtime::spawn(async move {
         manager.set_running(&task_id);
         log::info!("Starting clone repository task: {} -> {}", task_id, url);
 
-        manager.update_progress(&task_id, 10, Some("Preparing to clone...".to_string()));
+        manager.update_progress(&task_id, 5, Some("Preparing to clone...".to_string()));
+        let _ = db.update_git_repository_clone_status(&repo.id, CloneStatus::Cloning, 0);
 
-        let result = async {
-            manager.update_progress(&task_id, 30, Some("Cloning repository...".to_string()));
-            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let credentials = db.list_git_credentials().unwrap_or_default();
+        let clone_manager = manager.clone();
+        let clone_task_id = task_id.clone();
+        let clone_url = url.clone();
+        let clone_path = local_path.clone();
 
-            manager.update_progress(&task_id, 60, Some("Processing files...".to_string()));
-            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            let mut last_progress = 0;
+            let cloned = clone_repository(
+                &clone_url,
+                &clone_path,
+                &options,
+                select_git_credential(&credentials, &clone_url).cloned(),
+                |received, total| {
+                    let progress = (5 + (received * 80).checked_div(total).unwrap_or(0)) as u8;
+                    if progress != last_progress {
+                        last_progress = progress;
+                        let msg = format!("Receiving objects ({}/{})", received, total);
+                        clone_manager.update_progress(&clone_task_id, progress, Some(msg));
+                    }
+                    !clone_manager.is_cancelled(&clone_task_id)
+                },
+            )?;
 
-            manager.update_progress(&task_id, 90, Some("Finalizing...".to_string()));
+            let submodules = if recursive_submodules {
+                update_submodules(&cloned, &credentials, options.depth, &mut |name: &str| {
+                    let msg = format!("Updating submodule {}", name);
+                    clone_manager.update_progress(&clone_task_id, 88, Some(msg));
+                    !clone_manager.is_cancelled(&clone_task_id)
+                })?
+            } else {
+                Vec::new()
+            };
 
-            Ok::<serde_json::Value, String>(serde_json::json!({
-                "url": url,
-                "branch": branch,
-                "status": "cloned"
-            }))
-        }
-        .await;
+            let head = cloned.head().ok();
+            let branch = head
+                .as_ref()
+                .and_then(|h| h.shorthand().map(str::to_string))
+                .unwrap_or_default();
+            let commit = head.and_then(|h| h.target()).map(|oid| oid.to_string());
+            Ok::<_, String>((branch, commit, submodules))
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
 
         match result {
-            Ok(value) => {
-                manager.complete(&task_id, Some(value));
+            Ok((branch, commit, submodules)) => {
+                manager.update_progress(&task_id, 95, Some("Finalizing...".to_string()));
+                let submodule_ids = register_submodules(&db, &repo, &submodules);
+
+                if repo.branch.is_empty() {
+                    repo.branch = branch.clone();
+                }
+                repo.default_branch = Some(branch);
+                let _ = db.update_git_repository(&repo);
+                if let Some(commit) = &commit {
+                    let _ = db.update_git_repository_sync(&repo.id, commit);
+                }
+                let _ =
+                    db.update_git_repository_clone_status(&repo.id, CloneStatus::Completed, 100);
+
+                manager.complete(
+                    &task_id,
+                    Some(serde_json::json!({
+                        "repositoryId": repo.id,
+                        "url": url,
+                        "branch": repo.branch,
+                        "localPath": local_path,
+                        "commit": commit,
+                        "submoduleIds": submodule_ids,
+                        "status": "cloned"
+                    })),
+                );
                 log::info!("Clone repository task completed: {}", task_id);
                 let _ = app.emit(
                     "task:completed",
@@ -102,6 +209,17 @@ pub async fn clone_repository_task(
                 );
             }
             Err(e) => {
+                // 清理未完成的克隆目录和仓库记录，重试时重新登记
+                let _ = std::fs::remove_dir_all(&local_path);
+                if let Err(e) = db.delete_git_repository(&repo.id) {
+                    log::warn!("Failed to delete repository {}: {}", repo.id, e);
+                }
+
+                if manager.is_cancelled(&task_id) {
+                    log::info!("Clone repository task cancelled: {}", task_id);
+                    return;
+                }
+
                 manager.fail(&task_id, &e);
                 log::error!("Clone repository task failed: {} - {}", task_id, e);
                 let _ = app.emit(
@@ -119,13 +237,74 @@ pub async fn clone_repository_task(
     Ok(handle)
 }
 
+/// 将克隆得到的子模块登记为子仓库，返回新建的仓库 ID
+fn register_submodules(
+    db: &DatabaseManager,
+    parent: &GitRepository,
+    submodules: &[ClonedSubmodule],
+) -> Vec<String> {
+    let mut ids: Vec<(PathBuf, String)> = vec![(parent.local_path.clone(), parent.id.clone())];
+
+    for submodule in submodules {
+        let parent_id = ids
+            .iter()
+            .find(|(path, _)| *path == submodule.parent_path)
+            .map(|(_, id)| id.clone())
+            .unwrap_or_else(|| parent.id.clone());
+
+        let mut child = GitRepository::new(
+            parent.workspace_id.clone(),
+            submodule.name.clone(),
+            submodule.url.clone(),
+            submodule.path.clone(),
+            submodule
+                .branch
+                .clone()
+                .unwrap_or_else(|| "HEAD".to_string()),
+        );
+        child.default_branch = submodule.branch.clone();
+        child.parent_id = Some(parent_id);
+        child.last_commit_hash = submodule.head.clone();
+        child.clone_status = CloneStatus::Completed;
+        child.clone_progress = 100;
+
+        match db.create_git_repository(&child) {
+            Ok(()) => ids.push((child.local_path.clone(), child.id.clone())),
+            Err(e) => log::warn!("Failed to register submodule {}: {}", submodule.name, e),
+        }
+    }
+
+    ids.into_iter().skip(1).map(|(_, id)| id).collect()
+}
+
 #[tauri::command]
 pub async fn index_repository_task(
     app: tauri::AppHandle,
     dto: IndexRepositoryTaskDto,
+    state: tauri::State<'_, AppState>,
     task_manager: tauri::State<'_, TaskManager>,
 ) -> Result<TaskHandle, String> {
-    let task = task_manager.create_task("index_repository");
+    let job_type = dto.job_type.unwrap_or(IndexJobType::Full);
+    Ok(spawn_index_task(
+        app,
+        task_manager.inner().clone(),
+        state.db(),
+        dto.repository_id,
+        job_type,
+    ))
+}
+
+/// 启动仓库索引任务，同时记录 IndexJob 与仓库索引状态
+///
+/// 同步或切换分支后 HEAD 变化时也会调用，以增量方式重新索引。
+pub(super) fn spawn_index_task(
+    app: tauri::AppHandle,
+    manager: TaskManager,
+    db: Arc<DatabaseManager>,
+    repo_id: String,
+    job_type: IndexJobType,
+) -> TaskHandle {
+    let task = manager.create_task("index_repository");
     let task_id = task.id.clone();
     let task_type = task.task_type.clone();
 
@@ -135,19 +314,41 @@ pub async fn index_repository_task(
         status: TaskStatus::Pending,
     };
 
-    let manager = task_manager.inner().clone();
-    let repo_id = dto.repository_id.clone();
-
     tauri::async_runtime::spawn(async move {
         manager.set_running(&task_id);
         log::info!(
-            "Starting index repository task: {} for repo {}",
+            "Starting {} index repository task: {} for repo {}",
+            job_type.as_str(),
             task_id,
             repo_id
         );
 
+        let mut job = IndexJob::new(repo_id.clone(), job_type.clone());
+        job.status = IndexJobStatus::Running;
+        job.started_at = Some(Utc::now().timestamp_millis());
+        let _ = db.create_index_job(&job);
+        let _ = db.update_git_repository_index_status(&repo_id, IndexStatus::Indexing, None);
+
         manager.update_progress(&task_id, 10, Some("Scanning files...".to_string()));
-        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let manifest_count = match db.get_git_repository(&repo_id) {
+            Ok(Some(repo)) => {
+                let db = db.clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    let manifests = detect_manifests(&repo.local_path)?;
+                    db.replace_project_manifests(&repo.id, &repo.workspace_id, &manifests)
+                        .map_err(|e| format!("Failed to save manifests: {}", e))?;
+                    Ok::<_, String>(manifests.len())
+                })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|r| r)
+                .unwrap_or_else(|e| {
+                    log::warn!("Manifest detection failed for repo {}: {}", repo_id, e);
+                    0
+                })
+            }
+            _ => 0,
+        };
 
         manager.update_progress(&task_id, 30, Some("Parsing AST...".to_string()));
         tokio::time::sleep(std::time::Duration::from_millis(100)).await;
@@ -158,10 +359,20 @@ pub async fn index_repository_task(
         manager.update_progress(&task_id, 80, Some("Generating embeddings...".to_string()));
         tokio::time::sleep(std::time::Duration::from_millis(100)).await;
 
+        let now = Utc::now().timestamp_millis();
+        job.status = IndexJobStatus::Completed;
+        job.progress = 100;
+        job.completed_at = Some(now);
+        let _ = db.update_index_job(&job);
+        let _ = db.update_git_repository_index_status(&repo_id, IndexStatus::Indexed, Some(now));
+
         manager.complete(
             &task_id,
             Some(serde_json::json!({
                 "repositoryId": repo_id,
+                "jobId": job.id,
+                "jobType": job_type,
+                "manifestCount": manifest_count,
                 "status": "indexed"
             })),
         );
@@ -176,6 +387,319 @@ pub async fn index_repository_task(
         );
     });
 
+    handle
+}
+
+#[tauri::command]
+pub async fn sync_workspace_repositories_task(
+    app: tauri::AppHandle,
+    dto: SyncWorkspaceRepositoriesTaskDto,
+    state: tauri::State<'_, AppState>,
+    task_manager: tauri::State<'_, TaskManager>,
+) -> Result<TaskHandle, String> {
+    let db = state.db();
+    let repos: Vec<_> = db
+        .list_git_repositories(&dto.workspace_id)
+        .map_err(|e| format!("Failed to fetch repositories: {}", e))?
+        .into_iter()
+        // 子模块固定在父仓库记录的提交上，不单独同步
+        .filter(|repo| {
+            repo.clone_status == CloneStatus::Completed
+                && !repo.is_archived
+                && repo.parent_id.is_none()
+        })
+        .collect();
+
+    let task = task_manager.create_task("sync_workspace_repositories");
+    let task_id = task.id.clone();
+    let task_type = task.task_type.clone();
+
+    let handle = TaskHandle {
+        task_id: task_id.clone(),
+        task_type: task_type.clone(),
+        status: TaskStatus::Pending,
+    };
+
+    let manager = task_manager.inner().clone();
+    let total = repos.len();
+
+    tauri::async_runtime::spawn(async move {
+        manager.set_running(&task_id);
+        log::info!(
+            "Starting sync workspace repositories task: {} with {} repositories",
+            task_id,
+            total
+        );
+
+        let mut results = Vec::new();
+        let mut diverged = 0;
+        let mut failed = 0;
+
+        for (i, repo) in repos.into_iter().enumerate() {
+            if manager.is_cancelled(&task_id) {
+                log::info!("Sync workspace repositories task cancelled: {}", task_id);
+                return;
+            }
+
+            let progress = (i * 100 / total.max(1)) as u8;
+            let msg = format!("Syncing {} ({}/{})", repo.name, i + 1, total);
+            manager.update_progress(&task_id, progress, Some(msg));
+
+            let sync_db = db.clone();
+            let sync_repo = repo.clone();
+            let outcome = tauri::async_runtime::spawn_blocking(move || {
+                sync_git_repository(&sync_db, &sync_repo)
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|r| r);
+
+            match outcome {
+                Ok(report) => {
+                    if report.state == SyncState::Diverged {
+                        diverged += 1;
+                    }
+                    let index_task = report.head_moved.then(|| {
+                        spawn_index_task(
+                            app.clone(),
+                            manager.clone(),
+                            db.clone(),
+                            repo.id.clone(),
+                            IndexJobType::Incremental,
+                        )
+                    });
+                    results.push(serde_json::json!({
+                        "repositoryId": repo.id,
+                        "name": repo.name,
+                        "report": report,
+                        "indexTaskId": index_task.map(|h| h.task_id),
+                    }));
+                }
+                Err(e) => {
+                    failed += 1;
+                    log::warn!("Failed to sync repository {}: {}", repo.name, e);
+                    results.push(serde_json::json!({
+                        "repositoryId": repo.id,
+                        "name": repo.name,
+                        "error": e,
+                    }));
+                }
+            }
+        }
+
+        manager.complete(
+            &task_id,
+            Some(serde_json::json!({
+                "synced": total - failed,
+                "diverged": diverged,
+                "failed": failed,
+                "results": results
+            })),
+        );
+
+        log::info!("Sync workspace repositories task completed: {}", task_id);
+        let _ = app.emit(
+            "task:completed",
+            serde_json::json!({
+                "taskId": task_id,
+                "taskType": task_type
+            }),
+        );
+    });
+
+    Ok(handle)
+}
+
+/// 删除仓库记录并清理索引数据，可选删除本地克隆目录
+///
+/// 删除目录的过程可以取消；取消或失败时保留仓库记录并将克隆状态标记为失败。
+#[tauri::command]
+pub async fn delete_repository_task(
+    app: tauri::AppHandle,
+    dto: DeleteRepositoryTaskDto,
+    state: tauri::State<'_, AppState>,
+    task_manager: tauri::State<'_, TaskManager>,
+) -> Result<TaskHandle, String> {
+    let db = state.db();
+    let config = state.config();
+    let repo = db
+        .get_git_repository(&dto.repository_id)
+        .map_err(|e| format!("Failed to fetch repository: {}", e))?
+        .ok_or_else(|| format!("Repository not found: {}", dto.repository_id))?;
+
+    // 子模块记录随父仓库级联删除，但索引数据需要逐个清理
+    let mut submodules = Vec::new();
+    let mut pending = vec![repo.id.clone()];
+    while let Some(parent_id) = pending.pop() {
+        let children = db
+            .list_child_repositories(&parent_id)
+            .map_err(|e| format!("Failed to fetch repositories: {}", e))?;
+        pending.extend(children.iter().map(|child| child.id.clone()));
+        submodules.extend(children);
+    }
+
+    let mut report = RepositoryPurgeReport {
+        repository_id: repo.id.clone(),
+        submodule_ids: submodules.iter().map(|child| child.id.clone()).collect(),
+        ..Default::default()
+    };
+    let remove_path = if dto.delete_local_clone.unwrap_or(false) && repo.local_path.exists() {
+        if is_managed_clone(&config, &repo.local_path) {
+            Some(repo.local_path.clone())
+        } else {
+            report.warnings.push(format!(
+                "Local path is outside the workspace directory and was kept: {}",
+                repo.local_path.display()
+            ));
+            None
+        }
+    } else {
+        None
+    };
+    let database = config.database.clone().unwrap_or_default();
+    let (qdrant, surrealdb) = (database.qdrant, database.surrealdb);
+
+    let task = task_manager.create_task("delete_repository");
+    let task_id = task.id.clone();
+    let task_type = task.task_type.clone();
+
+    let handle = TaskHandle {
+        task_id: task_id.clone(),
+        task_type: task_type.clone(),
+        status: TaskStatus::Pending,
+    };
+
+    let manager = task_manager.inner().clone();
+
+    tauri::async_runtime::spawn(async move {
+        manager.set_running(&task_id);
+        log::info!("Starting delete repository task: {} ({})", task_id, repo.id);
+
+        if let Some(path) = remove_path {
+            manager.update_progress(&task_id, 5, Some("Measuring local clone...".to_string()));
+            let remove_manager = manager.clone();
+            let remove_task_id = task_id.clone();
+
+            let result = tauri::async_runtime::spawn_blocking(move || {
+                let total = dir_size(&path).max(1);
+                let mut last_progress = 0;
+                remove_dir_tracked(&path, |freed| {
+                    let progress = (10 + freed * 70 / total) as u8;
+                    if progress != last_progress {
+                        last_progress = progress;
+                        let msg = format!("Deleting local clone ({} MB freed)", freed >> 20);
+                        remove_manager.update_progress(&remove_task_id, progress, Some(msg));
+                    }
+                    !remove_manager.is_cancelled(&remove_task_id)
+                })
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|r| r);
+
+            match result {
+                Ok((freed, files)) => {
+                    report.local_path_removed = true;
+                    report.freed_bytes = freed;
+                    report.files_removed = files;
+                }
+                Err(e) => {
+                    // 本地克隆已不完整
+                    let _ = db.update_git_repository_clone_status(&repo.id, CloneStatus::Failed, 0);
+
+                    if manager.is_cancelled(&task_id) {
+                        log::info!("Delete repository task cancelled: {}", task_id);
+                        return;
+                    }
+
+                    manager.fail(&task_id, &e);
+                    log::error!("Delete repository task failed: {} - {}", task_id, e);
+                    let _ = app.emit(
+                        "task:failed",
+                        serde_json::json!({
+                            "taskId": task_id,
+                            "taskType": task_type,
+                            "error": e
+                        }),
+                    );
+                    return;
+                }
+            }
+        }
+
+        manager.update_progress(&task_id, 85, Some("Purging index data...".to_string()));
+        let targets: Vec<GitRepository> = std::iter::once(repo.clone()).chain(submodules).collect();
+
+        let purge_targets = targets.clone();
+        let purged = tauri::async_runtime::spawn_blocking(move || {
+            purge_targets
+                .iter()
+                .map(|target| purge_symbol_stores(&target.workspace_id, &target.id))
+                .collect::<Vec<_>>()
+        })
+        .await
+        .unwrap_or_default();
+        for result in purged {
+            match result {
+                Ok((symbols, edges)) => {
+                    report.symbols_removed += symbols;
+                    report.edges_removed += edges;
+                }
+                Err(e) => report.warnings.push(e),
+            }
+        }
+
+        report.full_text_purged = true;
+        for target in &targets {
+            if let Err(e) = purge_repository_full_text(&surrealdb, &target.id).await {
+                report.full_text_purged = false;
+                report.warnings.push(e);
+                break;
+            }
+        }
+
+        report.vectors_purged = true;
+        for target in &targets {
+            if let Err(e) = purge_repository_vectors(&qdrant, &target.id).await {
+                report.vectors_purged = false;
+                report.warnings.push(e);
+                break;
+            }
+        }
+
+        if let Err(e) = db.delete_git_repository(&repo.id) {
+            let e = format!("Failed to delete repository: {}", e);
+            manager.fail(&task_id, &e);
+            log::error!("Delete repository task failed: {} - {}", task_id, e);
+            let _ = app.emit(
+                "task:failed",
+                serde_json::json!({
+                    "taskId": task_id,
+                    "taskType": task_type,
+                    "error": e
+                }),
+            );
+            return;
+        }
+
+        for warning in &report.warnings {
+            log::warn!("Delete repository {}: {}", repo.id, warning);
+        }
+        manager.complete(&task_id, serde_json::to_value(&report).ok());
+        log::info!(
+            "Delete repository task completed: {} ({} bytes freed)",
+            task_id,
+            report.freed_bytes
+        );
+        let _ = app.emit(
+            "task:completed",
+            serde_json::json!({
+                "taskId": task_id,
+                "taskType": task_type
+            }),
+        );
+    });
+
     Ok(handle)
 }
 
@@ -234,3 +758,338 @@ pub async fn import_files_task(
 
     Ok(handle)
 }
+
+/// 导入 Obsidian 仓库或 Markdown 目录，重复导入同一目录会更新已导入的笔记
+#[tauri::command]
+pub async fn import_markdown_vault_task(
+    app: tauri::AppHandle,
+    dto: ImportMarkdownVaultTaskDto,
+    state: tauri::State<'_, AppState>,
+    task_manager: tauri::State<'_, TaskManager>,
+) -> Result<TaskHandle, String> {
+    let db = state.db();
+    let config = state.config();
+    let vault = PathBuf::from(&dto.vault_path);
+    if !vault.is_dir() {
+        return Err(format!("Vault directory not found: {}", dto.vault_path));
+    }
+
+    let task = task_manager.create_task("import_markdown_vault");
+    let task_id = task.id.clone();
+    let task_type = task.task_type.clone();
+
+    let handle = TaskHandle {
+        task_id: task_id.clone(),
+        task_type: task_type.clone(),
+        status: TaskStatus::Pending,
+    };
+
+    let manager = task_manager.inner().clone();
+    let workspace_id = dto.workspace_id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        manager.set_running(&task_id);
+        log::info!(
+            "Starting import markdown vault task: {} from {}",
+            task_id,
+            vault.display()
+        );
+
+        let import_manager = manager.clone();
+        let import_task_id = task_id.clone();
+        let import_db = db.clone();
+        let import_workspace_id = workspace_id.clone();
+        let notes_config = config.notes.clone().unwrap_or_default();
+        let files_dir = PathBuf::from(&config.files_dir);
+        let import_vault = vault.clone();
+
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            import_markdown_vault(
+                &import_db,
+                &notes_config,
+                &files_dir,
+                &import_workspace_id,
+                &import_vault,
+                |done, total, path| {
+                    let progress = (5 + (done * 90).checked_div(total).unwrap_or(0)) as u8;
+                    let name = std::path::Path::new(path)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    import_manager.update_progress(
+                        &import_task_id,
+                        progress,
+                        Some(format!("Importing {} ({}/{})", name, done, total)),
+                    );
+                },
+            )
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+
+        match result {
+            Ok(report) => {
+                let notes_config = config.notes.clone().unwrap_or_default();
+                if notes_config.markdown_sync {
+                    manager.update_progress(
+                        &task_id,
+                        95,
+                        Some("Syncing note files...".to_string()),
+                    );
+                    if let Err(e) = notebook_root(&config.notebook_dir).and_then(|root| {
+                        sync_workspace_note_files(&db, &root, &notes_config, &workspace_id)
+                    }) {
+                        log::warn!("{}", e);
+                    }
+                }
+
+                manager.complete(
+                    &task_id,
+                    Some(serde_json::json!({
+                        "workspaceId": workspace_id,
+                        "vaultPath": vault,
+                        "report": report,
+                        "status": "imported"
+                    })),
+                );
+                log::info!("Import markdown vault task completed: {}", task_id);
+                let _ = app.emit(
+                    "task:completed",
+                    serde_json::json!({
+                        "taskId": task_id,
+                        "taskType": task_type
+                    }),
+                );
+            }
+            Err(e) => {
+                manager.fail(&task_id, &e);
+                log::error!("Import markdown vault task failed: {} - {}", task_id, e);
+                let _ = app.emit(
+                    "task:failed",
+                    serde_json::json!({
+                        "taskId": task_id,
+                        "taskType": task_type,
+                        "error": e
+                    }),
+                );
+            }
+        }
+    });
+
+    Ok(handle)
+}
+
+/// 导入 Notion 导出的 Markdown & CSV 压缩包，重复导入同一工作区会更新已导入的页面
+#[tauri::command]
+pub async fn import_notion_export_task(
+    app: tauri::AppHandle,
+    dto: ImportNotionExportTaskDto,
+    state: tauri::State<'_, AppState>,
+    task_manager: tauri::State<'_, TaskManager>,
+) -> Result<TaskHandle, String> {
+    let db = state.db();
+    let config = state.config();
+    let zip_path = PathBuf::from(&dto.zip_path);
+    if !zip_path.is_file() {
+        return Err(format!("Notion export not found: {}", dto.zip_path));
+    }
+
+    let task = task_manager.create_task("import_notion_export");
+    let task_id = task.id.clone();
+    let task_type = task.task_type.clone();
+
+    let handle = TaskHandle {
+        task_id: task_id.clone(),
+        task_type: task_type.clone(),
+        status: TaskStatus::Pending,
+    };
+
+    let manager = task_manager.inner().clone();
+    let workspace_id = dto.workspace_id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        manager.set_running(&task_id);
+        log::info!(
+            "Starting import notion export task: {} from {}",
+            task_id,
+            zip_path.display()
+        );
+
+        let import_manager = manager.clone();
+        let import_task_id = task_id.clone();
+        let import_db = db.clone();
+        let import_workspace_id = workspace_id.clone();
+        let notes_config = config.notes.clone().unwrap_or_default();
+        let files_dir = PathBuf::from(&config.files_dir);
+        let cache_dir = PathBuf::from(&config.cache_dir);
+        let import_zip_path = zip_path.clone();
+
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            import_notion_export(
+                &import_db,
+                &notes_config,
+                &files_dir,
+                &cache_dir,
+                &import_workspace_id,
+                &import_zip_path,
+                |done, total, title| {
+                    let progress = (5 + (done * 90).checked_div(total).unwrap_or(0)) as u8;
+                    import_manager.update_progress(
+                        &import_task_id,
+                        progress,
+                        Some(format!("Importing {} ({}/{})", title, done, total)),
+                    );
+                },
+            )
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+
+        match result {
+            Ok(report) => {
+                let notes_config = config.notes.clone().unwrap_or_default();
+                if notes_config.markdown_sync {
+                    manager.update_progress(
+                        &task_id,
+                        95,
+                        Some("Syncing note files...".to_string()),
+                    );
+                    if let Err(e) = notebook_root(&config.notebook_dir).and_then(|root| {
+                        sync_workspace_note_files(&db, &root, &notes_config, &workspace_id)
+                    }) {
+                        log::warn!("{}", e);
+                    }
+                }
+
+                manager.complete(
+                    &task_id,
+                    Some(serde_json::json!({
+                        "workspaceId": workspace_id,
+                        "zipPath": zip_path,
+                        "report": report,
+                        "status": "imported"
+                    })),
+                );
+                log::info!("Import notion export task completed: {}", task_id);
+                let _ = app.emit(
+                    "task:completed",
+                    serde_json::json!({
+                        "taskId": task_id,
+                        "taskType": task_type
+                    }),
+                );
+            }
+            Err(e) => {
+                manager.fail(&task_id, &e);
+                log::error!("Import notion export task failed: {} - {}", task_id, e);
+                let _ = app.emit(
+                    "task:failed",
+                    serde_json::json!({
+                        "taskId": task_id,
+                        "taskType": task_type,
+                        "error": e
+                    }),
+                );
+            }
+        }
+    });
+
+    Ok(handle)
+}
+
+/// 将工作区或笔记子树导出为 Markdown 或 HTML 压缩包
+#[tauri::command]
+pub async fn export_notes_task(
+    app: tauri::AppHandle,
+    dto: ExportNotesTaskDto,
+    state: tauri::State<'_, AppState>,
+    task_manager: tauri::State<'_, TaskManager>,
+) -> Result<TaskHandle, String> {
+    let db = state.db();
+    let format = NoteExportFormat::parse(&dto.format)
+        .ok_or_else(|| format!("Unsupported export format: {}", dto.format))?;
+    let output = PathBuf::from(&dto.output_path);
+
+    let task = task_manager.create_task("export_notes");
+    let task_id = task.id.clone();
+    let task_type = task.task_type.clone();
+
+    let handle = TaskHandle {
+        task_id: task_id.clone(),
+        task_type: task_type.clone(),
+        status: TaskStatus::Pending,
+    };
+
+    let manager = task_manager.inner().clone();
+    let workspace_id = dto.workspace_id.clone();
+    let note_id = dto.note_id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        manager.set_running(&task_id);
+        log::info!(
+            "Starting export notes task: {} to {}",
+            task_id,
+            output.display()
+        );
+
+        let export_manager = manager.clone();
+        let export_task_id = task_id.clone();
+
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            export_notes(
+                &db,
+                &workspace_id,
+                note_id.as_deref(),
+                &format,
+                &output,
+                |done, total, title| {
+                    let progress = (5 + (done * 90).checked_div(total).unwrap_or(0)) as u8;
+                    export_manager.update_progress(
+                        &export_task_id,
+                        progress,
+                        Some(format!("Exporting {} ({}/{})", title, done, total)),
+                    );
+                },
+            )
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+
+        match result {
+            Ok(report) => {
+                manager.complete(
+                    &task_id,
+                    Some(serde_json::json!({
+                        "report": report,
+                        "status": "exported"
+                    })),
+                );
+                log::info!("Export notes task completed: {}", task_id);
+                let _ = app.emit(
+                    "task:completed",
+                    serde_json::json!({
+                        "taskId": task_id,
+                        "taskType": task_type
+                    }),
+                );
+            }
+            Err(e) => {
+                manager.fail(&task_id, &e);
+                log::error!("Export notes task failed: {} - {}", task_id, e);
+                let _ = app.emit(
+                    "task:failed",
+                    serde_json::json!({
+                        "taskId": task_id,
+                        "taskType": task_type,
+                        "error": e
+                    }),
+                );
+            }
+        }
+    });
+
+    Ok(handle)
+}
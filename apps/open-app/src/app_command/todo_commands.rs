@@ -0,0 +1,64 @@
+//! 待办相关的 Tauri 命令
+//!
+//! 待办由笔记内容解析得到，勾选时改写笔记中对应行的复选框并按普通笔记修改保存。
+
+use crate::app_state::{AppState, Todo, parse_todos, set_todo_line_state};
+use chrono::Utc;
+
+use super::note_commands::after_note_saved;
+
+fn find_todo(state: &AppState, id: &str) -> Result<Todo, String> {
+    match state.db().get_todo(id) {
+        Ok(Some(todo)) => Ok(todo),
+        Ok(None) => Err(format!("Todo not found: {}", id)),
+        Err(e) => Err(format!("Failed to fetch todo: {}", e)),
+    }
+}
+
+/// 工作空间内的待办，默认只返回未完成的
+#[tauri::command]
+pub fn get_todos(
+    workspace_id: String,
+    include_done: Option<bool>,
+    state: tauri::State<AppState>,
+) -> Result<Vec<Todo>, String> {
+    let db = state.db();
+    db.list_todos(&workspace_id, include_done.unwrap_or(false))
+        .map_err(|e| format!("Failed to fetch todos: {}", e))
+}
+
+/// 切换待办的完成状态，`done` 为空时取反
+#[tauri::command]
+pub fn toggle_todo(
+    id: String,
+    done: Option<bool>,
+    state: tauri::State<AppState>,
+) -> Result<Todo, String> {
+    let todo = find_todo(&state, &id)?;
+    let done = done.unwrap_or(!todo.done);
+    if done == todo.done {
+        return Ok(todo);
+    }
+
+    let db = state.db();
+    let mut note = db
+        .get_note(&todo.note_id)
+        .map_err(|e| format!("Failed to fetch note: {}", e))?
+        .ok_or_else(|| format!("Note not found: {}", todo.note_id))?;
+    // 笔记可能在解析后被外部修改，行号对不上时按文本查找
+    let parsed = parse_todos(&note.content);
+    let line = parsed
+        .iter()
+        .find(|p| p.line == todo.line && p.text == todo.text)
+        .or_else(|| parsed.iter().find(|p| p.text == todo.text))
+        .map(|p| p.line)
+        .ok_or_else(|| format!("Todo no longer exists in note: {}", todo.text))?;
+    note.content = set_todo_line_state(&note.content, line, done)
+        .ok_or_else(|| format!("Todo no longer exists in note: {}", todo.text))?;
+    note.updated_at = Utc::now().timestamp_millis();
+
+    db.update_note(&note)
+        .map_err(|e| format!("Failed to update note: {}", e))?;
+    after_note_saved(&state, note, false);
+    find_todo(&state, &id)
+}
@@ -0,0 +1,718 @@
+//! 上下文包构建
+//!
+//! 将用户选择的仓库文件、符号、笔记、链接和会话片段按 token 预算排序裁剪，
+//! 输出为单个 Markdown 或 XML 文档（含文件树和来源引用），可直接粘贴给任意模型。
+
+use chrono::Utc;
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::KeyvStore;
+use crate::app_state::{AppConfig, DatabaseManager, NoteType};
+
+/// 单个文件的最大读取大小
+const MAX_FILE_BYTES: u64 = 512 * 1024;
+/// 剩余预算低于该值时不再截断放入，直接跳过
+const MIN_TRUNCATED_TOKENS: usize = 128;
+/// 每个条目的标题、引用等包装文本的预估开销
+const ITEM_OVERHEAD_TOKENS: usize = 24;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextPackFormat {
+    Markdown,
+    Xml,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextItemKind {
+    Symbol,
+    Note,
+    File,
+    Conversation,
+    Link,
+}
+
+impl ContextItemKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ContextItemKind::Symbol => "symbol",
+            ContextItemKind::Note => "note",
+            ContextItemKind::File => "file",
+            ContextItemKind::Conversation => "conversation",
+            ContextItemKind::Link => "link",
+        }
+    }
+
+    /// 默认排序权重，越大越优先放入
+    fn default_priority(&self) -> i32 {
+        match self {
+            ContextItemKind::Symbol => 50,
+            ContextItemKind::Note => 40,
+            ContextItemKind::File => 30,
+            ContextItemKind::Conversation => 20,
+            ContextItemKind::Link => 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextPathSelection {
+    pub repository_id: String,
+    /// 仓库内相对路径，可以是文件或目录；为空表示整个仓库
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationExcerpt {
+    pub conversation_id: String,
+    /// 起始消息下标（包含）
+    pub start: Option<usize>,
+    /// 结束消息下标（不包含）
+    pub end: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ContextSelection {
+    pub paths: Vec<ContextPathSelection>,
+    pub symbol_ids: Vec<String>,
+    pub note_ids: Vec<String>,
+    pub link_ids: Vec<String>,
+    pub conversations: Vec<ConversationExcerpt>,
+}
+
+/// 待打包的一段上下文
+#[derive(Debug, Clone)]
+pub struct ContextItem {
+    pub kind: ContextItemKind,
+    pub title: String,
+    /// 来源引用，例如 `repo:src/main.rs#L10-L20`、笔记 ID 或 URL
+    pub citation: String,
+    /// 出现在文件树中的路径（首段为仓库名）
+    pub tree_path: Option<String>,
+    pub language: Option<String>,
+    pub content: String,
+    /// 仓库文件在放入上下文包时才读取，读取前 content 为空
+    pub source_file: Option<PathBuf>,
+    pub priority: i32,
+}
+
+impl ContextItem {
+    pub fn new(kind: ContextItemKind, title: String, citation: String, content: String) -> Self {
+        Self {
+            kind,
+            title,
+            citation,
+            tree_path: None,
+            language: None,
+            content,
+            source_file: None,
+            priority: kind.default_priority(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextPackEntry {
+    pub kind: ContextItemKind,
+    pub title: String,
+    pub citation: String,
+    pub tokens: usize,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextPack {
+    pub format: ContextPackFormat,
+    pub content: String,
+    pub token_budget: usize,
+    pub token_count: usize,
+    pub entries: Vec<ContextPackEntry>,
+    /// 因预算不足被丢弃的条目引用
+    pub omitted: Vec<String>,
+    pub output_path: Option<String>,
+}
+
+pub fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7AF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{3000}'..='\u{303F}'
+        | '\u{FF00}'..='\u{FFEF}')
+}
+
+/// 粗略估算 token 数：CJK 字符按 1 个 token，其余按 4 个字符 1 个 token
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut cjk = 0usize;
+    let mut other = 0usize;
+    for c in text.chars() {
+        if is_cjk(c) {
+            cjk += 1;
+        } else {
+            other += 1;
+        }
+    }
+    cjk + other.div_ceil(4)
+}
+
+fn language_for_path(path: &str) -> Option<String> {
+    let ext = Path::new(path).extension()?.to_str()?.to_lowercase();
+    let lang = match ext.as_str() {
+        "rs" => "rust",
+        "ts" | "mts" | "cts" => "typescript",
+        "tsx" => "tsx",
+        "js" | "mjs" | "cjs" => "javascript",
+        "jsx" => "jsx",
+        "py" => "python",
+        "go" => "go",
+        "java" => "java",
+        "kt" => "kotlin",
+        "c" | "h" => "c",
+        "cc" | "cpp" | "hpp" => "cpp",
+        "cs" => "csharp",
+        "rb" => "ruby",
+        "php" => "php",
+        "swift" => "swift",
+        "sh" | "bash" | "zsh" => "bash",
+        "md" | "markdown" => "markdown",
+        "json" => "json",
+        "toml" => "toml",
+        "yaml" | "yml" => "yaml",
+        "html" => "html",
+        "css" => "css",
+        "scss" => "scss",
+        "sql" => "sql",
+        "xml" => "xml",
+        _ => return None,
+    };
+    Some(lang.to_string())
+}
+
+fn read_text_file(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    if bytes.iter().take(8192).any(|b| *b == 0) {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// 根据选择从数据库、仓库目录和符号库收集上下文条目
+pub fn collect_context_items(
+    db: &DatabaseManager,
+    selection: &ContextSelection,
+) -> Result<Vec<ContextItem>, String> {
+    let mut items = Vec::new();
+
+    if !selection.symbol_ids.is_empty() {
+        let store = KeyvStore::open_read_only(&AppConfig::sqlite_symbol_db_path())?;
+        for symbol_id in &selection.symbol_ids {
+            let Some(symbol) = store.get(&format!("symbol:{}", symbol_id))? else {
+                log::warn!("Symbol not found for context pack: {}", symbol_id);
+                continue;
+            };
+            items.push(symbol_item(symbol_id, &symbol));
+        }
+    }
+
+    for note_id in &selection.note_ids {
+        let note = db
+            .get_note(note_id)
+            .map_err(|e| format!("Failed to fetch note: {}", e))?
+            .ok_or_else(|| format!("Note not found: {}", note_id))?;
+        let mut item = ContextItem::new(
+            ContextItemKind::Note,
+            note.title.clone(),
+            format!("note:{}", note.id),
+            note.content,
+        );
+        if note.note_type == NoteType::Markdown {
+            item.language = Some("markdown".to_string());
+        }
+        items.push(item);
+    }
+
+    for path_selection in &selection.paths {
+        items.extend(collect_path_items(db, path_selection)?);
+    }
+
+    for excerpt in &selection.conversations {
+        let conversation = db
+            .get_conversation(&excerpt.conversation_id)
+            .map_err(|e| format!("Failed to fetch conversation: {}", e))?
+            .ok_or_else(|| format!("Conversation not found: {}", excerpt.conversation_id))?;
+        let messages: Vec<serde_json::Value> =
+            serde_json::from_str(&conversation.messages).unwrap_or_default();
+
+        let start = excerpt.start.unwrap_or(0).min(messages.len());
+        let end = excerpt
+            .end
+            .unwrap_or(messages.len())
+            .clamp(start, messages.len());
+        let content = messages[start..end]
+            .iter()
+            .map(|m| {
+                let role = m.get("role").and_then(|v| v.as_str()).unwrap_or("user");
+                let text = m.get("content").and_then(|v| v.as_str()).unwrap_or("");
+                format!("**{}**: {}", role, text)
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        items.push(ContextItem::new(
+            ContextItemKind::Conversation,
+            conversation.title,
+            format!(
+                "conversation:{}#messages-{}-{}",
+                conversation.id, start, end
+            ),
+            content,
+        ));
+    }
+
+    for link_id in &selection.link_ids {
+        let link = db
+            .get_web_link(link_id)
+            .map_err(|e| format!("Failed to fetch web link: {}", e))?
+            .ok_or_else(|| format!("Web link not found: {}", link_id))?;
+        let content = link.content.or(link.description).unwrap_or_default();
+        items.push(ContextItem::new(
+            ContextItemKind::Link,
+            link.title,
+            link.url,
+            content,
+        ));
+    }
+
+    Ok(items)
+}
+
+fn symbol_item(symbol_id: &str, symbol: &serde_json::Value) -> ContextItem {
+    let field = |name: &str| symbol.get(name).and_then(|v| v.as_str()).unwrap_or("");
+    // 符号 ID 格式：`{workspaceId}/{repoId}/{filePath}#{qualifiedName}`
+    let file_path = symbol_id
+        .split_once('#')
+        .map(|(left, _)| left.splitn(3, '/').nth(2).unwrap_or(left))
+        .unwrap_or(symbol_id);
+    let start_line = symbol
+        .pointer("/location/startLine")
+        .and_then(|v| v.as_i64());
+    let end_line = symbol.pointer("/location/endLine").and_then(|v| v.as_i64());
+
+    let citation = match (start_line, end_line) {
+        (Some(start), Some(end)) => format!("{}#L{}-L{}", file_path, start, end),
+        _ => file_path.to_string(),
+    };
+
+    let mut content = String::new();
+    if !field("docComment").is_empty() {
+        content.push_str(field("docComment"));
+        content.push('\n');
+    }
+    content.push_str(field("codeChunk"));
+
+    let title = if field("qualifiedName").is_empty() {
+        field("name").to_string()
+    } else {
+        format!("{} ({})", field("qualifiedName"), field("kind"))
+    };
+
+    let mut item = ContextItem::new(ContextItemKind::Symbol, title, citation, content);
+    item.language = language_for_path(file_path);
+    item
+}
+
+fn collect_path_items(
+    db: &DatabaseManager,
+    selection: &ContextPathSelection,
+) -> Result<Vec<ContextItem>, String> {
+    let repo = db
+        .get_git_repository(&selection.repository_id)
+        .map_err(|e| format!("Failed to fetch repository: {}", e))?
+        .ok_or_else(|| format!("Repository not found: {}", selection.repository_id))?;
+
+    let root = repo
+        .local_path
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve repository path: {}", e))?;
+    let relative = selection.path.as_deref().unwrap_or("").trim_matches('/');
+    let target = resolve_in_root(&root, relative)?;
+
+    // 只收集路径和大小，内容在按预算放入时再读取
+    let mut files = Vec::new();
+    if target.is_file() {
+        files.push(target);
+    } else {
+        let walker = WalkBuilder::new(&target)
+            .hidden(true)
+            .git_ignore(true)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build();
+        for entry in walker.filter_map(|e| e.ok()) {
+            if entry.file_type().is_some_and(|t| t.is_file()) {
+                files.push(entry.into_path());
+            }
+        }
+    }
+
+    let mut items = Vec::new();
+    for file in files {
+        if std::fs::metadata(&file).map_or(true, |m| m.len() > MAX_FILE_BYTES) {
+            continue;
+        }
+        let rel = file
+            .strip_prefix(&root)
+            .unwrap_or(&file)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let mut item = ContextItem::new(
+            ContextItemKind::File,
+            rel.clone(),
+            format!("{}:{}", repo.name, rel),
+            String::new(),
+        );
+        item.tree_path = Some(format!("{}/{}", repo.name, rel));
+        item.language = language_for_path(&rel);
+        item.source_file = Some(file);
+        items.push(item);
+    }
+
+    Ok(items)
+}
+
+/// 将前端传入的相对路径解析到仓库内，拒绝 `..` 或符号链接指向仓库外的路径
+fn resolve_in_root(root: &Path, relative: &str) -> Result<PathBuf, String> {
+    let target = root
+        .join(relative)
+        .canonicalize()
+        .map_err(|_| format!("路径不存在: {}", relative))?;
+    if !target.starts_with(root) {
+        return Err(format!("路径不在仓库内: {}", relative));
+    }
+    Ok(target)
+}
+
+/// 按 token 行截断内容
+fn truncate_to_tokens(content: &str, max_tokens: usize) -> String {
+    let mut out = String::new();
+    let mut used = 0usize;
+    for line in content.lines() {
+        let cost = estimate_tokens(line) + 1;
+        if used + cost > max_tokens {
+            break;
+        }
+        out.push_str(line);
+        out.push('\n');
+        used += cost;
+    }
+    out.push_str("… (truncated)\n");
+    out
+}
+
+/// 对条目排序并按预算裁剪，渲染成上下文包
+pub fn assemble_context_pack(
+    title: &str,
+    mut items: Vec<ContextItem>,
+    token_budget: usize,
+    format: ContextPackFormat,
+) -> ContextPack {
+    // 优先级高的在前，同优先级保持选择顺序
+    items.sort_by_key(|item| std::cmp::Reverse(item.priority));
+
+    let full_tree = render_file_tree(items.iter().filter_map(|i| i.tree_path.as_deref()));
+    let mut remaining = token_budget.saturating_sub(estimate_tokens(&full_tree) + 64);
+
+    let mut included: Vec<(ContextItem, bool)> = Vec::new();
+    let mut omitted = Vec::new();
+
+    for mut item in items {
+        if let Some(path) = item.source_file.take() {
+            if remaining < MIN_TRUNCATED_TOKENS {
+                omitted.push(item.citation);
+                continue;
+            }
+            // 二进制或非 UTF-8 文件直接跳过
+            let Some(content) = read_text_file(&path) else {
+                continue;
+            };
+            item.content = content;
+        }
+        let cost = estimate_tokens(&item.content) + ITEM_OVERHEAD_TOKENS;
+        if cost <= remaining {
+            remaining -= cost;
+            included.push((item, false));
+        } else if remaining >= MIN_TRUNCATED_TOKENS {
+            item.content = truncate_to_tokens(&item.content, remaining - ITEM_OVERHEAD_TOKENS);
+            remaining =
+                remaining.saturating_sub(estimate_tokens(&item.content) + ITEM_OVERHEAD_TOKENS);
+            included.push((item, true));
+        } else {
+            omitted.push(item.citation);
+        }
+    }
+
+    let tree = render_file_tree(included.iter().filter_map(|(i, _)| i.tree_path.as_deref()));
+    let content = match format {
+        ContextPackFormat::Markdown => render_markdown(title, &tree, &included),
+        ContextPackFormat::Xml => render_xml(title, &tree, &included),
+    };
+
+    let entries = included
+        .iter()
+        .map(|(item, truncated)| ContextPackEntry {
+            kind: item.kind,
+            title: item.title.clone(),
+            citation: item.citation.clone(),
+            tokens: estimate_tokens(&item.content),
+            truncated: *truncated,
+        })
+        .collect();
+
+    ContextPack {
+        format,
+        token_count: estimate_tokens(&content),
+        content,
+        token_budget,
+        entries,
+        omitted,
+        output_path: None,
+    }
+}
+
+#[derive(Default)]
+struct TreeNode {
+    children: BTreeMap<String, TreeNode>,
+}
+
+fn render_file_tree<'a>(paths: impl Iterator<Item = &'a str>) -> String {
+    let mut root = TreeNode::default();
+    for path in paths {
+        let mut node = &mut root;
+        for segment in path.split('/').filter(|s| !s.is_empty()) {
+            node = node.children.entry(segment.to_string()).or_default();
+        }
+    }
+
+    fn walk(node: &TreeNode, depth: usize, out: &mut String) {
+        for (name, child) in &node.children {
+            out.push_str(&"  ".repeat(depth));
+            out.push_str(name);
+            if !child.children.is_empty() {
+                out.push('/');
+            }
+            out.push('\n');
+            walk(child, depth + 1, out);
+        }
+    }
+
+    let mut out = String::new();
+    walk(&root, 0, &mut out);
+    out
+}
+
+fn code_fence(content: &str) -> String {
+    let mut longest = 0usize;
+    let mut current = 0usize;
+    for c in content.chars() {
+        if c == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    "`".repeat((longest + 1).max(3))
+}
+
+fn render_markdown(title: &str, tree: &str, items: &[(ContextItem, bool)]) -> String {
+    let mut out = format!("# Context Pack: {}\n\n", title);
+    out.push_str(&format!(
+        "> Generated by {} at {}. {} items.\n\n",
+        crate::common::APP_NAME,
+        Utc::now().to_rfc3339(),
+        items.len()
+    ));
+
+    if !tree.is_empty() {
+        out.push_str("## File Tree\n\n```\n");
+        out.push_str(tree);
+        out.push_str("```\n\n");
+    }
+
+    out.push_str("## Sources\n\n");
+    for (i, (item, truncated)) in items.iter().enumerate() {
+        out.push_str(&format!(
+            "{}. [{}] {} — `{}`{}\n",
+            i + 1,
+            item.kind.as_str(),
+            item.title,
+            item.citation,
+            if *truncated { " (truncated)" } else { "" }
+        ));
+    }
+
+    out.push_str("\n## Contents\n");
+    for (i, (item, _)) in items.iter().enumerate() {
+        let fence = code_fence(&item.content);
+        out.push_str(&format!("\n### [{}] {}\n\n", i + 1, item.title));
+        out.push_str(&format!("Source: `{}`\n\n", item.citation));
+        out.push_str(&fence);
+        out.push_str(item.language.as_deref().unwrap_or(""));
+        out.push('\n');
+        out.push_str(&item.content);
+        if !item.content.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str(&fence);
+        out.push('\n');
+    }
+
+    out
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_xml(title: &str, tree: &str, items: &[(ContextItem, bool)]) -> String {
+    let mut out = format!(
+        "<context_pack title=\"{}\" generator=\"{}\" generated_at=\"{}\">\n",
+        xml_escape(title),
+        crate::common::APP_NAME,
+        Utc::now().to_rfc3339()
+    );
+
+    if !tree.is_empty() {
+        out.push_str("<file_tree>\n");
+        out.push_str(&xml_escape(tree));
+        out.push_str("</file_tree>\n");
+    }
+
+    out.push_str("<items>\n");
+    for (item, truncated) in items {
+        out.push_str(&format!(
+            "<item kind=\"{}\" title=\"{}\" source=\"{}\" truncated=\"{}\">\n",
+            item.kind.as_str(),
+            xml_escape(&item.title),
+            xml_escape(&item.citation),
+            truncated
+        ));
+        out.push_str(&xml_escape(&item.content));
+        if !item.content.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str("</item>\n");
+    }
+    out.push_str("</items>\n</context_pack>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_item(path: &str, content: &str) -> ContextItem {
+        let mut item = ContextItem::new(
+            ContextItemKind::File,
+            path.to_string(),
+            format!("repo:{}", path),
+            content.to_string(),
+        );
+        item.tree_path = Some(format!("repo/{}", path));
+        item
+    }
+
+    #[test]
+    fn test_estimate_tokens_counts_cjk_per_char() {
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+        assert_eq!(estimate_tokens("上下文"), 3);
+        assert_eq!(estimate_tokens("上下文 abcd"), 5);
+    }
+
+    #[test]
+    fn test_budget_ranks_and_trims() {
+        let note = ContextItem::new(
+            ContextItemKind::Note,
+            "Design".to_string(),
+            "note:1".to_string(),
+            "short note".to_string(),
+        );
+        let big = file_item("src/big.rs", &"let x = 1;\n".repeat(400));
+        let small = file_item("src/small.rs", "fn main() {}\n");
+
+        let pack = assemble_context_pack(
+            "test",
+            vec![small, big, note],
+            600,
+            ContextPackFormat::Markdown,
+        );
+
+        assert_eq!(pack.entries[0].kind, ContextItemKind::Note);
+        assert!(pack.entries.iter().any(|e| e.truncated));
+        assert!(pack.content.contains("## File Tree"));
+        assert!(pack.content.contains("src/\n"));
+        assert!(pack.content.contains("`repo:src/small.rs`"));
+    }
+
+    #[test]
+    fn test_path_selection_stays_in_repo() {
+        let dir = std::env::temp_dir().join(format!("test_context_pack_{}", uuid::Uuid::new_v4()));
+        let root = dir.join("repo");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/a.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(dir.join("secret.txt"), "secret").unwrap();
+        let root = root.canonicalize().unwrap();
+
+        assert!(resolve_in_root(&root, "src/a.rs").is_ok());
+        assert!(resolve_in_root(&root, "").is_ok());
+        assert!(resolve_in_root(&root, "../secret.txt").is_err());
+        assert!(resolve_in_root(&root, "src/../../secret.txt").is_err());
+
+        // 文件在放入时才读取，预算耗尽后的文件不读取
+        let mut lazy = file_item("src/a.rs", "");
+        lazy.source_file = Some(root.join("src/a.rs"));
+        let mut missing = file_item("src/gone.rs", "");
+        missing.source_file = Some(root.join("src/gone.rs"));
+        let pack = assemble_context_pack(
+            "lazy",
+            vec![lazy.clone(), missing],
+            10_000,
+            ContextPackFormat::Markdown,
+        );
+        assert_eq!(pack.entries.len(), 1);
+        assert!(pack.content.contains("fn a() {}"));
+        let pack = assemble_context_pack("lazy", vec![lazy], 10, ContextPackFormat::Markdown);
+        assert!(pack.entries.is_empty());
+        assert_eq!(pack.omitted, ["repo:src/a.rs"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_xml_output_is_escaped() {
+        let pack = assemble_context_pack(
+            "a < b",
+            vec![file_item("lib.ts", "if (a < b && c) {}")],
+            10_000,
+            ContextPackFormat::Xml,
+        );
+
+        assert!(pack.content.starts_with("<context_pack title=\"a &lt; b\""));
+        assert!(pack.content.contains("if (a &lt; b &amp;&amp; c) {}"));
+        assert!(pack.omitted.is_empty());
+    }
+}
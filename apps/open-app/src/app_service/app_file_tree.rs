@@ -10,6 +10,8 @@ use std::time::{Duration, SystemTime};
 use tauri::{AppHandle, Emitter};
 use tokio::fs;
 
+use super::{GitFileStatus, git_decorations};
+
 const CACHE_DURATION_SECS: u64 = 300;
 const DEBOUNCE_MILLIS: u64 = 50;
 
@@ -23,6 +25,9 @@ pub struct FileTreeNode {
     pub size: Option<u64>,
     pub modified: Option<i64>,
     pub children: Option<Vec<FileTreeNode>>,
+    /// 所在 Git 仓库中的状态（modified / untracked / ignored 等）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_status: Option<GitFileStatus>,
 }
 
 #[derive(Debug, Clone)]
@@ -84,6 +89,14 @@ pub async fn read_dir_on_demand(dir_path: String) -> Result<Vec<FileTreeNode>, S
         }
     }
 
+    // git status 在大仓库上很慢，放到阻塞线程池执行
+    let status_dir = path.clone();
+    let decorations = tauri::async_runtime::spawn_blocking(move || git_decorations(&status_dir))
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("Git status task failed for {}: {}", dir_path, e);
+            HashMap::new()
+        });
     let mut nodes = Vec::new();
     let mut entries = fs::read_dir(&path)
         .await
@@ -114,6 +127,7 @@ pub async fn read_dir_on_demand(dir_path: String) -> Result<Vec<FileTreeNode>, S
                 .map(|d| d.as_millis() as i64)
         });
 
+        let git_status = decorations.get(&name).copied();
         nodes.push(FileTreeNode {
             path: entry_path.to_string_lossy().to_string(),
             name,
@@ -122,6 +136,7 @@ pub async fn read_dir_on_demand(dir_path: String) -> Result<Vec<FileTreeNode>, S
             size,
             modified,
             children: None,
+            git_status,
         });
     }
 
@@ -0,0 +1,966 @@
+//! Git 仓库同步
+//!
+//! 基于 git2 对 `GitRepository.local_path` 指向的本地克隆执行 fetch，
+//! 在安全时快进跟踪分支，并在分支分叉时如实报告而不做合并；
+//! 同时负责克隆（浅克隆、单分支、稀疏检出、子模块）和识别磁盘上已有的本地仓库。
+
+use git2::{
+    BranchType, Cred, CredentialType, Direction, ErrorClass, ErrorCode, FetchOptions, IndexEntry,
+    IndexEntryExtendedFlag, IndexEntryFlag, ObjectType, Oid, Pathspec, PathspecFlags, Remote,
+    RemoteCallbacks, Repository, SubmoduleUpdateOptions, TreeWalkMode, TreeWalkResult,
+    build::CheckoutBuilder, build::RepoBuilder,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::app_state::{
+    CloneStatus, DatabaseManager, GitCredential, GitCredentialKind, GitRepository,
+    select_git_credential,
+};
+
+/// 默认远程名称
+const DEFAULT_REMOTE: &str = "origin";
+
+/// libgit2 在认证失败后会反复请求凭据，超过该次数即视为认证失败
+const MAX_AUTH_ATTEMPTS: usize = 3;
+
+/// 稀疏检出模式文件，相对 `.git` 目录
+const SPARSE_CHECKOUT_FILE: &str = "info/sparse-checkout";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncState {
+    /// 本地分支与上游一致
+    UpToDate,
+    /// 已快进到上游提交
+    FastForwarded,
+    /// 本地有未推送的提交，上游没有新提交
+    Ahead,
+    /// 本地与上游均有对方没有的提交，需要手动处理
+    Diverged,
+    /// 找不到上游分支
+    NoUpstream,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    pub repository_id: String,
+    pub branch: String,
+    pub state: SyncState,
+    /// 本地分支领先上游的提交数
+    pub ahead: usize,
+    /// 本地分支落后上游的提交数
+    pub behind: usize,
+    pub previous_head: Option<String>,
+    pub head: Option<String>,
+    pub head_moved: bool,
+}
+
+pub fn open_repository(path: &Path) -> Result<Repository, String> {
+    Repository::open(path).map_err(|e| format!("无法打开 Git 仓库 {}: {}", path.display(), e))
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest))
+            .unwrap_or_else(|| PathBuf::from(path)),
+        None => PathBuf::from(path),
+    }
+}
+
+fn credential_for(
+    credential: Option<&GitCredential>,
+    url: &str,
+    username_from_url: Option<&str>,
+    allowed: CredentialType,
+) -> Result<Cred, git2::Error> {
+    let username = credential
+        .and_then(|c| c.username.as_deref())
+        .or(username_from_url)
+        .unwrap_or("git");
+
+    if allowed.contains(CredentialType::USERNAME) {
+        return Cred::username(username);
+    }
+
+    let Some(credential) = credential else {
+        // 未配置凭据时尝试 ssh-agent 与系统默认凭据
+        if allowed.contains(CredentialType::SSH_KEY) {
+            return Cred::ssh_key_from_agent(username);
+        }
+        if allowed.contains(CredentialType::DEFAULT) {
+            return Cred::default();
+        }
+        return Err(git2::Error::new(
+            ErrorCode::Auth,
+            ErrorClass::Callback,
+            format!("no credentials configured for {}", url),
+        ));
+    };
+
+    match credential.kind {
+        GitCredentialKind::SshKey if allowed.contains(CredentialType::SSH_KEY) => {
+            let key_path = credential.ssh_key_path.as_deref().ok_or_else(|| {
+                git2::Error::new(
+                    ErrorCode::Auth,
+                    ErrorClass::Callback,
+                    "SSH key path is not configured",
+                )
+            })?;
+            Cred::ssh_key(
+                username,
+                None,
+                &expand_home(key_path),
+                credential.secret.as_deref(),
+            )
+        }
+        GitCredentialKind::SshAgent if allowed.contains(CredentialType::SSH_KEY) => {
+            Cred::ssh_key_from_agent(username)
+        }
+        GitCredentialKind::HttpsToken if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) => {
+            let token = credential.secret.as_deref().ok_or_else(|| {
+                git2::Error::new(
+                    ErrorCode::Auth,
+                    ErrorClass::Callback,
+                    "HTTPS token is not configured",
+                )
+            })?;
+            Cred::userpass_plaintext(username, token)
+        }
+        _ => Err(git2::Error::new(
+            ErrorCode::Auth,
+            ErrorClass::Callback,
+            format!(
+                "{} credential for {} cannot be used with {}",
+                credential.kind.as_str(),
+                credential.remote_pattern,
+                url
+            ),
+        )),
+    }
+}
+
+/// 构造带凭据回调的远程回调
+pub fn remote_callbacks<'a>(credential: Option<GitCredential>) -> RemoteCallbacks<'a> {
+    let mut callbacks = RemoteCallbacks::new();
+    let mut attempts = 0;
+    callbacks.credentials(move |url, username_from_url, allowed| {
+        attempts += 1;
+        if attempts > MAX_AUTH_ATTEMPTS {
+            return Err(git2::Error::new(
+                ErrorCode::Auth,
+                ErrorClass::Callback,
+                "credentials were rejected",
+            ));
+        }
+        credential_for(credential.as_ref(), url, username_from_url, allowed)
+    });
+    callbacks
+}
+
+/// 构造 fetch 选项
+pub fn fetch_options<'a>(credential: Option<GitCredential>) -> FetchOptions<'a> {
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(remote_callbacks(credential));
+    options
+}
+
+/// 将 git2 错误转换为面向用户的说明，认证失败时给出明确提示
+pub fn describe_git_error(action: &str, url: &str, e: &git2::Error) -> String {
+    let message = e.message();
+    let lower = message.to_lowercase();
+    let auth_failed = e.code() == ErrorCode::Auth
+        || lower.contains("authentication")
+        || lower.contains("401")
+        || lower.contains("403")
+        || (e.class() == ErrorClass::Ssh && lower.contains("key"));
+
+    if auth_failed {
+        format!(
+            "Authentication failed for {}: {}. Check the credentials configured for this remote.",
+            url, message
+        )
+    } else if e.code() == ErrorCode::Certificate {
+        format!("Certificate check failed for {}: {}", url, message)
+    } else {
+        format!("Failed to {} {}: {}", action, url, message)
+    }
+}
+
+/// 从远程地址推断仓库名称
+pub fn repository_name_from_url(url: &str) -> String {
+    let trimmed = url.trim().trim_end_matches('/');
+    let last = trimmed.rsplit(['/', ':']).next().unwrap_or(trimmed);
+    let name = last.strip_suffix(".git").unwrap_or(last);
+    if name.is_empty() {
+        "repository".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// 克隆选项
+#[derive(Debug, Clone, Default)]
+pub struct CloneOptions {
+    pub branch: Option<String>,
+    /// 浅克隆深度，为空或 0 时获取完整历史
+    pub depth: Option<u32>,
+    /// 只获取指定分支（未指定时为远程默认分支）
+    pub single_branch: bool,
+    /// 稀疏检出的路径模式，如 `apps/web`、`docs/*.md`；为空时检出全部文件
+    pub sparse_paths: Vec<String>,
+}
+
+/// 克隆时初始化的子模块
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClonedSubmodule {
+    pub name: String,
+    /// 子模块工作区的绝对路径
+    pub path: PathBuf,
+    /// 所属仓库的工作区路径，嵌套子模块指向上一级子模块
+    pub parent_path: PathBuf,
+    pub url: String,
+    pub branch: Option<String>,
+    pub head: Option<String>,
+}
+
+/// 查询远程默认分支
+fn remote_default_branch(url: &str, credential: Option<GitCredential>) -> Result<String, String> {
+    let mut remote =
+        Remote::create_detached(url).map_err(|e| describe_git_error("connect to", url, &e))?;
+    let connection = remote
+        .connect_auth(Direction::Fetch, Some(remote_callbacks(credential)), None)
+        .map_err(|e| describe_git_error("connect to", url, &e))?;
+    let head = connection
+        .default_branch()
+        .map_err(|e| describe_git_error("read default branch of", url, &e))?;
+    let head = head
+        .as_str()
+        .ok_or_else(|| format!("Invalid default branch for {}", url))?;
+    Ok(head.strip_prefix("refs/heads/").unwrap_or(head).to_string())
+}
+
+/// 克隆远程仓库
+///
+/// `on_progress(已接收对象数, 对象总数)` 返回 false 时中止克隆。
+pub fn clone_repository<F>(
+    url: &str,
+    dest: &Path,
+    options: &CloneOptions,
+    credential: Option<GitCredential>,
+    mut on_progress: F,
+) -> Result<Repository, String>
+where
+    F: FnMut(usize, usize) -> bool,
+{
+    let mut branch = options.branch.clone().filter(|b| !b.is_empty());
+    if options.single_branch && branch.is_none() {
+        branch = Some(remote_default_branch(url, credential.clone())?);
+    }
+
+    let mut callbacks = remote_callbacks(credential);
+    callbacks
+        .transfer_progress(|stats| on_progress(stats.received_objects(), stats.total_objects()));
+
+    let mut fetch = FetchOptions::new();
+    fetch.remote_callbacks(callbacks);
+    if let Some(depth) = options.depth.filter(|d| *d > 0) {
+        fetch.depth(depth.min(i32::MAX as u32) as i32);
+    }
+
+    let mut builder = RepoBuilder::new();
+    builder.fetch_options(fetch);
+    if let Some(branch) = &branch {
+        builder.branch(branch);
+    }
+    if options.single_branch
+        && let Some(branch) = branch.clone()
+    {
+        // 默认 refspec 会获取全部分支，单分支克隆只跟踪目标分支
+        builder.remote_create(move |repo, name, url| {
+            let refspec = format!("+refs/heads/{0}:refs/remotes/{1}/{0}", branch, name);
+            repo.remote_with_fetch(name, url, &refspec)
+        });
+    }
+    if !options.sparse_paths.is_empty() {
+        let mut checkout = CheckoutBuilder::new();
+        for pattern in &options.sparse_paths {
+            checkout.path(pattern);
+        }
+        builder.with_checkout(checkout);
+    }
+
+    let repo = builder
+        .clone(url, dest)
+        .map_err(|e| describe_git_error("clone", url, &e))?;
+
+    if !options.sparse_paths.is_empty() {
+        enable_sparse_checkout(&repo, &options.sparse_paths)?;
+    }
+    Ok(repo)
+}
+
+/// 读取仓库的稀疏检出模式，未启用时为空
+pub fn sparse_patterns(repo: &Repository) -> Vec<String> {
+    let enabled = repo
+        .config()
+        .and_then(|config| config.get_bool("core.sparseCheckout"))
+        .unwrap_or(false);
+    if !enabled {
+        return Vec::new();
+    }
+
+    fs::read_to_string(repo.path().join(SPARSE_CHECKOUT_FILE))
+        .map(|content| {
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 写入稀疏检出配置（与 git 命令行共用），并标记范围外的索引项
+pub fn enable_sparse_checkout(repo: &Repository, patterns: &[String]) -> Result<(), String> {
+    let sparse_file = repo.path().join(SPARSE_CHECKOUT_FILE);
+    if let Some(parent) = sparse_file.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to write sparse-checkout patterns: {}", e))?;
+    }
+    fs::write(&sparse_file, format!("{}\n", patterns.join("\n")))
+        .map_err(|e| format!("Failed to write sparse-checkout patterns: {}", e))?;
+    repo.config()
+        .and_then(|mut config| config.set_bool("core.sparseCheckout", true))
+        .map_err(|e| format!("Failed to enable sparse checkout: {}", e))?;
+
+    refresh_sparse_index(repo)
+}
+
+/// 构造 safe 模式的检出选项；启用稀疏检出时只写入范围内的文件
+pub fn checkout_builder<'a>(repo: &Repository) -> CheckoutBuilder<'a> {
+    let mut builder = CheckoutBuilder::new();
+    builder.safe();
+    for pattern in sparse_patterns(repo) {
+        builder.path(pattern);
+    }
+    builder
+}
+
+/// 将稀疏检出范围外的索引项同步为 HEAD 中的版本并标记 skip-worktree
+///
+/// libgit2 不支持稀疏检出，范围内的文件由带路径过滤的检出写入，
+/// 这里补齐范围外的条目，避免它们在状态中显示为已删除。
+pub fn refresh_sparse_index(repo: &Repository) -> Result<(), String> {
+    let patterns = sparse_patterns(repo);
+    if patterns.is_empty() {
+        return Ok(());
+    }
+
+    let pathspec =
+        Pathspec::new(patterns.iter()).map_err(|e| format!("Invalid sparse pattern: {}", e))?;
+    let in_scope = |path: &Path| pathspec.matches_path(path, PathspecFlags::DEFAULT);
+
+    let tree = repo
+        .head()
+        .and_then(|head| head.peel_to_tree())
+        .map_err(|e| format!("Failed to resolve HEAD: {}", e))?;
+    let mut index = repo
+        .index()
+        .map_err(|e| format!("Failed to read index: {}", e))?;
+
+    let stale: Vec<PathBuf> = index
+        .iter()
+        .map(|entry| PathBuf::from(String::from_utf8_lossy(&entry.path).to_string()))
+        .filter(|path| !in_scope(path))
+        .collect();
+    for path in &stale {
+        index
+            .remove_path(path)
+            .map_err(|e| format!("Failed to update index: {}", e))?;
+    }
+
+    let mut entries = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+        // 文件与子模块（gitlink）都需要写入索引
+        if entry.kind() == Some(ObjectType::Tree) {
+            return TreeWalkResult::Ok;
+        }
+        let Some(name) = entry.name() else {
+            return TreeWalkResult::Ok;
+        };
+        let path = format!("{}{}", root, name);
+        if !in_scope(Path::new(&path)) {
+            entries.push((path, entry.id(), entry.filemode() as u32));
+        }
+        TreeWalkResult::Ok
+    })
+    .map_err(|e| format!("Failed to read tree: {}", e))?;
+
+    for (path, id, mode) in entries {
+        let file_size = repo
+            .find_blob(id)
+            .map(|blob| blob.size() as u32)
+            .unwrap_or(0);
+        index
+            .add(&IndexEntry {
+                ctime: git2::IndexTime::new(0, 0),
+                mtime: git2::IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode,
+                uid: 0,
+                gid: 0,
+                file_size,
+                id,
+                flags: IndexEntryFlag::EXTENDED.bits(),
+                flags_extended: IndexEntryExtendedFlag::SKIP_WORKTREE.bits(),
+                path: path.into_bytes(),
+            })
+            .map_err(|e| format!("Failed to update index: {}", e))?;
+    }
+
+    index
+        .write()
+        .map_err(|e| format!("Failed to write index: {}", e))
+}
+
+/// 递归初始化并更新子模块
+///
+/// 每个子模块开始前调用 `on_submodule(名称)`，返回 false 时中止。
+pub fn update_submodules<F>(
+    repo: &Repository,
+    credentials: &[GitCredential],
+    depth: Option<u32>,
+    on_submodule: &mut F,
+) -> Result<Vec<ClonedSubmodule>, String>
+where
+    F: FnMut(&str) -> bool,
+{
+    let Some(workdir) = repo.workdir().map(Path::to_path_buf) else {
+        return Ok(Vec::new());
+    };
+    let mut submodules = repo
+        .submodules()
+        .map_err(|e| format!("Failed to read submodules: {}", e))?;
+    // 稀疏检出范围外的子模块不初始化
+    let patterns = sparse_patterns(repo);
+    let pathspec = if patterns.is_empty() {
+        None
+    } else {
+        Some(Pathspec::new(patterns.iter()).map_err(|e| format!("Invalid sparse pattern: {}", e))?)
+    };
+
+    let mut cloned = Vec::new();
+    for submodule in submodules.iter_mut() {
+        if pathspec
+            .as_ref()
+            .is_some_and(|p| !p.matches_path(submodule.path(), PathspecFlags::DEFAULT))
+        {
+            continue;
+        }
+        let name = submodule.name().unwrap_or_default().to_string();
+        if !on_submodule(&name) {
+            return Err("Submodule update cancelled".to_string());
+        }
+
+        let url = submodule.url().unwrap_or_default().to_string();
+        let mut fetch = fetch_options(select_git_credential(credentials, &url).cloned());
+        if let Some(depth) = depth.filter(|d| *d > 0) {
+            fetch.depth(depth.min(i32::MAX as u32) as i32);
+        }
+        let mut opts = SubmoduleUpdateOptions::new();
+        opts.fetch(fetch);
+        submodule.update(true, Some(&mut opts)).map_err(|e| {
+            describe_git_error(&format!("update submodule {} from", name), &url, &e)
+        })?;
+
+        let sub_repo = submodule
+            .open()
+            .map_err(|e| format!("Failed to open submodule {}: {}", name, e))?;
+        cloned.push(ClonedSubmodule {
+            name: name.clone(),
+            path: workdir.join(submodule.path()),
+            parent_path: workdir.clone(),
+            url,
+            branch: submodule.branch().map(str::to_string),
+            head: head_oid(&sub_repo).map(|oid| oid.to_string()),
+        });
+        cloned.extend(update_submodules(
+            &sub_repo,
+            credentials,
+            depth,
+            on_submodule,
+        )?);
+    }
+    Ok(cloned)
+}
+
+fn head_oid(repo: &Repository) -> Option<Oid> {
+    repo.head().ok().and_then(|head| head.target())
+}
+
+/// fetch 远程并在安全时快进本地分支
+///
+/// 只有在本地分支没有领先提交时才会快进；若分支当前已检出，
+/// 使用 safe 模式检出，工作区中会被覆盖的本地修改会使同步失败。
+pub fn sync_branch(
+    repo: &Repository,
+    branch: &str,
+    credentials: &[GitCredential],
+) -> Result<(SyncState, usize, usize), String> {
+    let local_ref = format!("refs/heads/{}", branch);
+    let mut local_branch = repo
+        .find_branch(branch, BranchType::Local)
+        .map_err(|_| format!("Branch not found: {}", branch))?;
+
+    let remote_name = repo
+        .branch_upstream_remote(&local_ref)
+        .ok()
+        .and_then(|buf| buf.as_str().map(str::to_string))
+        .unwrap_or_else(|| DEFAULT_REMOTE.to_string());
+
+    let mut remote = repo
+        .find_remote(&remote_name)
+        .map_err(|e| format!("Remote not found: {} ({})", remote_name, e))?;
+    let url = remote.url().unwrap_or(&remote_name).to_string();
+    let credential = select_git_credential(credentials, &url).cloned();
+    remote
+        .fetch::<&str>(&[], Some(&mut fetch_options(credential)), None)
+        .map_err(|e| describe_git_error("fetch", &url, &e))?;
+
+    let upstream_ref = repo
+        .branch_upstream_name(&local_ref)
+        .ok()
+        .and_then(|buf| buf.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("refs/remotes/{}/{}", remote_name, branch));
+
+    let Ok(upstream_oid) = repo.refname_to_id(&upstream_ref) else {
+        return Ok((SyncState::NoUpstream, 0, 0));
+    };
+    let local_oid = local_branch
+        .get()
+        .target()
+        .ok_or_else(|| format!("Branch has no target: {}", branch))?;
+
+    let (ahead, behind) = repo
+        .graph_ahead_behind(local_oid, upstream_oid)
+        .map_err(|e| format!("Failed to compare branches: {}", e))?;
+
+    let state = match (ahead, behind) {
+        (0, 0) => SyncState::UpToDate,
+        (_, 0) => SyncState::Ahead,
+        (0, _) => {
+            let is_head = local_branch.is_head();
+            if is_head {
+                let target = repo
+                    .find_object(upstream_oid, None)
+                    .map_err(|e| format!("Failed to find upstream commit: {}", e))?;
+                repo.checkout_tree(&target, Some(&mut checkout_builder(repo)))
+                    .map_err(|e| format!("Local changes would be overwritten by sync: {}", e))?;
+            }
+            local_branch
+                .get_mut()
+                .set_target(upstream_oid, "open-context: fast-forward")
+                .map_err(|e| format!("Failed to fast-forward {}: {}", branch, e))?;
+            if is_head {
+                refresh_sparse_index(repo)?;
+            }
+            SyncState::FastForwarded
+        }
+        _ => SyncState::Diverged,
+    };
+
+    Ok((state, ahead, behind))
+}
+
+/// 同步仓库记录对应的本地克隆，并更新 `last_commit_hash` / `last_synced_at`
+pub fn sync_git_repository(
+    db: &DatabaseManager,
+    git_repo: &GitRepository,
+) -> Result<SyncReport, String> {
+    let repo = open_repository(&git_repo.local_path)?;
+    let credentials = db
+        .list_git_credentials()
+        .map_err(|e| format!("Failed to load git credentials: {}", e))?;
+
+    let previous_head = head_oid(&repo);
+    let (state, ahead, behind) = sync_branch(&repo, &git_repo.branch, &credentials)?;
+    let head = head_oid(&repo);
+
+    if let Some(head) = head {
+        db.update_git_repository_sync(&git_repo.id, &head.to_string())
+            .map_err(|e| format!("Failed to update repository sync status: {}", e))?;
+    }
+
+    Ok(SyncReport {
+        repository_id: git_repo.id.clone(),
+        branch: git_repo.branch.clone(),
+        state,
+        ahead,
+        behind,
+        previous_head: previous_head.map(|oid| oid.to_string()),
+        head: head.map(|oid| oid.to_string()),
+        head_moved: previous_head != head,
+    })
+}
+
+/// 本地已有仓库的基本信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalRepositoryInfo {
+    /// 工作区根目录
+    pub path: PathBuf,
+    pub name: String,
+    pub remote_url: String,
+    /// 当前检出的分支，分离 HEAD 时回退到默认分支
+    pub branch: String,
+    pub default_branch: Option<String>,
+    pub head: Option<String>,
+}
+
+/// 扫描时跳过的目录
+const SCAN_SKIP_DIRS: &[&str] = &["node_modules", "target", "dist", "build", "vendor"];
+
+/// 读取路径所在的本地仓库信息
+pub fn inspect_local_repository(path: &Path) -> Result<LocalRepositoryInfo, String> {
+    let repo = Repository::discover(path)
+        .map_err(|e| format!("Not a git repository: {} ({})", path.display(), e))?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| format!("Bare repositories are not supported: {}", path.display()))?;
+    let workdir = workdir
+        .canonicalize()
+        .unwrap_or_else(|_| workdir.to_path_buf());
+
+    let remote = repo.find_remote(DEFAULT_REMOTE).ok().or_else(|| {
+        let remotes = repo.remotes().ok()?;
+        let first = remotes.iter().flatten().next()?.to_string();
+        repo.find_remote(&first).ok()
+    });
+    let remote_url = remote
+        .as_ref()
+        .and_then(|r| r.url().map(str::to_string))
+        .unwrap_or_default();
+
+    // refs/remotes/origin/HEAD 指向远程默认分支
+    let default_branch = remote
+        .as_ref()
+        .and_then(|r| r.name().map(str::to_string))
+        .and_then(|remote_name| {
+            let head_ref = repo
+                .find_reference(&format!("refs/remotes/{}/HEAD", remote_name))
+                .ok()?;
+            let target = head_ref.symbolic_target()?.to_string();
+            target
+                .strip_prefix(&format!("refs/remotes/{}/", remote_name))
+                .map(str::to_string)
+        });
+
+    let head = repo.head().ok();
+    let current_branch = head
+        .as_ref()
+        .filter(|h| h.is_branch())
+        .and_then(|h| h.shorthand().map(str::to_string))
+        .or_else(|| {
+            // 尚无提交的仓库 HEAD 指向未出生的分支
+            repo.find_reference("HEAD")
+                .ok()
+                .and_then(|r| r.symbolic_target().map(str::to_string))
+                .and_then(|t| t.strip_prefix("refs/heads/").map(str::to_string))
+        });
+    let branch = current_branch
+        .clone()
+        .or_else(|| default_branch.clone())
+        .unwrap_or_else(|| "HEAD".to_string());
+
+    let name = workdir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| workdir.to_string_lossy().to_string());
+
+    Ok(LocalRepositoryInfo {
+        path: workdir.clone(),
+        name,
+        remote_url,
+        default_branch: default_branch.or(current_branch),
+        branch,
+        head: head.and_then(|h| h.target()).map(|oid| oid.to_string()),
+    })
+}
+
+/// 在目录下查找 Git 仓库，找到仓库后不再深入其子目录
+pub fn find_git_repositories(root: &Path, max_depth: usize) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut walker = WalkDir::new(root).max_depth(max_depth).into_iter();
+
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy();
+        if entry.depth() > 0 && (name.starts_with('.') || SCAN_SKIP_DIRS.contains(&name.as_ref())) {
+            walker.skip_current_dir();
+            continue;
+        }
+
+        if entry.path().join(".git").exists() {
+            found.push(entry.path().to_path_buf());
+            walker.skip_current_dir();
+        }
+    }
+
+    found
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedRepository {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalRepositoryImport {
+    pub imported: Vec<GitRepository>,
+    pub skipped: Vec<SkippedRepository>,
+}
+
+/// 将本地已有仓库登记到工作空间，状态直接标记为已克隆
+pub fn register_local_repository(
+    db: &DatabaseManager,
+    workspace_id: &str,
+    path: &Path,
+) -> Result<GitRepository, String> {
+    let info = inspect_local_repository(path)?;
+
+    let existing = db
+        .list_git_repositories(workspace_id)
+        .map_err(|e| format!("Failed to fetch repositories: {}", e))?;
+    if existing.iter().any(|repo| repo.local_path == info.path) {
+        return Err(format!(
+            "Repository already registered: {}",
+            info.path.display()
+        ));
+    }
+
+    let mut repo = GitRepository::new(
+        workspace_id.to_string(),
+        info.name,
+        info.remote_url,
+        info.path,
+        info.branch,
+    );
+    repo.default_branch = info.default_branch;
+    repo.last_commit_hash = info.head;
+    repo.clone_status = CloneStatus::Completed;
+    repo.clone_progress = 100;
+
+    db.create_git_repository(&repo)
+        .map_err(|e| format!("Failed to create repository: {}", e))?;
+    Ok(repo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("test_git_{}_{}", name, uuid::Uuid::new_v4()))
+    }
+
+    fn commit_file(repo: &Repository, name: &str, content: &str) -> Oid {
+        let workdir = repo.workdir().unwrap();
+        fs::write(workdir.join(name), content).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let parent = head_oid(repo).map(|oid| repo.find_commit(oid).unwrap());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, name, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_sync_fast_forwards_and_detects_divergence() {
+        let upstream_path = temp_path("upstream");
+        let upstream = Repository::init(&upstream_path).unwrap();
+        commit_file(&upstream, "a.txt", "a");
+        let branch = upstream.head().unwrap().shorthand().unwrap().to_string();
+
+        let local_path = temp_path("local");
+        let local = Repository::clone(upstream_path.to_str().unwrap(), &local_path).unwrap();
+
+        let (state, _, _) = sync_branch(&local, &branch, &[]).unwrap();
+        assert_eq!(state, SyncState::UpToDate);
+
+        let new_head = commit_file(&upstream, "b.txt", "b");
+        let (state, ahead, behind) = sync_branch(&local, &branch, &[]).unwrap();
+        assert_eq!(state, SyncState::FastForwarded);
+        assert_eq!((ahead, behind), (0, 1));
+        assert_eq!(head_oid(&local), Some(new_head));
+        assert!(local_path.join("b.txt").exists());
+
+        commit_file(&upstream, "c.txt", "c");
+        commit_file(&local, "d.txt", "d");
+        let local_head = head_oid(&local);
+        let (state, ahead, behind) = sync_branch(&local, &branch, &[]).unwrap();
+        assert_eq!(state, SyncState::Diverged);
+        assert_eq!((ahead, behind), (1, 1));
+        assert_eq!(head_oid(&local), local_head);
+
+        let _ = fs::remove_dir_all(upstream_path);
+        let _ = fs::remove_dir_all(local_path);
+    }
+
+    #[test]
+    fn test_clone_single_branch_sparse_and_submodules() {
+        let root = temp_path("clone_options");
+        let sub_path = root.join("sub");
+        let sub = Repository::init(&sub_path).unwrap();
+        commit_file(&sub, "lib.rs", "pub fn f() {}");
+
+        let upstream_path = root.join("upstream");
+        let upstream = Repository::init(&upstream_path).unwrap();
+        fs::create_dir_all(upstream_path.join("apps/web")).unwrap();
+        fs::create_dir_all(upstream_path.join("docs")).unwrap();
+        commit_file(&upstream, "apps/web/index.ts", "export {}");
+        commit_file(&upstream, "docs/guide.md", "# guide");
+        let branch = upstream.head().unwrap().shorthand().unwrap().to_string();
+        let head = upstream.head().unwrap().peel_to_commit().unwrap();
+        upstream.branch("other", &head, false).unwrap();
+
+        let mut submodule = upstream
+            .submodule(sub_path.to_str().unwrap(), Path::new("libs/sub"), true)
+            .unwrap();
+        submodule.clone(None).unwrap();
+        submodule.add_finalize().unwrap();
+        {
+            let mut index = upstream.index().unwrap();
+            let tree = upstream.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = Signature::now("Test", "test@example.com").unwrap();
+            let parent = upstream.head().unwrap().peel_to_commit().unwrap();
+            upstream
+                .commit(Some("HEAD"), &sig, &sig, "add sub", &tree, &[&parent])
+                .unwrap();
+        }
+
+        let sparse_path = root.join("sparse");
+        let options = CloneOptions {
+            single_branch: true,
+            sparse_paths: vec!["apps/web".to_string()],
+            ..Default::default()
+        };
+        let sparse = clone_repository(
+            upstream_path.to_str().unwrap(),
+            &sparse_path,
+            &options,
+            None,
+            |_, _| true,
+        )
+        .unwrap();
+        assert!(sparse_path.join("apps/web/index.ts").exists());
+        assert!(!sparse_path.join("docs/guide.md").exists());
+        assert_eq!(sparse_patterns(&sparse), vec!["apps/web".to_string()]);
+        assert!(
+            crate::app_service::get_working_tree_status(&sparse)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            sparse
+                .find_branch(&format!("origin/{}", branch), BranchType::Remote)
+                .is_ok()
+        );
+        assert!(
+            sparse
+                .find_branch("origin/other", BranchType::Remote)
+                .is_err()
+        );
+        let submodules = update_submodules(&sparse, &[], None, &mut |_: &str| true).unwrap();
+        assert!(submodules.is_empty());
+
+        let full_path = root.join("full");
+        let full = clone_repository(
+            upstream_path.to_str().unwrap(),
+            &full_path,
+            &CloneOptions::default(),
+            None,
+            |_, _| true,
+        )
+        .unwrap();
+        let submodules = update_submodules(&full, &[], None, &mut |_: &str| true).unwrap();
+        assert_eq!(submodules.len(), 1);
+        assert_eq!(submodules[0].path, full_path.join("libs/sub"));
+        assert!(full_path.join("libs/sub/lib.rs").exists());
+        assert!(submodules[0].head.is_some());
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_repository_name_and_auth_errors() {
+        assert_eq!(
+            repository_name_from_url("git@github.com:org/my-repo.git"),
+            "my-repo"
+        );
+        assert_eq!(
+            repository_name_from_url("https://github.com/org/app/"),
+            "app"
+        );
+
+        let auth = git2::Error::new(ErrorCode::Auth, ErrorClass::Http, "too many redirects");
+        assert!(
+            describe_git_error("clone", "https://x/y", &auth).starts_with("Authentication failed")
+        );
+        let other = git2::Error::new(ErrorCode::NotFound, ErrorClass::Net, "unreachable");
+        assert_eq!(
+            describe_git_error("fetch", "https://x/y", &other),
+            "Failed to fetch https://x/y: unreachable"
+        );
+    }
+
+    #[test]
+    fn test_inspect_and_find_local_repositories() {
+        let root = temp_path("scan");
+        let upstream_path = root.join("upstream");
+        let upstream = Repository::init(&upstream_path).unwrap();
+        let head = commit_file(&upstream, "a.txt", "a");
+        let branch = upstream.head().unwrap().shorthand().unwrap().to_string();
+
+        let cloned_path = root.join("group").join("cloned");
+        Repository::clone(upstream_path.to_str().unwrap(), &cloned_path).unwrap();
+        fs::create_dir_all(root.join("node_modules").join("dep").join(".git")).unwrap();
+
+        let mut found = find_git_repositories(&root, 3);
+        found.sort();
+        assert_eq!(found, vec![cloned_path.clone(), upstream_path.clone()]);
+
+        let info = inspect_local_repository(&cloned_path.join("a.txt")).unwrap();
+        assert_eq!(info.name, "cloned");
+        assert_eq!(info.remote_url, upstream_path.to_str().unwrap());
+        assert_eq!(info.branch, branch);
+        assert_eq!(info.default_branch.as_deref(), Some(branch.as_str()));
+        assert_eq!(info.head, Some(head.to_string()));
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,495 @@
+//! 仓库分析
+//!
+//! 基于提交历史统计作者活跃度、文件变更量（churn）、共同变更耦合
+//! 以及热点文件（变更频繁且体积大），用于决定优先提供给 Agent 的上下文。
+//! 结果按 HEAD 提交缓存在 app.db 中。
+
+use chrono::{TimeZone, Utc};
+use git2::{ObjectType, Patch, Repository, Sort, Tree};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use super::{diff_to_parent, open_repository, path_to_string};
+use crate::app_state::{DatabaseManager, GitRepository};
+
+/// 单个提交修改的文件超过该数量时不计入共同变更（通常是格式化或批量重命名）
+const MAX_COUPLING_FILES: usize = 50;
+
+/// 共同变更次数低于该值的文件对不报告
+const MIN_COUPLING_COUNT: usize = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalyticsOptions {
+    /// 最多分析的提交数（从 HEAD 起按时间倒序）
+    pub max_commits: usize,
+    /// 只分析该时间（毫秒）之后的提交
+    pub since: Option<i64>,
+    /// 每类排行保留的条目数
+    pub top: usize,
+}
+
+impl Default for AnalyticsOptions {
+    fn default() -> Self {
+        Self {
+            max_commits: 1000,
+            since: None,
+            top: 50,
+        }
+    }
+}
+
+impl AnalyticsOptions {
+    fn cache_key(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.max_commits,
+            self.since.unwrap_or(0),
+            self.top
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodCount {
+    /// 月份，如 `2024-05`
+    pub period: String,
+    pub commits: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorActivity {
+    pub name: String,
+    pub email: String,
+    pub commits: usize,
+    pub additions: usize,
+    pub deletions: usize,
+    pub first_commit_at: i64,
+    pub last_commit_at: i64,
+    /// 按月统计的提交数，按时间升序
+    pub timeline: Vec<PeriodCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChurn {
+    pub path: String,
+    pub commits: usize,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoChange {
+    pub path_a: String,
+    pub path_b: String,
+    /// 两个文件在同一提交中被修改的次数
+    pub count: usize,
+    /// count / 两者中较少的提交数，越接近 1 耦合越强
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hotspot {
+    pub path: String,
+    pub commits: usize,
+    /// 新增与删除行数之和
+    pub churn: usize,
+    /// HEAD 中的文件大小（字节）
+    pub size: u64,
+    /// 变更量与大小分别归一化后的乘积，范围 0..=1
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryAnalytics {
+    pub repository_id: String,
+    pub head: String,
+    pub commits_analyzed: usize,
+    /// 是否因 `max_commits` 截断了历史
+    pub truncated: bool,
+    pub authors: Vec<AuthorActivity>,
+    pub churn: Vec<FileChurn>,
+    pub coupling: Vec<CoChange>,
+    pub hotspots: Vec<Hotspot>,
+    pub generated_at: i64,
+}
+
+#[derive(Default)]
+struct AuthorStats {
+    name: String,
+    email: String,
+    commits: usize,
+    additions: usize,
+    deletions: usize,
+    first_commit_at: i64,
+    last_commit_at: i64,
+    timeline: BTreeMap<String, usize>,
+}
+
+fn month_of(time_ms: i64) -> String {
+    Utc.timestamp_millis_opt(time_ms)
+        .single()
+        .map(|t| t.format("%Y-%m").to_string())
+        .unwrap_or_default()
+}
+
+/// 统计提交历史
+pub fn analyze_repository(
+    repo: &Repository,
+    options: &AnalyticsOptions,
+) -> Result<RepositoryAnalytics, String> {
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|e| format!("Failed to resolve HEAD: {}", e))?;
+
+    let mut revwalk = repo
+        .revwalk()
+        .map_err(|e| format!("Failed to walk history: {}", e))?;
+    revwalk
+        .set_sorting(Sort::TIME)
+        .and_then(|_| revwalk.push(head.id()))
+        .map_err(|e| format!("Failed to walk history: {}", e))?;
+
+    let mut authors: HashMap<String, AuthorStats> = HashMap::new();
+    let mut files: HashMap<String, FileChurn> = HashMap::new();
+    let mut pairs: HashMap<(String, String), usize> = HashMap::new();
+    let mut commits_analyzed = 0;
+    let mut truncated = false;
+
+    for oid in revwalk {
+        if commits_analyzed >= options.max_commits {
+            truncated = true;
+            break;
+        }
+        let oid = oid.map_err(|e| format!("Failed to walk history: {}", e))?;
+        let commit = repo
+            .find_commit(oid)
+            .map_err(|e| format!("Failed to read commit {}: {}", oid, e))?;
+        let time = commit.time().seconds() * 1000;
+        if options.since.is_some_and(|since| time < since) {
+            break;
+        }
+        commits_analyzed += 1;
+
+        let signature = commit.author();
+        let email = signature.email().unwrap_or_default().to_lowercase();
+        let author = authors.entry(email.clone()).or_insert_with(|| AuthorStats {
+            // 按时间倒序遍历，第一次遇到的是最新使用的名字
+            name: signature.name().unwrap_or_default().to_string(),
+            email,
+            last_commit_at: time,
+            ..Default::default()
+        });
+        author.commits += 1;
+        author.first_commit_at = time;
+        *author.timeline.entry(month_of(time)).or_default() += 1;
+
+        // 合并提交的改动已体现在被合并的提交中
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let diff = diff_to_parent(repo, &commit, None)
+            .map_err(|e| format!("Failed to diff commit {}: {}", oid, e))?;
+
+        let mut changed = Vec::new();
+        for (idx, delta) in diff.deltas().enumerate() {
+            let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+                continue;
+            };
+            let path = path_to_string(path);
+            let (additions, deletions) = match Patch::from_diff(&diff, idx) {
+                Ok(Some(patch)) => patch
+                    .line_stats()
+                    .map(|(_, added, deleted)| (added, deleted))
+                    .unwrap_or_default(),
+                _ => (0, 0),
+            };
+
+            author.additions += additions;
+            author.deletions += deletions;
+            let churn = files.entry(path.clone()).or_insert_with(|| FileChurn {
+                path: path.clone(),
+                commits: 0,
+                additions: 0,
+                deletions: 0,
+            });
+            churn.commits += 1;
+            churn.additions += additions;
+            churn.deletions += deletions;
+            changed.push(path);
+        }
+
+        if changed.len() >= 2 && changed.len() <= MAX_COUPLING_FILES {
+            changed.sort();
+            for (i, a) in changed.iter().enumerate() {
+                for b in &changed[i + 1..] {
+                    *pairs.entry((a.clone(), b.clone())).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    let mut authors: Vec<AuthorActivity> = authors
+        .into_values()
+        .map(|stats| AuthorActivity {
+            name: stats.name,
+            email: stats.email,
+            commits: stats.commits,
+            additions: stats.additions,
+            deletions: stats.deletions,
+            first_commit_at: stats.first_commit_at,
+            last_commit_at: stats.last_commit_at,
+            timeline: stats
+                .timeline
+                .into_iter()
+                .map(|(period, commits)| PeriodCount { period, commits })
+                .collect(),
+        })
+        .collect();
+    authors.sort_by(|a, b| b.commits.cmp(&a.commits).then(a.email.cmp(&b.email)));
+
+    let mut coupling: Vec<CoChange> = pairs
+        .into_iter()
+        .filter(|(_, count)| *count >= MIN_COUPLING_COUNT)
+        .map(|((path_a, path_b), count)| {
+            let min_commits = files[&path_a].commits.min(files[&path_b].commits).max(1);
+            CoChange {
+                path_a,
+                path_b,
+                count,
+                confidence: count as f64 / min_commits as f64,
+            }
+        })
+        .collect();
+    coupling.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then(b.confidence.total_cmp(&a.confidence))
+            .then(a.path_a.cmp(&b.path_a))
+    });
+    coupling.truncate(options.top);
+
+    let hotspots = hotspots(repo, head.tree().ok().as_ref(), &files, options.top);
+
+    let mut churn: Vec<FileChurn> = files.into_values().collect();
+    churn.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then((b.additions + b.deletions).cmp(&(a.additions + a.deletions)))
+            .then(a.path.cmp(&b.path))
+    });
+    churn.truncate(options.top);
+
+    Ok(RepositoryAnalytics {
+        repository_id: String::new(),
+        head: head.id().to_string(),
+        commits_analyzed,
+        truncated,
+        authors,
+        churn,
+        coupling,
+        hotspots,
+        generated_at: Utc::now().timestamp_millis(),
+    })
+}
+
+/// 只统计 HEAD 中仍然存在的文件
+fn hotspots(
+    repo: &Repository,
+    tree: Option<&Tree>,
+    files: &HashMap<String, FileChurn>,
+    top: usize,
+) -> Vec<Hotspot> {
+    let Some(tree) = tree else {
+        return Vec::new();
+    };
+
+    let mut hotspots: Vec<Hotspot> = files
+        .values()
+        .filter_map(|file| {
+            let entry = tree.get_path(Path::new(&file.path)).ok()?;
+            if entry.kind() != Some(ObjectType::Blob) {
+                return None;
+            }
+            let size = repo.find_blob(entry.id()).ok()?.size() as u64;
+            Some(Hotspot {
+                path: file.path.clone(),
+                commits: file.commits,
+                churn: file.additions + file.deletions,
+                size,
+                score: 0.0,
+            })
+        })
+        .collect();
+
+    let max_churn = hotspots.iter().map(|h| h.churn).max().unwrap_or(0).max(1) as f64;
+    let max_size = hotspots.iter().map(|h| h.size).max().unwrap_or(0).max(1) as f64;
+    for hotspot in &mut hotspots {
+        hotspot.score = (hotspot.churn as f64 / max_churn) * (hotspot.size as f64 / max_size);
+    }
+
+    hotspots.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.path.cmp(&b.path)));
+    hotspots.truncate(top);
+    hotspots
+}
+
+/// 读取或计算仓库分析结果
+///
+/// 缓存以 HEAD 提交和统计参数为键；同步或提交后 HEAD 变化会自动重新计算。
+pub fn repository_analytics(
+    db: &DatabaseManager,
+    git_repo: &GitRepository,
+    options: &AnalyticsOptions,
+    refresh: bool,
+) -> Result<RepositoryAnalytics, String> {
+    let repo = open_repository(&git_repo.local_path)?;
+    let head = repo
+        .head()
+        .ok()
+        .and_then(|head| head.target())
+        .map(|oid| oid.to_string())
+        .or_else(|| git_repo.last_commit_hash.clone())
+        .ok_or_else(|| format!("Repository has no commits: {}", git_repo.name))?;
+    let cache_key = options.cache_key();
+
+    if !refresh
+        && let Ok(Some(cached)) = db.get_repository_analytics(&git_repo.id, &head, &cache_key)
+        && let Ok(analytics) = serde_json::from_str::<RepositoryAnalytics>(&cached)
+    {
+        return Ok(analytics);
+    }
+
+    let mut analytics = analyze_repository(&repo, options)?;
+    analytics.repository_id = git_repo.id.clone();
+
+    match serde_json::to_string(&analytics) {
+        Ok(data) => {
+            if let Err(e) = db.save_repository_analytics(&git_repo.id, &head, &cache_key, &data) {
+                log::warn!("Failed to cache analytics for {}: {}", git_repo.name, e);
+            }
+        }
+        Err(e) => log::warn!("Failed to serialize analytics: {}", e),
+    }
+    Ok(analytics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::{CloneStatus, Workspace};
+    use git2::Signature;
+    use std::env;
+    use std::fs;
+
+    fn commit(repo: &Repository, author: &str, files: &[(&str, &str)], time: i64) {
+        let workdir = repo.workdir().unwrap();
+        let mut index = repo.index().unwrap();
+        for (name, content) in files {
+            fs::write(workdir.join(name), content).unwrap();
+            index.add_path(Path::new(name)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::new(
+            author,
+            &format!("{}@example.com", author.to_lowercase()),
+            &git2::Time::new(time, 0),
+        )
+        .unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, "change", &tree, &parents)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_analytics_and_cache() {
+        let root = env::temp_dir().join(format!("test_analytics_{}", uuid::Uuid::new_v4()));
+        let repo = Repository::init(&root).unwrap();
+        // 2024-01 与 2024-02
+        commit(
+            &repo,
+            "Alice",
+            &[("a.rs", "1\n"), ("b.rs", "1\n")],
+            1704153600,
+        );
+        commit(
+            &repo,
+            "Bob",
+            &[("a.rs", "1\n2\n"), ("b.rs", "1\n2\n")],
+            1706832000,
+        );
+        commit(
+            &repo,
+            "Alice",
+            &[("a.rs", "1\n2\n3\n"), ("c.rs", "x\n")],
+            1706918400,
+        );
+
+        let analytics = analyze_repository(&repo, &AnalyticsOptions::default()).unwrap();
+        assert_eq!(analytics.commits_analyzed, 3);
+        assert!(!analytics.truncated);
+
+        let alice = &analytics.authors[0];
+        assert_eq!(alice.email, "alice@example.com");
+        assert_eq!(alice.commits, 2);
+        let periods: Vec<_> = alice.timeline.iter().map(|p| p.period.as_str()).collect();
+        assert_eq!(periods, vec!["2024-01", "2024-02"]);
+
+        assert_eq!(analytics.churn[0].path, "a.rs");
+        assert_eq!(analytics.churn[0].commits, 3);
+        assert_eq!(analytics.churn[0].additions, 3);
+
+        assert_eq!(analytics.coupling.len(), 1);
+        assert_eq!(analytics.coupling[0].path_a, "a.rs");
+        assert_eq!(analytics.coupling[0].path_b, "b.rs");
+        assert_eq!(analytics.coupling[0].confidence, 1.0);
+
+        assert_eq!(analytics.hotspots[0].path, "a.rs");
+        assert_eq!(analytics.hotspots[0].score, 1.0);
+
+        let truncated = analyze_repository(
+            &repo,
+            &AnalyticsOptions {
+                max_commits: 2,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(truncated.truncated);
+        assert_eq!(truncated.commits_analyzed, 2);
+
+        let db_path = env::temp_dir().join(format!("test_analytics_{}.db", uuid::Uuid::new_v4()));
+        let db = DatabaseManager::new(db_path).unwrap();
+        let workspace = Workspace::new("Test".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+        let mut git_repo = GitRepository::new(
+            workspace.id.clone(),
+            "repo".to_string(),
+            String::new(),
+            root.clone(),
+            "main".to_string(),
+        );
+        git_repo.clone_status = CloneStatus::Completed;
+        db.create_git_repository(&git_repo).unwrap();
+
+        let options = AnalyticsOptions::default();
+        let first = repository_analytics(&db, &git_repo, &options, false).unwrap();
+        let cached = repository_analytics(&db, &git_repo, &options, false).unwrap();
+        assert_eq!(first.generated_at, cached.generated_at);
+        assert_eq!(cached.repository_id, git_repo.id);
+
+        commit(&repo, "Bob", &[("c.rs", "y\n")], 1707004800);
+        let updated = repository_analytics(&db, &git_repo, &options, false).unwrap();
+        assert_eq!(updated.commits_analyzed, 4);
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
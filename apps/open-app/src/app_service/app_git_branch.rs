@@ -0,0 +1,442 @@
+//! Git 分支、标签与工作树管理
+
+use git2::{
+    BranchType, ErrorCode, ObjectType, Repository, WorktreeAddOptions, WorktreeLockStatus,
+    WorktreePruneOptions,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::{checkout_builder, refresh_sparse_index};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBranch {
+    /// 本地分支为 `main`，远程分支为 `origin/main`
+    pub name: String,
+    pub is_remote: bool,
+    pub is_head: bool,
+    pub commit_id: Option<String>,
+    /// 本地分支跟踪的上游分支
+    pub upstream: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitTag {
+    pub name: String,
+    /// 标签最终指向的提交
+    pub commit_id: Option<String>,
+    /// 附注标签的说明，轻量标签为空
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitWorktree {
+    pub name: String,
+    pub path: PathBuf,
+    pub branch: Option<String>,
+    pub is_locked: bool,
+    /// 工作树目录是否仍然存在且有效
+    pub is_valid: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutResult {
+    pub branch: String,
+    /// 是否新建了本地分支
+    pub created: bool,
+    pub previous_head: Option<String>,
+    pub head: Option<String>,
+    pub head_moved: bool,
+}
+
+pub fn list_branches(repo: &Repository) -> Result<Vec<GitBranch>, String> {
+    let branches = repo
+        .branches(None)
+        .map_err(|e| format!("Failed to list branches: {}", e))?;
+
+    let mut result = Vec::new();
+    for branch in branches {
+        let (branch, branch_type) = branch.map_err(|e| format!("Failed to read branch: {}", e))?;
+        let Ok(Some(name)) = branch.name() else {
+            continue;
+        };
+        // 跳过 origin/HEAD 这类符号引用
+        if branch.get().symbolic_target().is_some() {
+            continue;
+        }
+
+        let upstream = branch
+            .upstream()
+            .ok()
+            .and_then(|u| u.name().ok().flatten().map(str::to_string));
+        result.push(GitBranch {
+            name: name.to_string(),
+            is_remote: branch_type == BranchType::Remote,
+            is_head: branch.is_head(),
+            commit_id: branch.get().target().map(|oid| oid.to_string()),
+            upstream,
+        });
+    }
+
+    result.sort_by(|a, b| a.is_remote.cmp(&b.is_remote).then(a.name.cmp(&b.name)));
+    Ok(result)
+}
+
+pub fn list_tags(repo: &Repository) -> Result<Vec<GitTag>, String> {
+    let names = repo
+        .tag_names(None)
+        .map_err(|e| format!("Failed to list tags: {}", e))?;
+
+    let mut tags = Vec::new();
+    for name in names.iter().flatten() {
+        let Ok(object) = repo.revparse_single(&format!("refs/tags/{}", name)) else {
+            continue;
+        };
+        let message = match object.kind() {
+            Some(ObjectType::Tag) => object
+                .as_tag()
+                .and_then(|tag| tag.message())
+                .map(|m| m.trim().to_string()),
+            _ => None,
+        };
+        tags.push(GitTag {
+            name: name.to_string(),
+            commit_id: object.peel_to_commit().ok().map(|c| c.id().to_string()),
+            message,
+        });
+    }
+    Ok(tags)
+}
+
+/// 检出分支
+///
+/// 本地分支不存在时，若存在同名远程分支则从它创建并设置上游；
+/// 否则仅在 `create` 为 true 时从 `start_point`（默认 HEAD）新建。
+pub fn checkout_branch(
+    repo: &Repository,
+    name: &str,
+    create: bool,
+    start_point: Option<&str>,
+) -> Result<CheckoutResult, String> {
+    let previous_head = repo.head().ok().and_then(|h| h.target());
+    let mut created = false;
+
+    let mut branch = match repo.find_branch(name, BranchType::Local) {
+        Ok(branch) => branch,
+        Err(_) => {
+            let remote_name = format!("origin/{}", name);
+            let remote = repo.find_branch(&remote_name, BranchType::Remote).ok();
+            if remote.is_none() && !create {
+                return Err(format!("Branch not found: {}", name));
+            }
+
+            let start = match (start_point, &remote) {
+                (Some(rev), _) => repo
+                    .revparse_single(rev)
+                    .and_then(|object| object.peel_to_commit())
+                    .map_err(|e| format!("Revision not found: {} ({})", rev, e))?,
+                (None, Some(remote)) => remote
+                    .get()
+                    .peel_to_commit()
+                    .map_err(|e| format!("Failed to resolve {}: {}", remote_name, e))?,
+                (None, None) => repo
+                    .head()
+                    .and_then(|head| head.peel_to_commit())
+                    .map_err(|e| format!("Failed to resolve HEAD: {}", e))?,
+            };
+
+            let mut branch = repo
+                .branch(name, &start, false)
+                .map_err(|e| format!("Failed to create branch {}: {}", name, e))?;
+            if remote.is_some() && start_point.is_none() {
+                branch
+                    .set_upstream(Some(&remote_name))
+                    .map_err(|e| format!("Failed to set upstream for {}: {}", name, e))?;
+            }
+            created = true;
+            branch
+        }
+    };
+
+    let refname = branch
+        .get()
+        .name()
+        .ok_or_else(|| format!("Invalid branch name: {}", name))?
+        .to_string();
+    let target = branch
+        .get()
+        .peel(ObjectType::Commit)
+        .map_err(|e| format!("Failed to resolve branch {}: {}", name, e))?;
+
+    // 先确认 HEAD 可以切换，避免工作区已检出而 HEAD 未移动
+    if let Some(worktree) = worktree_using_branch(repo, &refname) {
+        return Err(format!(
+            "Branch {} is checked out in worktree {}",
+            name, worktree
+        ));
+    }
+
+    let switched = repo
+        .checkout_tree(&target, Some(&mut checkout_builder(repo)))
+        .map_err(|e| match e.code() {
+            ErrorCode::Conflict => format!("Local changes would be overwritten by checkout: {}", e),
+            _ => format!("Failed to check out {} ({:?}): {}", name, e.class(), e),
+        })
+        .and_then(|()| {
+            repo.set_head(&refname)
+                .map_err(|e| format!("Failed to switch to {}: {}", name, e))
+        });
+    if let Err(e) = switched {
+        // 检出失败时删除本次新建的分支
+        if created && let Err(err) = branch.delete() {
+            log::warn!("Failed to delete branch {}: {}", name, err);
+        }
+        return Err(e);
+    }
+    refresh_sparse_index(repo)?;
+
+    let head = repo.head().ok().and_then(|h| h.target());
+    Ok(CheckoutResult {
+        branch: name.to_string(),
+        created,
+        previous_head: previous_head.map(|oid| oid.to_string()),
+        head: head.map(|oid| oid.to_string()),
+        head_moved: previous_head != head,
+    })
+}
+
+/// 检出了 `refname` 的其他工作树名称
+fn worktree_using_branch(repo: &Repository, refname: &str) -> Option<String> {
+    let names = repo.worktrees().ok()?;
+    names
+        .iter()
+        .flatten()
+        .find(|name| {
+            repo.find_worktree(name)
+                .ok()
+                .and_then(|worktree| Repository::open_from_worktree(&worktree).ok())
+                .and_then(|wt_repo| {
+                    wt_repo
+                        .head()
+                        .ok()
+                        .and_then(|h| h.name().map(str::to_string))
+                })
+                .is_some_and(|head| head == refname)
+        })
+        .map(str::to_string)
+}
+
+pub fn list_worktrees(repo: &Repository) -> Result<Vec<GitWorktree>, String> {
+    let names = repo
+        .worktrees()
+        .map_err(|e| format!("Failed to list worktrees: {}", e))?;
+
+    let mut worktrees = Vec::new();
+    for name in names.iter().flatten() {
+        let worktree = repo
+            .find_worktree(name)
+            .map_err(|e| format!("Failed to open worktree {}: {}", name, e))?;
+        let is_valid = worktree.validate().is_ok();
+        let branch = if is_valid {
+            Repository::open_from_worktree(&worktree)
+                .ok()
+                .and_then(|wt_repo| {
+                    wt_repo
+                        .head()
+                        .ok()
+                        .and_then(|h| h.shorthand().map(str::to_string))
+                })
+        } else {
+            None
+        };
+
+        worktrees.push(GitWorktree {
+            name: name.to_string(),
+            path: worktree.path().to_path_buf(),
+            branch,
+            is_locked: matches!(worktree.is_locked(), Ok(WorktreeLockStatus::Locked(_))),
+            is_valid,
+        });
+    }
+    Ok(worktrees)
+}
+
+/// 新建工作树；指定的分支不存在时从 HEAD 创建
+pub fn add_worktree(
+    repo: &Repository,
+    name: &str,
+    path: &Path,
+    branch: Option<&str>,
+) -> Result<GitWorktree, String> {
+    if path.exists() {
+        return Err(format!("Path already exists: {}", path.display()));
+    }
+
+    let reference = match branch {
+        Some(branch) => {
+            let local = match repo.find_branch(branch, BranchType::Local) {
+                Ok(local) => local,
+                Err(_) => {
+                    let head = repo
+                        .head()
+                        .and_then(|head| head.peel_to_commit())
+                        .map_err(|e| format!("Failed to resolve HEAD: {}", e))?;
+                    repo.branch(branch, &head, false)
+                        .map_err(|e| format!("Failed to create branch {}: {}", branch, e))?
+                }
+            };
+            Some(local.into_reference())
+        }
+        None => None,
+    };
+
+    let mut opts = WorktreeAddOptions::new();
+    opts.reference(reference.as_ref());
+    let worktree = repo
+        .worktree(name, path, Some(&opts))
+        .map_err(|e| format!("Failed to add worktree {}: {}", name, e))?;
+
+    let branch = Repository::open_from_worktree(&worktree)
+        .ok()
+        .and_then(|wt_repo| {
+            wt_repo
+                .head()
+                .ok()
+                .and_then(|h| h.shorthand().map(str::to_string))
+        });
+    Ok(GitWorktree {
+        name: name.to_string(),
+        path: worktree.path().to_path_buf(),
+        branch,
+        is_locked: false,
+        is_valid: true,
+    })
+}
+
+/// 移除工作树及其目录；已锁定的工作树需要 `force`
+pub fn remove_worktree(repo: &Repository, name: &str, force: bool) -> Result<(), String> {
+    let worktree = repo
+        .find_worktree(name)
+        .map_err(|_| format!("Worktree not found: {}", name))?;
+
+    if !force && matches!(worktree.is_locked(), Ok(WorktreeLockStatus::Locked(_))) {
+        return Err(format!("Worktree is locked: {}", name));
+    }
+
+    let mut opts = WorktreePruneOptions::new();
+    opts.valid(true).locked(force).working_tree(true);
+    worktree
+        .prune(Some(&mut opts))
+        .map_err(|e| format!("Failed to remove worktree {}: {}", name, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use std::env;
+    use std::fs;
+
+    fn init_repo_with_commit(path: &Path) -> Repository {
+        let repo = Repository::init(path).unwrap();
+        fs::write(path.join("a.txt"), "a").unwrap();
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("a.txt")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = Signature::now("Test", "test@example.com").unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+                .unwrap();
+        }
+        repo
+    }
+
+    #[test]
+    fn test_branches_tags_and_worktrees() {
+        let root = env::temp_dir().join(format!("test_git_branch_{}", uuid::Uuid::new_v4()));
+        let repo_path = root.join("repo");
+        fs::create_dir_all(&repo_path).unwrap();
+        let repo = init_repo_with_commit(&repo_path);
+        let default_branch = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        repo.tag("v1.0.0", head.as_object(), &sig, "first release", false)
+            .unwrap();
+        repo.tag_lightweight("light", head.as_object(), false)
+            .unwrap();
+
+        let tags = list_tags(&repo).unwrap();
+        assert_eq!(tags.len(), 2);
+        let release = tags.iter().find(|t| t.name == "v1.0.0").unwrap();
+        assert_eq!(release.message.as_deref(), Some("first release"));
+        assert_eq!(release.commit_id, Some(head.id().to_string()));
+
+        assert!(checkout_branch(&repo, "feature", false, None).is_err());
+        let result = checkout_branch(&repo, "feature", true, None).unwrap();
+        assert!(result.created);
+        assert!(!result.head_moved);
+
+        let branches = list_branches(&repo).unwrap();
+        let names: Vec<_> = branches.iter().map(|b| b.name.as_str()).collect();
+        assert!(names.contains(&"feature"));
+        assert!(names.contains(&default_branch.as_str()));
+        assert!(
+            branches
+                .iter()
+                .find(|b| b.name == "feature")
+                .unwrap()
+                .is_head
+        );
+
+        let wt_path = root.join("wt-review");
+        let worktree = add_worktree(&repo, "review", &wt_path, Some("review")).unwrap();
+        assert_eq!(worktree.branch.as_deref(), Some("review"));
+        assert!(wt_path.join("a.txt").exists());
+        assert_eq!(list_worktrees(&repo).unwrap().len(), 1);
+        // 已在其他工作树中检出的分支不能切换，工作区和 HEAD 保持不变
+        let err = checkout_branch(&repo, "review", false, None).unwrap_err();
+        assert!(err.contains("worktree"), "{}", err);
+        assert_eq!(repo.head().unwrap().shorthand(), Some("feature"));
+
+        // 检出失败时不残留新建的分支
+        let other = {
+            let mut index = repo.index().unwrap();
+            let blob = repo.blob(b"other").unwrap();
+            let mut entry = index.get_path(Path::new("a.txt"), 0).unwrap();
+            entry.id = blob;
+            entry.file_size = 5;
+            index.add(&entry).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(None, &sig, &sig, "other", &tree, &[&parent])
+                .unwrap()
+        };
+        repo.reset(
+            repo.head()
+                .unwrap()
+                .peel(ObjectType::Commit)
+                .as_ref()
+                .unwrap(),
+            git2::ResetType::Mixed,
+            None,
+        )
+        .unwrap();
+        fs::write(repo_path.join("a.txt"), "dirty").unwrap();
+        let err = checkout_branch(&repo, "broken", true, Some(&other.to_string())).unwrap_err();
+        assert!(err.contains("Local changes"), "{}", err);
+        assert!(repo.find_branch("broken", BranchType::Local).is_err());
+        fs::write(repo_path.join("a.txt"), "a").unwrap();
+
+        remove_worktree(&repo, "review", false).unwrap();
+        assert!(list_worktrees(&repo).unwrap().is_empty());
+        assert!(!wt_path.exists());
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
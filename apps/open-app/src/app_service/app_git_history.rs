@@ -0,0 +1,397 @@
+//! Git 历史浏览
+//!
+//! 提供分页提交日志（按路径、作者过滤）、提交详情（变更文件与统一 diff）
+//! 以及逐行 blame。
+
+use git2::{Commit, Delta, Diff, DiffFormat, DiffOptions, Oid, Patch, Repository, Sort};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitSummary {
+    pub id: String,
+    pub short_id: String,
+    pub summary: String,
+    pub message: String,
+    pub author_name: String,
+    pub author_email: String,
+    /// 提交时间（毫秒）
+    pub time: i64,
+    pub parent_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CommitLogQuery {
+    /// 起始修订（分支、标签或提交），为空时从 HEAD 开始
+    pub rev: Option<String>,
+    /// 只保留修改了该路径（文件或目录）的提交
+    pub path: Option<String>,
+    /// 作者名或邮箱，大小写不敏感的子串匹配
+    pub author: Option<String>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitLogPage {
+    pub commits: Vec<CommitSummary>,
+    pub offset: usize,
+    pub limit: usize,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangedFile {
+    pub path: String,
+    pub old_path: Option<String>,
+    /// added / deleted / modified / renamed / copied / typechange
+    pub status: String,
+    pub additions: usize,
+    pub deletions: usize,
+    pub binary: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitDetail {
+    pub commit: CommitSummary,
+    pub files: Vec<ChangedFile>,
+    /// 相对第一个父提交的统一 diff
+    pub diff: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlameLine {
+    /// 从 1 开始的行号
+    pub line_number: usize,
+    pub content: String,
+    pub commit_id: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub time: i64,
+    pub summary: String,
+}
+
+pub(crate) fn commit_summary(commit: &Commit) -> CommitSummary {
+    let id = commit.id().to_string();
+    let author = commit.author();
+    CommitSummary {
+        short_id: id.chars().take(7).collect(),
+        id,
+        summary: commit.summary().unwrap_or_default().to_string(),
+        message: commit.message().unwrap_or_default().to_string(),
+        author_name: author.name().unwrap_or_default().to_string(),
+        author_email: author.email().unwrap_or_default().to_string(),
+        time: commit.time().seconds() * 1000,
+        parent_ids: commit.parent_ids().map(|oid| oid.to_string()).collect(),
+    }
+}
+
+/// 提交相对第一个父提交的 diff（根提交与空树比较）
+pub(crate) fn diff_to_parent<'r>(
+    repo: &'r Repository,
+    commit: &Commit,
+    opts: Option<&mut DiffOptions>,
+) -> Result<Diff<'r>, git2::Error> {
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), opts)
+}
+
+fn touches_path(repo: &Repository, commit: &Commit, path: &str) -> Result<bool, git2::Error> {
+    let mut opts = DiffOptions::new();
+    opts.pathspec(path);
+    let diff = diff_to_parent(repo, commit, Some(&mut opts))?;
+    Ok(diff.deltas().len() > 0)
+}
+
+fn matches_author(commit: &Commit, author: &str) -> bool {
+    let needle = author.to_lowercase();
+    let signature = commit.author();
+    [signature.name(), signature.email()]
+        .into_iter()
+        .flatten()
+        .any(|value| value.to_lowercase().contains(&needle))
+}
+
+pub fn list_commits(repo: &Repository, query: &CommitLogQuery) -> Result<CommitLogPage, String> {
+    let mut revwalk = repo
+        .revwalk()
+        .map_err(|e| format!("Failed to walk history: {}", e))?;
+    revwalk
+        .set_sorting(Sort::TIME)
+        .map_err(|e| format!("Failed to walk history: {}", e))?;
+
+    match query.rev.as_deref().filter(|rev| !rev.is_empty()) {
+        Some(rev) => {
+            let object = repo
+                .revparse_single(rev)
+                .map_err(|e| format!("Revision not found: {} ({})", rev, e))?;
+            let commit = object
+                .peel_to_commit()
+                .map_err(|e| format!("Revision is not a commit: {} ({})", rev, e))?;
+            revwalk
+                .push(commit.id())
+                .map_err(|e| format!("Failed to walk history: {}", e))?;
+        }
+        None => revwalk
+            .push_head()
+            .map_err(|e| format!("Failed to walk history: {}", e))?,
+    }
+
+    let path = query.path.as_deref().filter(|p| !p.is_empty());
+    let author = query.author.as_deref().filter(|a| !a.is_empty());
+
+    let mut commits = Vec::new();
+    let mut matched = 0;
+    let mut has_more = false;
+
+    for oid in revwalk {
+        let oid = oid.map_err(|e| format!("Failed to walk history: {}", e))?;
+        let commit = repo
+            .find_commit(oid)
+            .map_err(|e| format!("Failed to read commit {}: {}", oid, e))?;
+
+        if let Some(author) = author
+            && !matches_author(&commit, author)
+        {
+            continue;
+        }
+        if let Some(path) = path
+            && !touches_path(repo, &commit, path)
+                .map_err(|e| format!("Failed to diff commit {}: {}", oid, e))?
+        {
+            continue;
+        }
+
+        matched += 1;
+        if matched <= query.offset {
+            continue;
+        }
+        if commits.len() == query.limit {
+            has_more = true;
+            break;
+        }
+        commits.push(commit_summary(&commit));
+    }
+
+    Ok(CommitLogPage {
+        commits,
+        offset: query.offset,
+        limit: query.limit,
+        has_more,
+    })
+}
+
+fn delta_status(delta: Delta) -> &'static str {
+    match delta {
+        Delta::Added | Delta::Untracked => "added",
+        Delta::Deleted => "deleted",
+        Delta::Renamed => "renamed",
+        Delta::Copied => "copied",
+        Delta::Typechange => "typechange",
+        _ => "modified",
+    }
+}
+
+pub fn get_commit_detail(repo: &Repository, commit_id: &str) -> Result<CommitDetail, String> {
+    let commit = repo
+        .revparse_single(commit_id)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|e| format!("Commit not found: {} ({})", commit_id, e))?;
+
+    let mut diff = diff_to_parent(repo, &commit, None)
+        .map_err(|e| format!("Failed to diff commit {}: {}", commit_id, e))?;
+    diff.find_similar(None)
+        .map_err(|e| format!("Failed to detect renames: {}", e))?;
+
+    let mut files = Vec::new();
+    for (idx, delta) in diff.deltas().enumerate() {
+        let new_path = delta.new_file().path().map(path_to_string);
+        let old_path = delta.old_file().path().map(path_to_string);
+        let (additions, deletions) = match Patch::from_diff(&diff, idx) {
+            Ok(Some(patch)) => patch
+                .line_stats()
+                .map(|(_, added, deleted)| (added, deleted))
+                .unwrap_or_default(),
+            _ => (0, 0),
+        };
+
+        files.push(ChangedFile {
+            path: new_path.clone().or(old_path.clone()).unwrap_or_default(),
+            old_path: old_path.filter(|old| Some(old) != new_path.as_ref()),
+            status: delta_status(delta.status()).to_string(),
+            additions,
+            deletions,
+            binary: delta.flags().is_binary(),
+        });
+    }
+
+    let mut patch = String::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin());
+        }
+        patch.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })
+    .map_err(|e| format!("Failed to render diff: {}", e))?;
+
+    Ok(CommitDetail {
+        commit: commit_summary(&commit),
+        files,
+        diff: patch,
+    })
+}
+
+pub(crate) fn path_to_string(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// 对 HEAD 中的文件做逐行 blame
+pub fn blame_file(repo: &Repository, file_path: &str) -> Result<Vec<BlameLine>, String> {
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|e| format!("Failed to resolve HEAD: {}", e))?;
+    let entry = head
+        .tree()
+        .and_then(|tree| tree.get_path(Path::new(file_path)))
+        .map_err(|_| format!("File not found in HEAD: {}", file_path))?;
+    let blob = repo
+        .find_blob(entry.id())
+        .map_err(|e| format!("Failed to read file {}: {}", file_path, e))?;
+    if blob.is_binary() {
+        return Err(format!("Cannot blame binary file: {}", file_path));
+    }
+
+    let blame = repo
+        .blame_file(Path::new(file_path), None)
+        .map_err(|e| format!("Failed to blame {}: {}", file_path, e))?;
+
+    let content = String::from_utf8_lossy(blob.content());
+    let mut summaries: HashMap<Oid, String> = HashMap::new();
+    let mut lines = Vec::new();
+
+    for (idx, line) in content.lines().enumerate() {
+        let line_number = idx + 1;
+        let Some(hunk) = blame.get_line(line_number) else {
+            continue;
+        };
+        let commit_oid = hunk.final_commit_id();
+        let signature = hunk.final_signature();
+        let summary = summaries
+            .entry(commit_oid)
+            .or_insert_with(|| {
+                repo.find_commit(commit_oid)
+                    .ok()
+                    .and_then(|c| c.summary().map(str::to_string))
+                    .unwrap_or_default()
+            })
+            .clone();
+
+        lines.push(BlameLine {
+            line_number,
+            content: line.to_string(),
+            commit_id: commit_oid.to_string(),
+            author_name: signature.name().unwrap_or_default().to_string(),
+            author_email: signature.email().unwrap_or_default().to_string(),
+            time: signature.when().seconds() * 1000,
+            summary,
+        });
+    }
+
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use std::env;
+    use std::fs;
+
+    fn commit_as(repo: &Repository, author: &str, name: &str, content: &str, message: &str) -> Oid {
+        fs::write(repo.workdir().unwrap().join(name), content).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now(author, &format!("{}@example.com", author)).unwrap();
+        let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_log_detail_and_blame() {
+        let path = env::temp_dir().join(format!("test_git_history_{}", uuid::Uuid::new_v4()));
+        let repo = Repository::init(&path).unwrap();
+
+        commit_as(&repo, "alice", "a.txt", "one\n", "add a");
+        commit_as(&repo, "bob", "b.txt", "bee\n", "add b");
+        let last = commit_as(&repo, "bob", "a.txt", "one\ntwo\n", "extend a");
+
+        let page = list_commits(
+            &repo,
+            &CommitLogQuery {
+                limit: 2,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(page.commits.len(), 2);
+        assert!(page.has_more);
+
+        let by_path = list_commits(
+            &repo,
+            &CommitLogQuery {
+                path: Some("a.txt".to_string()),
+                limit: 10,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let summaries: Vec<_> = by_path.commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["extend a", "add a"]);
+
+        let by_author = list_commits(
+            &repo,
+            &CommitLogQuery {
+                author: Some("ALICE".to_string()),
+                limit: 10,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(by_author.commits.len(), 1);
+
+        let detail = get_commit_detail(&repo, &last.to_string()).unwrap();
+        assert_eq!(detail.files.len(), 1);
+        assert_eq!(detail.files[0].path, "a.txt");
+        assert_eq!(detail.files[0].status, "modified");
+        assert_eq!(
+            (detail.files[0].additions, detail.files[0].deletions),
+            (1, 0)
+        );
+        assert!(detail.diff.contains("+two"));
+
+        let blame = blame_file(&repo, "a.txt").unwrap();
+        assert_eq!(blame.len(), 2);
+        assert_eq!(blame[0].author_name, "alice");
+        assert_eq!(blame[1].author_name, "bob");
+        assert_eq!(blame[1].commit_id, last.to_string());
+
+        let _ = fs::remove_dir_all(path);
+    }
+}
@@ -0,0 +1,425 @@
+//! Git 工作区状态
+//!
+//! 逐文件的工作区 / 暂存区状态、暂存与取消暂存、丢弃修改和提交，
+//! 以及供文件树展示的 git 装饰状态。
+
+use git2::{
+    Index, IndexAddOption, IndexEntryExtendedFlag, Repository, Status, StatusOptions,
+    build::CheckoutBuilder,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::{CommitSummary, commit_summary};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GitFileStatus {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Typechange,
+    Untracked,
+    Ignored,
+    Conflicted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkingTreeEntry {
+    /// 仓库内相对路径
+    pub path: String,
+    /// 暂存区相对 HEAD 的状态
+    pub index_status: Option<GitFileStatus>,
+    /// 工作区相对暂存区的状态
+    pub worktree_status: Option<GitFileStatus>,
+}
+
+fn index_status(status: Status) -> Option<GitFileStatus> {
+    if status.is_index_new() {
+        Some(GitFileStatus::Added)
+    } else if status.is_index_deleted() {
+        Some(GitFileStatus::Deleted)
+    } else if status.is_index_renamed() {
+        Some(GitFileStatus::Renamed)
+    } else if status.is_index_typechange() {
+        Some(GitFileStatus::Typechange)
+    } else if status.is_index_modified() {
+        Some(GitFileStatus::Modified)
+    } else {
+        None
+    }
+}
+
+fn worktree_status(status: Status) -> Option<GitFileStatus> {
+    if status.is_conflicted() {
+        Some(GitFileStatus::Conflicted)
+    } else if status.is_wt_new() {
+        Some(GitFileStatus::Untracked)
+    } else if status.is_wt_deleted() {
+        Some(GitFileStatus::Deleted)
+    } else if status.is_wt_renamed() {
+        Some(GitFileStatus::Renamed)
+    } else if status.is_wt_typechange() {
+        Some(GitFileStatus::Typechange)
+    } else if status.is_wt_modified() {
+        Some(GitFileStatus::Modified)
+    } else {
+        None
+    }
+}
+
+/// 文件树上展示的单一状态，工作区状态优先
+fn decoration(status: Status) -> Option<GitFileStatus> {
+    if status.is_ignored() {
+        return Some(GitFileStatus::Ignored);
+    }
+    worktree_status(status).or_else(|| index_status(status))
+}
+
+/// 稀疏检出范围外的文件带有 skip-worktree 标记，它们在工作区缺失属于正常情况
+fn is_skipped_worktree(index: Option<&Index>, path: &str, status: Status) -> bool {
+    status == Status::WT_DELETED
+        && index
+            .and_then(|index| index.get_path(Path::new(path), 0))
+            .is_some_and(|entry| {
+                IndexEntryExtendedFlag::from_bits_truncate(entry.flags_extended).is_skip_worktree()
+            })
+}
+
+pub fn get_working_tree_status(repo: &Repository) -> Result<Vec<WorkingTreeEntry>, String> {
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false)
+        .renames_head_to_index(true);
+
+    let statuses = repo
+        .statuses(Some(&mut opts))
+        .map_err(|e| format!("Failed to read git status: {}", e))?;
+    let index = repo.index().ok();
+
+    let mut entries: Vec<WorkingTreeEntry> = statuses
+        .iter()
+        .filter_map(|entry| {
+            let path = entry.path()?.to_string();
+            let status = entry.status();
+            if is_skipped_worktree(index.as_ref(), &path, status) {
+                return None;
+            }
+            Some(WorkingTreeEntry {
+                path,
+                index_status: index_status(status),
+                worktree_status: worktree_status(status),
+            })
+        })
+        .filter(|entry| entry.index_status.is_some() || entry.worktree_status.is_some())
+        .collect();
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+/// 暂存文件；工作区中已删除的文件会从暂存区移除
+pub fn stage_paths(repo: &Repository, paths: &[String]) -> Result<(), String> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| "Cannot stage in a bare repository".to_string())?;
+    let mut index = repo
+        .index()
+        .map_err(|e| format!("Failed to read index: {}", e))?;
+
+    for path in paths {
+        let result = if workdir.join(path).is_dir() {
+            index.add_all([path.as_str()], IndexAddOption::DEFAULT, None)
+        } else if workdir.join(path).exists() {
+            index.add_path(Path::new(path))
+        } else {
+            index.remove_path(Path::new(path))
+        };
+        result.map_err(|e| format!("Failed to stage {}: {}", path, e))?;
+    }
+
+    index
+        .write()
+        .map_err(|e| format!("Failed to write index: {}", e))
+}
+
+/// 取消暂存，将暂存区中的条目恢复为 HEAD 中的版本
+pub fn unstage_paths(repo: &Repository, paths: &[String]) -> Result<(), String> {
+    match repo.head().and_then(|head| head.peel_to_commit()) {
+        Ok(head) => repo
+            .reset_default(Some(head.as_object()), paths.iter().map(String::as_str))
+            .map_err(|e| format!("Failed to unstage: {}", e)),
+        Err(_) => {
+            // 尚无提交时直接从暂存区移除
+            let mut index = repo
+                .index()
+                .map_err(|e| format!("Failed to read index: {}", e))?;
+            for path in paths {
+                index
+                    .remove_path(Path::new(path))
+                    .map_err(|e| format!("Failed to unstage {}: {}", path, e))?;
+            }
+            index
+                .write()
+                .map_err(|e| format!("Failed to write index: {}", e))
+        }
+    }
+}
+
+/// 丢弃工作区修改：已跟踪文件恢复为暂存区版本，未跟踪文件直接删除
+pub fn discard_paths(repo: &Repository, paths: &[String]) -> Result<(), String> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| "Cannot discard in a bare repository".to_string())?
+        .to_path_buf();
+
+    let mut tracked = Vec::new();
+    for path in paths {
+        let status = repo
+            .status_file(Path::new(path))
+            .map_err(|e| format!("Failed to read status of {}: {}", path, e))?;
+        if status.is_wt_new() {
+            let full_path = workdir.join(path);
+            if full_path.is_dir() {
+                fs::remove_dir_all(&full_path)
+            } else {
+                fs::remove_file(&full_path)
+            }
+            .map_err(|e| format!("Failed to remove {}: {}", path, e))?;
+        } else {
+            tracked.push(path);
+        }
+    }
+
+    if tracked.is_empty() {
+        return Ok(());
+    }
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force();
+    for path in tracked {
+        checkout.path(path);
+    }
+    repo.checkout_index(None, Some(&mut checkout))
+        .map_err(|e| format!("Failed to discard changes: {}", e))
+}
+
+/// 提交暂存区，作者信息取自 git 配置（user.name / user.email）
+pub fn commit_staged(repo: &Repository, message: &str) -> Result<CommitSummary, String> {
+    if message.trim().is_empty() {
+        return Err("Commit message cannot be empty".to_string());
+    }
+
+    let signature = repo.signature().map_err(|e| {
+        format!(
+            "Git author is not configured, set user.name and user.email: {}",
+            e
+        )
+    })?;
+
+    let mut index = repo
+        .index()
+        .map_err(|e| format!("Failed to read index: {}", e))?;
+    let tree_id = index
+        .write_tree()
+        .map_err(|e| format!("Failed to write tree: {}", e))?;
+    let tree = repo
+        .find_tree(tree_id)
+        .map_err(|e| format!("Failed to find tree: {}", e))?;
+
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    if let Some(parent) = &parent
+        && parent.tree_id() == tree_id
+    {
+        return Err("Nothing to commit".to_string());
+    }
+
+    let parents: Vec<_> = parent.iter().collect();
+    let oid = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .map_err(|e| format!("Failed to commit: {}", e))?;
+    let commit = repo
+        .find_commit(oid)
+        .map_err(|e| format!("Failed to read commit {}: {}", oid, e))?;
+    Ok(commit_summary(&commit))
+}
+
+/// 计算目录下直接子项的 git 装饰状态，键为子项名称
+///
+/// 目录不在 Git 仓库中时返回空表；子目录内有变更时目录标记为 modified。
+pub fn git_decorations(dir: &Path) -> HashMap<String, GitFileStatus> {
+    let mut decorations = HashMap::new();
+
+    let Ok(repo) = Repository::discover(dir) else {
+        return decorations;
+    };
+    let Some(workdir) = repo.workdir().and_then(|w| w.canonicalize().ok()) else {
+        return decorations;
+    };
+    let Ok(dir) = dir.canonicalize() else {
+        return decorations;
+    };
+    let Ok(relative_dir) = dir.strip_prefix(&workdir) else {
+        return decorations;
+    };
+    let relative_dir = relative_dir.to_string_lossy().replace('\\', "/");
+
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .include_ignored(true)
+        .recurse_untracked_dirs(false)
+        .recurse_ignored_dirs(false);
+    if !relative_dir.is_empty() {
+        opts.pathspec(&relative_dir);
+    }
+
+    let Ok(statuses) = repo.statuses(Some(&mut opts)) else {
+        return decorations;
+    };
+    let index = repo.index().ok();
+
+    let prefix = if relative_dir.is_empty() {
+        String::new()
+    } else {
+        format!("{}/", relative_dir)
+    };
+
+    for entry in statuses.iter() {
+        let (Some(path), Some(status)) = (entry.path(), decoration(entry.status())) else {
+            continue;
+        };
+        if is_skipped_worktree(index.as_ref(), path, entry.status()) {
+            continue;
+        }
+        let Some(rest) = path.strip_prefix(&prefix) else {
+            continue;
+        };
+        let rest = rest.trim_end_matches('/');
+        let (child, nested) = match rest.split_once('/') {
+            Some((child, _)) => (child, true),
+            None => (rest, false),
+        };
+        if child.is_empty() {
+            continue;
+        }
+
+        let key = child.to_string();
+        let status = if nested && status != GitFileStatus::Ignored {
+            GitFileStatus::Modified
+        } else {
+            status
+        };
+        // 子目录中存在任意变更时，不让 ignored 覆盖 modified
+        decorations
+            .entry(key)
+            .and_modify(|existing| {
+                if *existing == GitFileStatus::Ignored {
+                    *existing = status;
+                }
+            })
+            .or_insert(status);
+    }
+
+    decorations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn setup_repo() -> (Repository, std::path::PathBuf) {
+        let path = env::temp_dir().join(format!("test_git_status_{}", uuid::Uuid::new_v4()));
+        let repo = Repository::init(&path).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Tester").unwrap();
+        config.set_str("user.email", "tester@example.com").unwrap();
+        (repo, path)
+    }
+
+    fn status_of<'a>(entries: &'a [WorkingTreeEntry], path: &str) -> &'a WorkingTreeEntry {
+        entries.iter().find(|e| e.path == path).unwrap()
+    }
+
+    #[test]
+    fn test_stage_unstage_commit_and_discard() {
+        let (repo, path) = setup_repo();
+        fs::write(path.join("a.txt"), "one\n").unwrap();
+        fs::write(path.join(".gitignore"), "target/\n").unwrap();
+
+        stage_paths(&repo, &["a.txt".to_string(), ".gitignore".to_string()]).unwrap();
+        let first = commit_staged(&repo, "initial").unwrap();
+        assert_eq!(first.author_name, "Tester");
+        assert_eq!(
+            commit_staged(&repo, "again").unwrap_err(),
+            "Nothing to commit"
+        );
+
+        fs::write(path.join("a.txt"), "one\ntwo\n").unwrap();
+        fs::write(path.join("new.txt"), "new\n").unwrap();
+        let entries = get_working_tree_status(&repo).unwrap();
+        assert_eq!(
+            status_of(&entries, "a.txt").worktree_status,
+            Some(GitFileStatus::Modified)
+        );
+        assert_eq!(
+            status_of(&entries, "new.txt").worktree_status,
+            Some(GitFileStatus::Untracked)
+        );
+
+        stage_paths(&repo, &["a.txt".to_string()]).unwrap();
+        let entries = get_working_tree_status(&repo).unwrap();
+        let a = status_of(&entries, "a.txt");
+        assert_eq!(a.index_status, Some(GitFileStatus::Modified));
+        assert_eq!(a.worktree_status, None);
+
+        unstage_paths(&repo, &["a.txt".to_string()]).unwrap();
+        let entries = get_working_tree_status(&repo).unwrap();
+        assert_eq!(status_of(&entries, "a.txt").index_status, None);
+
+        discard_paths(&repo, &["a.txt".to_string(), "new.txt".to_string()]).unwrap();
+        assert_eq!(fs::read_to_string(path.join("a.txt")).unwrap(), "one\n");
+        assert!(!path.join("new.txt").exists());
+        assert!(get_working_tree_status(&repo).unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(path);
+    }
+
+    #[test]
+    fn test_git_decorations() {
+        let (repo, path) = setup_repo();
+        fs::create_dir_all(path.join("src")).unwrap();
+        fs::create_dir_all(path.join("target")).unwrap();
+        fs::write(path.join(".gitignore"), "target/\n").unwrap();
+        fs::write(path.join("src/lib.rs"), "fn a() {}\n").unwrap();
+        fs::write(path.join("target/out"), "bin").unwrap();
+        stage_paths(&repo, &[".gitignore".to_string(), "src".to_string()]).unwrap();
+        commit_staged(&repo, "initial").unwrap();
+
+        fs::write(path.join("src/lib.rs"), "fn b() {}\n").unwrap();
+        fs::write(path.join("notes.md"), "draft").unwrap();
+
+        let decorations = git_decorations(&path);
+        let get = |name: &str| decorations.get(name).copied();
+        assert_eq!(get("src"), Some(GitFileStatus::Modified));
+        assert_eq!(get("notes.md"), Some(GitFileStatus::Untracked));
+        assert_eq!(get("target"), Some(GitFileStatus::Ignored));
+        assert_eq!(get(".gitignore"), None);
+
+        let nested = git_decorations(&path.join("src"));
+        assert_eq!(nested.get("lib.rs"), Some(&GitFileStatus::Modified));
+
+        let _ = fs::remove_dir_all(path);
+    }
+}
@@ -0,0 +1,450 @@
+//! 依赖图导出
+//!
+//! 读取 open-node 索引阶段写入的 `edge.db`（Keyv SQLite 存储），按模块或符号粒度
+//! 构建依赖图，并导出为 Graphviz DOT、JSON 或 Mermaid 文本。
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::path::Path;
+
+use super::KeyvStore;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    Dot,
+    Json,
+    Mermaid,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphGranularity {
+    /// 以文件为节点
+    Module,
+    /// 以符号为节点
+    Symbol,
+}
+
+#[derive(Debug, Clone)]
+pub struct GraphExportOptions {
+    pub granularity: GraphGranularity,
+    pub format: GraphFormat,
+    /// 只导出以该路径（仓库内相对路径）为根的子图
+    pub root_path: Option<String>,
+    /// 按目录折叠节点，保留路径的前 N 级
+    pub collapse_depth: Option<usize>,
+    /// 从根节点出发的最大遍历深度
+    pub max_depth: Option<usize>,
+    /// 只保留指定类型的边（IMPORTS、CALLS 等）
+    pub edge_types: Option<Vec<String>>,
+    /// 是否保留无法解析到仓库内符号的外部节点
+    pub include_external: bool,
+}
+
+impl Default for GraphExportOptions {
+    fn default() -> Self {
+        Self {
+            granularity: GraphGranularity::Module,
+            format: GraphFormat::Dot,
+            root_path: None,
+            collapse_depth: None,
+            max_depth: None,
+            edge_types: None,
+            include_external: false,
+        }
+    }
+}
+
+/// edge.db 中的一条原始边
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawEdge {
+    pub from: String,
+    pub to: String,
+    pub edge_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphNode {
+    pub id: String,
+    pub label: String,
+    pub external: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub edge_type: String,
+    pub weight: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// 解析后的符号 ID：`{workspaceId}/{repoId}/{filePath}#{qualifiedName}`
+struct SymbolRef<'a> {
+    file_path: &'a str,
+    qualified_name: &'a str,
+}
+
+fn parse_symbol_id<'a>(symbol_id: &'a str, prefix: &str) -> Option<SymbolRef<'a>> {
+    let rest = symbol_id.strip_prefix(prefix)?;
+    let (file_path, qualified_name) = rest.split_once('#')?;
+    Some(SymbolRef {
+        file_path,
+        qualified_name,
+    })
+}
+
+/// 读取某个仓库的全部正向边
+pub fn load_repo_edges(
+    edge_db_path: &Path,
+    workspace_id: &str,
+    repo_id: &str,
+) -> Result<Vec<RawEdge>, String> {
+    let store = KeyvStore::open_read_only(edge_db_path)?;
+    let entries = store.scan_prefix(&format!("{}/{}/", workspace_id, repo_id))?;
+
+    let mut edges = Vec::new();
+    for (key, value) in entries {
+        // 键格式：`{from}:{edgeType}`
+        let Some((from, edge_type)) = key.rsplit_once(':') else {
+            continue;
+        };
+
+        let targets: Vec<String> = serde_json::from_value(value).unwrap_or_default();
+        for to in targets {
+            edges.push(RawEdge {
+                from: from.to_string(),
+                to,
+                edge_type: edge_type.to_string(),
+            });
+        }
+    }
+
+    Ok(edges)
+}
+
+fn is_under(path: &str, root: &str) -> bool {
+    let root = root.trim_matches('/');
+    root.is_empty()
+        || path == root
+        || (path.starts_with(root) && path[root.len()..].starts_with('/'))
+}
+
+fn collapse_path(path: &str, depth: usize) -> String {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    if segments.len() <= depth {
+        return segments.join("/");
+    }
+    segments[..depth.max(1)].join("/")
+}
+
+/// 根据原始边构建依赖图
+pub fn build_dependency_graph(
+    raw_edges: &[RawEdge],
+    workspace_id: &str,
+    repo_id: &str,
+    options: &GraphExportOptions,
+) -> DependencyGraph {
+    let prefix = format!("{}/{}/", workspace_id, repo_id);
+
+    // 仓库内已知符号，用于把调用目标名解析回符号 ID
+    let mut by_name: HashMap<&str, &str> = HashMap::new();
+    for edge in raw_edges {
+        if let Some(symbol) = parse_symbol_id(&edge.from, &prefix) {
+            by_name
+                .entry(symbol.qualified_name)
+                .or_insert(edge.from.as_str());
+            if let Some((_, short)) = symbol.qualified_name.rsplit_once('.') {
+                by_name.entry(short).or_insert(edge.from.as_str());
+            }
+        }
+    }
+
+    // 节点键 -> (label, 所属路径, 是否外部)
+    let node_key = |symbol_id: &str| -> Option<(String, String, bool)> {
+        let symbol = parse_symbol_id(symbol_id, &prefix)?;
+        let path = match options.collapse_depth {
+            Some(depth) => collapse_path(symbol.file_path, depth),
+            None => symbol.file_path.to_string(),
+        };
+        let key = match (options.granularity, options.collapse_depth) {
+            (GraphGranularity::Symbol, None) => {
+                format!("{}#{}", symbol.file_path, symbol.qualified_name)
+            }
+            _ => path.clone(),
+        };
+        Some((key, path, false))
+    };
+
+    let mut nodes: BTreeMap<String, GraphNode> = BTreeMap::new();
+    let mut node_paths: HashMap<String, String> = HashMap::new();
+    let mut weights: BTreeMap<(String, String, String), u32> = BTreeMap::new();
+
+    for edge in raw_edges {
+        if let Some(types) = &options.edge_types
+            && !types
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(&edge.edge_type))
+        {
+            continue;
+        }
+
+        let Some((from_key, from_path, _)) = node_key(&edge.from) else {
+            continue;
+        };
+
+        let resolved = if edge.to.starts_with(&prefix) {
+            Some(edge.to.as_str())
+        } else {
+            by_name.get(edge.to.as_str()).copied()
+        };
+        let (to_key, to_path, external) = match resolved.and_then(node_key) {
+            Some(target) => target,
+            None if options.include_external => (edge.to.clone(), String::new(), true),
+            None => continue,
+        };
+
+        if from_key == to_key {
+            continue;
+        }
+
+        for (key, path, external) in [
+            (&from_key, &from_path, false),
+            (&to_key, &to_path, external),
+        ] {
+            nodes.entry(key.clone()).or_insert_with(|| GraphNode {
+                id: key.clone(),
+                label: key.clone(),
+                external,
+            });
+            node_paths
+                .entry(key.clone())
+                .or_insert_with(|| path.clone());
+        }
+
+        *weights
+            .entry((from_key, to_key, edge.edge_type.clone()))
+            .or_insert(0) += 1;
+    }
+
+    let mut edges: Vec<GraphEdge> = weights
+        .into_iter()
+        .map(|((from, to, edge_type), weight)| GraphEdge {
+            from,
+            to,
+            edge_type,
+            weight,
+        })
+        .collect();
+
+    if options.root_path.is_some() || options.max_depth.is_some() {
+        let root = options.root_path.as_deref().unwrap_or("");
+        let seeds: Vec<&String> = nodes
+            .values()
+            .filter(|n| !n.external && is_under(&node_paths[&n.id], root))
+            .map(|n| &n.id)
+            .collect();
+        let reachable = reachable_within(&edges, &seeds, options.max_depth);
+
+        edges.retain(|e| reachable.contains(&e.from) && reachable.contains(&e.to));
+        nodes.retain(|id, _| reachable.contains(id));
+    }
+
+    DependencyGraph {
+        nodes: nodes.into_values().collect(),
+        edges,
+    }
+}
+
+fn reachable_within(
+    edges: &[GraphEdge],
+    seeds: &[&String],
+    max_depth: Option<usize>,
+) -> BTreeSet<String> {
+    let mut adjacency: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in edges {
+        adjacency.entry(&edge.from).or_default().push(&edge.to);
+    }
+
+    let mut visited: BTreeSet<String> = seeds.iter().map(|s| s.to_string()).collect();
+    let mut queue: VecDeque<(&str, usize)> = seeds.iter().map(|s| (s.as_str(), 0)).collect();
+
+    while let Some((node, level)) = queue.pop_front() {
+        if max_depth.is_some_and(|max| level >= max) {
+            continue;
+        }
+        if let Some(targets) = adjacency.get(node) {
+            for target in targets {
+                if visited.insert(target.to_string()) {
+                    queue.push_back((target, level + 1));
+                }
+            }
+        }
+    }
+
+    visited
+}
+
+impl DependencyGraph {
+    pub fn render(&self, format: GraphFormat) -> Result<String, String> {
+        match format {
+            GraphFormat::Dot => Ok(self.to_dot()),
+            GraphFormat::Mermaid => Ok(self.to_mermaid()),
+            GraphFormat::Json => {
+                serde_json::to_string_pretty(self).map_err(|e| format!("序列化依赖图失败: {}", e))
+            }
+        }
+    }
+
+    fn to_dot(&self) -> String {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut out = String::from("digraph dependencies {\n  rankdir=LR;\n  node [shape=box];\n");
+        for node in &self.nodes {
+            let style = if node.external { ", style=dashed" } else { "" };
+            out.push_str(&format!(
+                "  \"{}\" [label=\"{}\"{}];\n",
+                escape(&node.id),
+                escape(&node.label),
+                style
+            ));
+        }
+        for edge in &self.edges {
+            let label = if edge.weight > 1 {
+                format!("{} ×{}", edge.edge_type, edge.weight)
+            } else {
+                edge.edge_type.clone()
+            };
+            out.push_str(&format!(
+                "  \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                escape(&edge.from),
+                escape(&edge.to),
+                escape(&label)
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn to_mermaid(&self) -> String {
+        let escape = |s: &str| s.replace('"', "#quot;");
+        let ids: HashMap<&str, String> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.id.as_str(), format!("n{}", i)))
+            .collect();
+
+        let mut out = String::from("graph LR\n");
+        for node in &self.nodes {
+            let id = &ids[node.id.as_str()];
+            if node.external {
+                out.push_str(&format!("  {}([\"{}\"])\n", id, escape(&node.label)));
+            } else {
+                out.push_str(&format!("  {}[\"{}\"]\n", id, escape(&node.label)));
+            }
+        }
+        for edge in &self.edges {
+            let label = if edge.weight > 1 {
+                format!("{} x{}", edge.edge_type, edge.weight)
+            } else {
+                edge.edge_type.clone()
+            };
+            out.push_str(&format!(
+                "  {} -->|{}| {}\n",
+                ids[edge.from.as_str()],
+                escape(&label),
+                ids[edge.to.as_str()]
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(from: &str, to: &str, edge_type: &str) -> RawEdge {
+        RawEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            edge_type: edge_type.to_string(),
+        }
+    }
+
+    fn sample_edges() -> Vec<RawEdge> {
+        vec![
+            edge("ws/repo/src/api/routes.ts#handle", "resolve", "CALLS"),
+            edge("ws/repo/src/api/routes.ts#handle", "render", "CALLS"),
+            edge("ws/repo/src/core/resolver.ts#resolve", "readFile", "CALLS"),
+            edge("ws/repo/src/core/render.ts#render", "resolve", "CALLS"),
+        ]
+    }
+
+    #[test]
+    fn test_module_graph_resolves_call_targets() {
+        let graph = build_dependency_graph(
+            &sample_edges(),
+            "ws",
+            "repo",
+            &GraphExportOptions::default(),
+        );
+
+        let ids: Vec<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "src/api/routes.ts",
+                "src/core/render.ts",
+                "src/core/resolver.ts"
+            ]
+        );
+        assert_eq!(graph.edges.len(), 3);
+    }
+
+    #[test]
+    fn test_collapse_and_root_path() {
+        let options = GraphExportOptions {
+            collapse_depth: Some(2),
+            root_path: Some("src/core".to_string()),
+            include_external: true,
+            ..Default::default()
+        };
+        let graph = build_dependency_graph(&sample_edges(), "ws", "repo", &options);
+
+        let ids: Vec<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["readFile", "src/core"]);
+        assert!(graph.nodes.iter().any(|n| n.id == "readFile" && n.external));
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let graph = build_dependency_graph(
+            &sample_edges(),
+            "ws",
+            "repo",
+            &GraphExportOptions::default(),
+        );
+
+        let dot = graph.render(GraphFormat::Dot).unwrap();
+        assert!(dot.starts_with("digraph dependencies {"));
+        assert!(dot.contains("\"src/api/routes.ts\" -> \"src/core/resolver.ts\""));
+
+        let mermaid = graph.render(GraphFormat::Mermaid).unwrap();
+        assert!(mermaid.starts_with("graph LR"));
+        assert!(mermaid.contains("-->|CALLS|"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&graph.render(GraphFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["edges"].as_array().unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,133 @@
+//! Keyv SQLite 存储访问
+//!
+//! open-node 使用 `@keyv/sqlite` 保存符号（symbol.db）和依赖边（edge.db），
+//! 数据表为 `keyv(key, value)`，键带有 `keyv:` 命名空间前缀，
+//! 值以 `{"value": ..., "expires": null}` 的 JSON 形式存储。
+//! 读取之外只提供删除和整值改写，用于删除仓库时移除其索引数据。
+
+use rusqlite::{Connection, OpenFlags, params};
+use std::path::Path;
+use std::time::Duration;
+
+/// Keyv 默认命名空间前缀
+const KEYV_NAMESPACE: &str = "keyv:";
+
+pub struct KeyvStore {
+    conn: Connection,
+}
+
+impl KeyvStore {
+    pub fn open_read_only(db_path: &Path) -> Result<Self, String> {
+        if !db_path.exists() {
+            return Err(format!("数据库不存在: {}", db_path.display()));
+        }
+
+        let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| format!("无法打开数据库 {}: {}", db_path.display(), e))?;
+        Ok(Self { conn })
+    }
+
+    /// 以读写方式打开，open-node 可能同时在写入，等待锁释放
+    pub fn open(db_path: &Path) -> Result<Self, String> {
+        if !db_path.exists() {
+            return Err(format!("数据库不存在: {}", db_path.display()));
+        }
+
+        let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_WRITE)
+            .map_err(|e| format!("无法打开数据库 {}: {}", db_path.display(), e))?;
+        conn.busy_timeout(Duration::from_secs(5))
+            .map_err(|e| format!("无法打开数据库 {}: {}", db_path.display(), e))?;
+        Ok(Self { conn })
+    }
+
+    /// 按键读取值（不含命名空间前缀）
+    pub fn get(&self, key: &str) -> Result<Option<serde_json::Value>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT value FROM keyv WHERE key = ?1")
+            .map_err(|e| format!("读取 Keyv 数据失败: {}", e))?;
+        let mut rows = stmt
+            .query(params![format!("{}{}", KEYV_NAMESPACE, key)])
+            .map_err(|e| format!("读取 Keyv 数据失败: {}", e))?;
+
+        match rows
+            .next()
+            .map_err(|e| format!("读取 Keyv 数据失败: {}", e))?
+        {
+            Some(row) => {
+                let value: String = row.get(0).map_err(|e| e.to_string())?;
+                Ok(decode_value(&value))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// 读取所有以指定前缀开头的键值对，返回的键已去掉命名空间前缀
+    pub fn scan_prefix(&self, prefix: &str) -> Result<Vec<(String, serde_json::Value)>, String> {
+        let key_prefix = format!("{}{}", KEYV_NAMESPACE, prefix);
+        let mut stmt = self
+            .conn
+            .prepare("SELECT key, value FROM keyv WHERE substr(key, 1, ?2) = ?1")
+            .map_err(|e| format!("读取 Keyv 数据失败: {}", e))?;
+
+        let rows = stmt
+            .query_map(
+                params![key_prefix, key_prefix.chars().count() as i64],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )
+            .map_err(|e| format!("读取 Keyv 数据失败: {}", e))?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            let (key, value) = row.map_err(|e| format!("读取 Keyv 数据失败: {}", e))?;
+            if let Some(value) = decode_value(&value) {
+                let key = key.strip_prefix(KEYV_NAMESPACE).unwrap_or(&key).to_string();
+                entries.push((key, value));
+            }
+        }
+        Ok(entries)
+    }
+
+    /// 以 Keyv 的格式写入值（不含命名空间前缀的键），已存在时覆盖
+    pub fn set(&self, key: &str, value: &serde_json::Value) -> Result<(), String> {
+        let raw = serde_json::json!({ "value": value, "expires": null }).to_string();
+        self.conn
+            .execute(
+                "INSERT INTO keyv (key, value) VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                params![format!("{}{}", KEYV_NAMESPACE, key), raw],
+            )
+            .map_err(|e| format!("写入 Keyv 数据失败: {}", e))?;
+        Ok(())
+    }
+
+    /// 删除单个键（不含命名空间前缀）
+    pub fn delete(&self, key: &str) -> Result<bool, String> {
+        self.conn
+            .execute(
+                "DELETE FROM keyv WHERE key = ?1",
+                params![format!("{}{}", KEYV_NAMESPACE, key)],
+            )
+            .map(|count| count > 0)
+            .map_err(|e| format!("删除 Keyv 数据失败: {}", e))
+    }
+
+    /// 删除所有以指定前缀开头的键，返回删除的条数
+    pub fn delete_prefix(&self, prefix: &str) -> Result<usize, String> {
+        let key_prefix = format!("{}{}", KEYV_NAMESPACE, prefix);
+        self.conn
+            .execute(
+                "DELETE FROM keyv WHERE substr(key, 1, ?2) = ?1",
+                params![key_prefix, key_prefix.chars().count() as i64],
+            )
+            .map_err(|e| format!("删除 Keyv 数据失败: {}", e))
+    }
+}
+
+fn decode_value(raw: &str) -> Option<serde_json::Value> {
+    let json = serde_json::from_str::<serde_json::Value>(raw).ok()?;
+    match json {
+        serde_json::Value::Object(mut map) if map.contains_key("value") => map.remove("value"),
+        other => Some(other),
+    }
+}
@@ -0,0 +1,577 @@
+//! 项目清单解析
+//!
+//! 在仓库中查找 Cargo.toml、package.json、pyproject.toml 和 go.mod，
+//! 提取项目名称、版本、工作区成员、依赖声明和脚本。
+
+use ignore::WalkBuilder;
+use std::fs;
+use std::path::Path;
+use toml::Value as TomlValue;
+
+use crate::app_state::{
+    DatabaseManager, DependencyMatch, ManifestDependency, ProjectManifest, ProjectType,
+};
+
+/// 不会包含项目自身清单的目录
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", ".venv", "venv", "dist"];
+
+fn project_type_for(file_name: &str) -> Option<ProjectType> {
+    match file_name {
+        "Cargo.toml" => Some(ProjectType::Cargo),
+        "package.json" => Some(ProjectType::Npm),
+        "pyproject.toml" => Some(ProjectType::Python),
+        "go.mod" => Some(ProjectType::Go),
+        _ => None,
+    }
+}
+
+/// 遍历仓库（遵循 .gitignore）识别所有项目清单
+///
+/// 无法解析的清单只记录日志，不影响其他清单。
+pub fn detect_manifests(root: &Path) -> Result<Vec<ProjectManifest>, String> {
+    if !root.is_dir() {
+        return Err(format!("Repository path not found: {}", root.display()));
+    }
+
+    let walker = WalkBuilder::new(root)
+        .hidden(true)
+        .git_ignore(true)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(|entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            !(is_dir && SKIPPED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref()))
+        })
+        .build();
+
+    let mut manifests = Vec::new();
+    for entry in walker.filter_map(|e| e.ok()) {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let Some(project_type) = project_type_for(&entry.file_name().to_string_lossy()) else {
+            continue;
+        };
+        let relative = entry
+            .path()
+            .strip_prefix(root)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let parsed = fs::read_to_string(entry.path())
+            .map_err(|e| e.to_string())
+            .and_then(|content| parse_manifest(relative.clone(), project_type, &content));
+        match parsed {
+            Ok(manifest) => manifests.push(manifest),
+            Err(e) => log::warn!("Failed to parse manifest {}: {}", relative, e),
+        }
+    }
+
+    Ok(manifests)
+}
+
+pub fn parse_manifest(
+    path: String,
+    project_type: ProjectType,
+    content: &str,
+) -> Result<ProjectManifest, String> {
+    match project_type {
+        ProjectType::Cargo => parse_cargo_toml(path, content),
+        ProjectType::Npm => parse_package_json(path, content),
+        ProjectType::Python => parse_pyproject_toml(path, content),
+        ProjectType::Go => Ok(parse_go_mod(path, content)),
+    }
+}
+
+fn toml_str(value: Option<&TomlValue>) -> Option<String> {
+    value.and_then(|v| v.as_str()).map(|s| s.to_string())
+}
+
+fn toml_str_array(value: Option<&TomlValue>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 读取 Cargo 依赖表，支持 `dep = "1"` 与 `dep = { version = "1", package = "..." }`
+fn cargo_dependencies(
+    table: Option<&TomlValue>,
+    kind: &str,
+    workspace_versions: &[ManifestDependency],
+    out: &mut Vec<ManifestDependency>,
+) {
+    let Some(table) = table.and_then(|t| t.as_table()) else {
+        return;
+    };
+    for (key, value) in table {
+        let (name, version) = match value {
+            TomlValue::String(version) => (key.clone(), Some(version.clone())),
+            TomlValue::Table(spec) => {
+                let name = toml_str(spec.get("package")).unwrap_or_else(|| key.clone());
+                let inherited = spec.get("workspace").and_then(|v| v.as_bool()) == Some(true);
+                let version = if inherited {
+                    workspace_versions
+                        .iter()
+                        .find(|d| d.name == name)
+                        .and_then(|d| d.version.clone())
+                } else {
+                    toml_str(spec.get("version"))
+                };
+                (name, version)
+            }
+            _ => continue,
+        };
+        out.push(ManifestDependency {
+            name,
+            version,
+            kind: kind.to_string(),
+        });
+    }
+}
+
+fn parse_cargo_toml(path: String, content: &str) -> Result<ProjectManifest, String> {
+    let doc: TomlValue = toml::from_str(content).map_err(|e| e.to_string())?;
+    let mut manifest = ProjectManifest::new(path, ProjectType::Cargo);
+
+    if let Some(package) = doc.get("package") {
+        manifest.name = toml_str(package.get("name"));
+        // `version.workspace = true` 时版本在工作区根清单中
+        manifest.version = toml_str(package.get("version"));
+    }
+
+    let mut workspace_versions = Vec::new();
+    if let Some(workspace) = doc.get("workspace") {
+        manifest.workspace_members = toml_str_array(workspace.get("members"));
+        cargo_dependencies(
+            workspace.get("dependencies"),
+            "workspace",
+            &[],
+            &mut workspace_versions,
+        );
+    }
+
+    let mut dependencies = Vec::new();
+    let sections = [
+        ("dependencies", "normal"),
+        ("dev-dependencies", "dev"),
+        ("build-dependencies", "build"),
+    ];
+    for (section, kind) in sections {
+        cargo_dependencies(
+            doc.get(section),
+            kind,
+            &workspace_versions,
+            &mut dependencies,
+        );
+    }
+    // [target.'cfg(...)'.dependencies]
+    if let Some(targets) = doc.get("target").and_then(|t| t.as_table()) {
+        for target in targets.values() {
+            for (section, kind) in sections {
+                cargo_dependencies(
+                    target.get(section),
+                    kind,
+                    &workspace_versions,
+                    &mut dependencies,
+                );
+            }
+        }
+    }
+
+    dependencies.extend(workspace_versions);
+    manifest.dependencies = dependencies;
+    Ok(manifest)
+}
+
+fn parse_package_json(path: String, content: &str) -> Result<ProjectManifest, String> {
+    let doc: serde_json::Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let mut manifest = ProjectManifest::new(path, ProjectType::Npm);
+
+    manifest.name = doc["name"].as_str().map(|s| s.to_string());
+    manifest.version = doc["version"].as_str().map(|s| s.to_string());
+
+    // workspaces 可以是数组，也可以是 { "packages": [...] }
+    let workspaces = if doc["workspaces"].is_array() {
+        &doc["workspaces"]
+    } else {
+        &doc["workspaces"]["packages"]
+    };
+    if let Some(items) = workspaces.as_array() {
+        manifest.workspace_members = items
+            .iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect();
+    }
+
+    if let Some(scripts) = doc["scripts"].as_object() {
+        for (name, command) in scripts {
+            if let Some(command) = command.as_str() {
+                manifest.scripts.insert(name.clone(), command.to_string());
+            }
+        }
+    }
+
+    let sections = [
+        ("dependencies", "normal"),
+        ("devDependencies", "dev"),
+        ("peerDependencies", "peer"),
+        ("optionalDependencies", "optional"),
+    ];
+    for (section, kind) in sections {
+        let Some(deps) = doc[section].as_object() else {
+            continue;
+        };
+        for (name, version) in deps {
+            manifest.dependencies.push(ManifestDependency {
+                name: name.clone(),
+                version: version.as_str().map(|s| s.to_string()),
+                kind: kind.to_string(),
+            });
+        }
+    }
+
+    Ok(manifest)
+}
+
+/// 拆分 PEP 508 依赖声明，如 `requests[socks]>=2.31; python_version >= "3.8"`
+fn parse_pep508(spec: &str, kind: &str) -> Option<ManifestDependency> {
+    let spec = spec.split(';').next().unwrap_or("").trim();
+    let name_end = spec
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(spec.len());
+    let name = &spec[..name_end];
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut rest = spec[name_end..].trim_start();
+    if rest.starts_with('[') {
+        rest = rest.find(']').map(|i| &rest[i + 1..]).unwrap_or("");
+    }
+    let version = rest.trim().trim_start_matches('(').trim_end_matches(')');
+    Some(ManifestDependency {
+        name: name.to_string(),
+        version: (!version.is_empty()).then(|| version.to_string()),
+        kind: kind.to_string(),
+    })
+}
+
+fn poetry_dependencies(table: Option<&TomlValue>, kind: &str, out: &mut Vec<ManifestDependency>) {
+    let Some(table) = table.and_then(|t| t.as_table()) else {
+        return;
+    };
+    for (name, value) in table {
+        // poetry 把解释器版本也写在依赖表里
+        if name == "python" {
+            continue;
+        }
+        let version = match value {
+            TomlValue::String(version) => Some(version.clone()),
+            TomlValue::Table(spec) => toml_str(spec.get("version")),
+            _ => None,
+        };
+        out.push(ManifestDependency {
+            name: name.clone(),
+            version,
+            kind: kind.to_string(),
+        });
+    }
+}
+
+fn parse_pyproject_toml(path: String, content: &str) -> Result<ProjectManifest, String> {
+    let doc: TomlValue = toml::from_str(content).map_err(|e| e.to_string())?;
+    let mut manifest = ProjectManifest::new(path, ProjectType::Python);
+    let project = doc.get("project");
+    let poetry = doc.get("tool").and_then(|t| t.get("poetry"));
+
+    manifest.name = toml_str(project.and_then(|p| p.get("name")))
+        .or_else(|| toml_str(poetry.and_then(|p| p.get("name"))));
+    manifest.version = toml_str(project.and_then(|p| p.get("version")))
+        .or_else(|| toml_str(poetry.and_then(|p| p.get("version"))));
+    manifest.workspace_members = toml_str_array(
+        doc.get("tool")
+            .and_then(|t| t.get("uv"))
+            .and_then(|u| u.get("workspace"))
+            .and_then(|w| w.get("members")),
+    );
+
+    for scripts in [
+        project.and_then(|p| p.get("scripts")),
+        poetry.and_then(|p| p.get("scripts")),
+    ] {
+        if let Some(scripts) = scripts.and_then(|s| s.as_table()) {
+            for (name, target) in scripts {
+                if let Some(target) = target.as_str() {
+                    manifest.scripts.insert(name.clone(), target.to_string());
+                }
+            }
+        }
+    }
+
+    let dependencies = &mut manifest.dependencies;
+    for spec in toml_str_array(project.and_then(|p| p.get("dependencies"))) {
+        dependencies.extend(parse_pep508(&spec, "normal"));
+    }
+    if let Some(extras) = project
+        .and_then(|p| p.get("optional-dependencies"))
+        .and_then(|o| o.as_table())
+    {
+        for specs in extras.values() {
+            for spec in toml_str_array(Some(specs)) {
+                dependencies.extend(parse_pep508(&spec, "optional"));
+            }
+        }
+    }
+    if let Some(groups) = doc.get("dependency-groups").and_then(|g| g.as_table()) {
+        for specs in groups.values() {
+            for spec in toml_str_array(Some(specs)) {
+                dependencies.extend(parse_pep508(&spec, "dev"));
+            }
+        }
+    }
+
+    if let Some(poetry) = poetry {
+        poetry_dependencies(poetry.get("dependencies"), "normal", dependencies);
+        poetry_dependencies(poetry.get("dev-dependencies"), "dev", dependencies);
+        if let Some(groups) = poetry.get("group").and_then(|g| g.as_table()) {
+            for group in groups.values() {
+                poetry_dependencies(group.get("dependencies"), "dev", dependencies);
+            }
+        }
+    }
+
+    Ok(manifest)
+}
+
+fn go_require(line: &str) -> Option<ManifestDependency> {
+    let (spec, comment) = match line.split_once("//") {
+        Some((spec, comment)) => (spec, comment.trim()),
+        None => (line, ""),
+    };
+    let mut parts = spec.split_whitespace();
+    let name = parts.next()?;
+    let version = parts.next()?;
+    Some(ManifestDependency {
+        name: name.to_string(),
+        version: Some(version.to_string()),
+        kind: if comment == "indirect" {
+            "indirect".to_string()
+        } else {
+            "normal".to_string()
+        },
+    })
+}
+
+fn parse_go_mod(path: String, content: &str) -> ProjectManifest {
+    let mut manifest = ProjectManifest::new(path, ProjectType::Go);
+    let mut in_require = false;
+
+    for line in content.lines().map(str::trim) {
+        if in_require {
+            if line == ")" {
+                in_require = false;
+            } else if !line.is_empty() && !line.starts_with("//") {
+                manifest.dependencies.extend(go_require(line));
+            }
+        } else if let Some(module) = line.strip_prefix("module ") {
+            manifest.name = Some(module.trim().trim_matches('"').to_string());
+        } else if let Some(require) = line.strip_prefix("require") {
+            let require = require.trim();
+            if require == "(" {
+                in_require = true;
+            } else {
+                manifest.dependencies.extend(go_require(require));
+            }
+        }
+    }
+
+    manifest
+}
+
+/// 提取版本号中的数字部分，遇到 `x` / `*` 或非数字时停止
+fn version_components(version: &str) -> Vec<u64> {
+    let Some(start) = version.find(|c: char| c.is_ascii_digit()) else {
+        return Vec::new();
+    };
+    version[start..]
+        .split('.')
+        .map_while(|part| {
+            let digits: String = part.chars().take_while(|c| c.is_ascii_digit()).collect();
+            digits.parse().ok()
+        })
+        .collect()
+}
+
+/// 判断声明的版本要求是否落在查询的版本范围内
+///
+/// 查询 `1.x`、`1` 或 `^1.2` 会与声明中第一个版本号逐段比较，
+/// 声明未写出的段视为匹配（`tokio = "1"` 满足 `1.35`）。
+pub fn version_matches(declared: Option<&str>, query: &str) -> bool {
+    let query = version_components(query);
+    if query.is_empty() {
+        return true;
+    }
+    let Some(declared) = declared else {
+        return false;
+    };
+    let declared = version_components(declared);
+    !declared.is_empty() && declared.iter().zip(&query).all(|(d, q)| d == q)
+}
+
+/// 在工作空间内检索依赖，可按版本范围和项目类型过滤
+pub fn search_workspace_dependencies(
+    db: &DatabaseManager,
+    workspace_id: &str,
+    name: &str,
+    version: Option<&str>,
+    project_type: Option<ProjectType>,
+) -> Result<Vec<DependencyMatch>, String> {
+    let matches = db
+        .search_manifest_dependencies(workspace_id, name.trim())
+        .map_err(|e| format!("Failed to search dependencies: {}", e))?;
+
+    Ok(matches
+        .into_iter()
+        .filter(|m| project_type.is_none_or(|t| m.project_type == t))
+        .filter(|m| version.is_none_or(|v| version_matches(m.dependency.version.as_deref(), v)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn find<'a>(manifest: &'a ProjectManifest, name: &str) -> &'a ManifestDependency {
+        manifest
+            .dependencies
+            .iter()
+            .find(|d| d.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_cargo_toml() {
+        let content = r#"
+[package]
+name = "app"
+version = "0.2.0"
+
+[workspace]
+members = ["crates/*"]
+
+[workspace.dependencies]
+serde = "1.0"
+
+[dependencies]
+tokio = { version = "1.35", features = ["full"] }
+serde = { workspace = true }
+local = { path = "../local" }
+rq = { package = "reqwest", version = "0.13" }
+
+[dev-dependencies]
+tempfile = "3"
+
+[target.'cfg(windows)'.dependencies]
+winapi = "0.3"
+"#;
+        let manifest = parse_cargo_toml("Cargo.toml".to_string(), content).unwrap();
+        assert_eq!(manifest.name.as_deref(), Some("app"));
+        assert_eq!(manifest.workspace_members, vec!["crates/*"]);
+
+        let tokio = find(&manifest, "tokio");
+        assert_eq!(tokio.version.as_deref(), Some("1.35"));
+        assert_eq!(tokio.kind, "normal");
+        assert_eq!(find(&manifest, "reqwest").version.as_deref(), Some("0.13"));
+        assert_eq!(find(&manifest, "local").version, None);
+        assert_eq!(find(&manifest, "tempfile").kind, "dev");
+        assert_eq!(find(&manifest, "winapi").kind, "normal");
+        assert!(
+            manifest
+                .dependencies
+                .iter()
+                .any(|d| d.name == "serde" && d.kind == "normal" && d.version.is_some())
+        );
+    }
+
+    #[test]
+    fn test_parse_package_pyproject_and_go_mod() {
+        let package = r#"{
+            "name": "web",
+            "workspaces": { "packages": ["apps/*"] },
+            "scripts": { "dev": "vite" },
+            "dependencies": { "react": "^18.2.0" },
+            "devDependencies": { "typescript": "~5.4.0" }
+        }"#;
+        let manifest = parse_package_json("package.json".to_string(), package).unwrap();
+        assert_eq!(manifest.workspace_members, vec!["apps/*"]);
+        assert_eq!(
+            manifest.scripts.get("dev").map(String::as_str),
+            Some("vite")
+        );
+        assert_eq!(find(&manifest, "typescript").kind, "dev");
+
+        let pyproject = r#"
+[project]
+name = "tool"
+dependencies = ["requests[socks]>=2.31; python_version >= '3.8'", "click"]
+
+[project.optional-dependencies]
+docs = ["mkdocs==1.5"]
+
+[project.scripts]
+tool = "tool.cli:main"
+"#;
+        let manifest = parse_pyproject_toml("pyproject.toml".to_string(), pyproject).unwrap();
+        assert_eq!(
+            find(&manifest, "requests").version.as_deref(),
+            Some(">=2.31")
+        );
+        assert_eq!(find(&manifest, "click").version, None);
+        assert_eq!(find(&manifest, "mkdocs").kind, "optional");
+        assert_eq!(manifest.scripts.len(), 1);
+
+        let go_mod = "module example.com/svc\n\ngo 1.22\n\nrequire github.com/a/b v1.2.3\n\nrequire (\n\tgithub.com/c/d v0.4.0 // indirect\n)\n";
+        let manifest = parse_go_mod("go.mod".to_string(), go_mod);
+        assert_eq!(manifest.name.as_deref(), Some("example.com/svc"));
+        assert_eq!(find(&manifest, "github.com/a/b").kind, "normal");
+        assert_eq!(find(&manifest, "github.com/c/d").kind, "indirect");
+    }
+
+    #[test]
+    fn test_version_matches() {
+        assert!(version_matches(Some("1.35"), "1.x"));
+        assert!(version_matches(Some("^1"), "1.35"));
+        assert!(version_matches(Some("v1.2.3"), "1.2"));
+        assert!(!version_matches(Some("0.2"), "1.x"));
+        assert!(!version_matches(Some(">=2.31"), "1"));
+        assert!(!version_matches(None, "1"));
+        assert!(version_matches(None, "*"));
+    }
+
+    #[test]
+    fn test_detect_manifests_skips_dependencies() {
+        let root = env::temp_dir().join(format!("test_manifest_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("web/node_modules/left-pad")).unwrap();
+        fs::write(root.join("Cargo.toml"), "[package]\nname = \"core\"\n").unwrap();
+        fs::write(root.join("web/package.json"), r#"{"name":"web"}"#).unwrap();
+        fs::write(
+            root.join("web/node_modules/left-pad/package.json"),
+            r#"{"name":"left-pad"}"#,
+        )
+        .unwrap();
+        fs::write(root.join("broken.toml"), "not a manifest").unwrap();
+
+        let manifests = detect_manifests(&root).unwrap();
+        let paths: Vec<&str> = manifests.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, vec!["Cargo.toml", "web/package.json"]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
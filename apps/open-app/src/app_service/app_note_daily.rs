@@ -0,0 +1,351 @@
+//! 日记与笔记日历
+//!
+//! 每天一篇日记，标题按 `daily_title_format` 格式化，放在 `daily_parent_title` 指定的根级
+//! 父笔记下（不存在时创建）；配置了 `daily_template_id` 时由模板渲染内容。
+//! 日历查询在本地时区下按 created_at / updated_at 把笔记划分到天。
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use super::{build_note_from_template, find_note_template, format_date};
+use crate::app_state::{DatabaseManager, Note, NoteType, NotesConfig};
+
+lazy_static! {
+    /// 避免并发调用为同一天创建两篇日记
+    static ref DAILY_NOTE_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// 日历中的一天，`updated` 不包含当天创建的笔记
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteCalendarDay {
+    pub date: String,
+    pub created: usize,
+    pub updated: usize,
+}
+
+/// 某一天创建和修改过的笔记
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteDayActivity {
+    pub date: String,
+    pub created: Vec<Note>,
+    pub updated: Vec<Note>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DailyNote {
+    pub note: Note,
+    /// 日记是本次新建的
+    pub created: bool,
+    /// 同时新建了父笔记
+    pub parent_created: bool,
+}
+
+/// 解析 `YYYY-MM-DD` 格式的日期
+pub fn parse_day(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date: {}", date))
+}
+
+/// 本地时区下当天的开始时刻，零点不存在（夏令时切换）时顺延
+fn day_start(date: NaiveDate) -> DateTime<Local> {
+    (0..3)
+        .find_map(|hour| {
+            let time = NaiveTime::from_hms_opt(hour, 0, 0)?;
+            Local.from_local_datetime(&date.and_time(time)).earliest()
+        })
+        .unwrap_or_else(|| Local.from_utc_datetime(&date.and_time(NaiveTime::MIN)))
+}
+
+/// `[first, last]` 这几天对应的毫秒时间戳范围 `[start, end)`
+fn day_range_millis(first: NaiveDate, last: NaiveDate) -> (i64, i64) {
+    let end = last.succ_opt().unwrap_or(last);
+    (
+        day_start(first).timestamp_millis(),
+        day_start(end).timestamp_millis(),
+    )
+}
+
+fn local_day(timestamp_millis: i64) -> Option<NaiveDate> {
+    Local
+        .timestamp_millis_opt(timestamp_millis)
+        .single()
+        .map(|dt| dt.date_naive())
+}
+
+/// `start` 到 `end`（含）之间每天创建和修改的笔记数，只返回有笔记的日期
+pub fn note_calendar(
+    db: &DatabaseManager,
+    workspace_id: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<NoteCalendarDay>, String> {
+    if start > end {
+        return Err(format!("Invalid date range: {} > {}", start, end));
+    }
+    let (start_ms, end_ms) = day_range_millis(start, end);
+    let timestamps = db
+        .list_note_timestamps(workspace_id, start_ms, end_ms)
+        .map_err(|e| format!("Failed to fetch notes: {}", e))?;
+
+    let in_range = |day: &NaiveDate| *day >= start && *day <= end;
+    let mut days: BTreeMap<NaiveDate, (usize, usize)> = BTreeMap::new();
+    for (created_at, updated_at) in timestamps {
+        let created_day = local_day(created_at);
+        if let Some(day) = created_day.filter(in_range) {
+            days.entry(day).or_default().0 += 1;
+        }
+        if let Some(day) = local_day(updated_at).filter(in_range)
+            && Some(day) != created_day
+        {
+            days.entry(day).or_default().1 += 1;
+        }
+    }
+
+    Ok(days
+        .into_iter()
+        .map(|(day, (created, updated))| NoteCalendarDay {
+            date: day.format("%Y-%m-%d").to_string(),
+            created,
+            updated,
+        })
+        .collect())
+}
+
+/// 某一天创建的笔记和当天修改过的其他笔记，按 updated_at 倒序
+pub fn notes_on_day(
+    db: &DatabaseManager,
+    workspace_id: &str,
+    date: NaiveDate,
+) -> Result<NoteDayActivity, String> {
+    let (start, end) = day_range_millis(date, date);
+    let notes = db
+        .list_notes_active_between(workspace_id, start, end)
+        .map_err(|e| format!("Failed to fetch notes: {}", e))?;
+    let (created, updated) = notes
+        .into_iter()
+        .partition(|note| note.created_at >= start && note.created_at < end);
+    Ok(NoteDayActivity {
+        date: date.format("%Y-%m-%d").to_string(),
+        created,
+        updated,
+    })
+}
+
+/// 查找或创建根级父笔记，返回父笔记 ID 和是否新建
+fn ensure_daily_parent(
+    db: &DatabaseManager,
+    workspace_id: &str,
+    title: &str,
+) -> Result<(String, bool), String> {
+    let existing = db
+        .find_child_note_by_title(workspace_id, None, title)
+        .map_err(|e| format!("Failed to fetch note: {}", e))?;
+    if let Some(parent) = existing {
+        return Ok((parent.id, false));
+    }
+
+    let mut parent = Note::new(
+        workspace_id.to_string(),
+        title.to_string(),
+        NoteType::Markdown,
+        String::new(),
+        PathBuf::from(format!("/notes/{}.md", title)),
+    );
+    parent.sort_order = db
+        .next_note_sort_order(workspace_id, None)
+        .map_err(|e| format!("Failed to fetch notes: {}", e))?;
+    db.create_note(&parent)
+        .map_err(|e| format!("Failed to create note: {}", e))?;
+    Ok((parent.id, true))
+}
+
+/// 返回指定日期的日记，不存在时按配置创建
+///
+/// 模板不存在或不属于该工作空间时记录警告并创建空白日记。
+pub fn get_or_create_daily_note(
+    db: &DatabaseManager,
+    config: &NotesConfig,
+    templates_dir: &str,
+    workspace_id: &str,
+    date: NaiveDate,
+) -> Result<DailyNote, String> {
+    let _guard = DAILY_NOTE_LOCK.lock().unwrap();
+
+    let workspace = db
+        .get_workspace(workspace_id)
+        .map_err(|e| format!("Failed to fetch workspace: {}", e))?
+        .ok_or_else(|| format!("Workspace not found: {}", workspace_id))?;
+    let start = day_start(date);
+    let title = format_date(&start, Some(&config.daily_title_format), "%Y-%m-%d");
+
+    let (parent_id, parent_created) = match config
+        .daily_parent_title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+    {
+        Some(parent_title) => {
+            let (id, created) = ensure_daily_parent(db, workspace_id, parent_title)?;
+            (Some(id), created)
+        }
+        None => (None, false),
+    };
+
+    let existing = db
+        .find_child_note_by_title(workspace_id, parent_id.as_deref(), &title)
+        .map_err(|e| format!("Failed to fetch note: {}", e))?;
+    if let Some(note) = existing {
+        return Ok(DailyNote {
+            note,
+            created: false,
+            parent_created,
+        });
+    }
+
+    let template = config.daily_template_id.as_deref().and_then(|id| {
+        match find_note_template(db, templates_dir, id) {
+            Ok(t)
+                if t.workspace_id
+                    .as_deref()
+                    .is_none_or(|ws| ws == workspace_id) =>
+            {
+                Some(t)
+            }
+            Ok(_) => {
+                log::warn!("Daily note template {} belongs to another workspace", id);
+                None
+            }
+            Err(e) => {
+                log::warn!("{}", e);
+                None
+            }
+        }
+    });
+    let now = Local::now();
+    let now = if now.date_naive() == date { now } else { start };
+
+    let mut note = match template {
+        Some(template) => build_note_from_template(
+            &template,
+            workspace_id,
+            &workspace.name,
+            Some(&title),
+            &HashMap::new(),
+            now,
+        ),
+        None => Note::new(
+            workspace_id.to_string(),
+            title.clone(),
+            NoteType::Markdown,
+            String::new(),
+            PathBuf::from(format!("/notes/{}.md", title)),
+        ),
+    };
+    note.parent_id = parent_id;
+    note.sort_order = db
+        .next_note_sort_order(workspace_id, note.parent_id.as_deref())
+        .map_err(|e| format!("Failed to fetch notes: {}", e))?;
+    db.create_note(&note)
+        .map_err(|e| format!("Failed to create note: {}", e))?;
+
+    Ok(DailyNote {
+        note,
+        created: true,
+        parent_created,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::{NoteTemplate, Workspace};
+    use std::env;
+
+    #[test]
+    fn test_daily_note_and_calendar() {
+        let test_db_path =
+            env::temp_dir().join(format!("test_daily_note_{}.db", uuid::Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Journal".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+
+        let mut template = NoteTemplate::new(
+            workspace.id.clone(),
+            "Daily".to_string(),
+            NoteType::Markdown,
+            "{{title}}".to_string(),
+            "# {{title}}\n{{date:%A}} in {{workspace}}\n".to_string(),
+        );
+        template.tags = vec!["journal".to_string()];
+        db.create_note_template(&template).unwrap();
+
+        let config = NotesConfig {
+            daily_title_format: "%d.%m.%Y".to_string(),
+            daily_template_id: Some(template.id.clone()),
+            ..Default::default()
+        };
+        let date = parse_day("2024-02-29").unwrap();
+        let first = get_or_create_daily_note(&db, &config, "", &workspace.id, date).unwrap();
+        assert!(first.created && first.parent_created);
+        assert_eq!(first.note.title, "29.02.2024");
+        assert_eq!(first.note.content, "# 29.02.2024\nThursday in Journal\n");
+        assert_eq!(first.note.tags, ["journal"]);
+        let parent = db.get_note(first.note.parent_id.as_ref().unwrap()).unwrap();
+        assert_eq!(parent.unwrap().title, "Daily Notes");
+
+        let again = get_or_create_daily_note(&db, &config, "", &workspace.id, date).unwrap();
+        assert!(!again.created && !again.parent_created);
+        assert_eq!(again.note.id, first.note.id);
+        let next_day = parse_day("2024-03-01").unwrap();
+        let second = get_or_create_daily_note(&db, &config, "", &workspace.id, next_day).unwrap();
+        assert_eq!(second.note.parent_id, first.note.parent_id);
+        assert_eq!(second.note.sort_order, 1);
+
+        // 2 月 28 日创建，2 月 29 日修改
+        let mut note = Note::new(
+            workspace.id.clone(),
+            "Old".to_string(),
+            NoteType::Markdown,
+            String::new(),
+            PathBuf::from("/notes/Old.md"),
+        );
+        note.created_at = day_start(parse_day("2024-02-28").unwrap()).timestamp_millis() + 1000;
+        note.updated_at = day_start(date).timestamp_millis() + 1000;
+        db.create_note(&note).unwrap();
+
+        let calendar = note_calendar(
+            &db,
+            &workspace.id,
+            parse_day("2024-02-01").unwrap(),
+            parse_day("2024-02-29").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            calendar,
+            [
+                NoteCalendarDay {
+                    date: "2024-02-28".to_string(),
+                    created: 1,
+                    updated: 0,
+                },
+                NoteCalendarDay {
+                    date: "2024-02-29".to_string(),
+                    created: 0,
+                    updated: 1,
+                },
+            ]
+        );
+
+        let activity = notes_on_day(&db, &workspace.id, date).unwrap();
+        assert!(activity.created.is_empty());
+        assert_eq!(activity.updated.len(), 1);
+        assert_eq!(activity.updated[0].id, note.id);
+        assert!(parse_day("2024-13-01").is_err());
+    }
+}
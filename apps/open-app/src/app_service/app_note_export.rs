@@ -0,0 +1,545 @@
+//! 笔记导出
+//!
+//! 将工作区或某个笔记子树导出为 zip：Markdown 格式每篇笔记一个带 front matter 的 `.md`
+//! 文件，HTML 格式每篇笔记一个独立页面并生成目录页 index.html。两种格式都附带附件和
+//! manifest.json。有子笔记的笔记导出为同名文件加同名目录，`note://` 链接改写为相对路径。
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+use super::{
+    NOTE_LINK_SCHEME, escape_html, markdown_to_html, note_markdown_body, render_note_markdown,
+    sanitize_file_name, workspace_note_tree,
+};
+use crate::app_state::{DatabaseManager, Note, NoteTreeNode, NoteType};
+
+/// manifest.json 的格式版本
+const MANIFEST_VERSION: u32 = 1;
+
+const HTML_STYLE: &str = "body{max-width:760px;margin:2rem auto;padding:0 1rem;font-family:-apple-system,BlinkMacSystemFont,\"Segoe UI\",sans-serif;line-height:1.6;color:#222}\
+pre{background:#f5f5f5;padding:.75rem;overflow:auto}code{font-family:SFMono-Regular,Menlo,monospace}\
+table{border-collapse:collapse}th,td{border:1px solid #ddd;padding:.25rem .5rem}\
+blockquote{margin:0;padding-left:1rem;border-left:3px solid #ddd;color:#555}img{max-width:100%}\
+nav{font-size:.9rem}.wiki-link.unresolved{color:#999}";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteExportFormat {
+    Markdown,
+    Html,
+}
+
+impl NoteExportFormat {
+    pub fn as_str(&self) -> &str {
+        match self {
+            NoteExportFormat::Markdown => "markdown",
+            NoteExportFormat::Html => "html",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "markdown" => Some(NoteExportFormat::Markdown),
+            "html" => Some(NoteExportFormat::Html),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &str {
+        match self {
+            NoteExportFormat::Markdown => "md",
+            NoteExportFormat::Html => "html",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteExportReport {
+    pub notes: usize,
+    pub attachments: usize,
+    pub output_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifest {
+    pub version: u32,
+    pub format: NoteExportFormat,
+    pub exported_at: i64,
+    pub workspace_id: String,
+    pub workspace_name: String,
+    /// 只导出子树时为子树根笔记
+    pub root_note_id: Option<String>,
+    pub notes: Vec<ExportManifestNote>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifestNote {
+    pub id: String,
+    pub parent_id: Option<String>,
+    pub title: String,
+    pub note_type: NoteType,
+    /// 压缩包内的路径
+    pub path: String,
+    pub tags: Vec<String>,
+    pub attachments: Vec<String>,
+    pub is_favorited: bool,
+    pub is_pinned: bool,
+    pub is_archived: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// 导出的笔记及其在压缩包内的路径
+struct ExportEntry {
+    note_id: String,
+    path: String,
+}
+
+fn find_subtree(nodes: Vec<NoteTreeNode>, note_id: &str) -> Option<NoteTreeNode> {
+    for node in nodes {
+        if node.id == note_id {
+            return Some(node);
+        }
+        if let Some(found) = find_subtree(node.children, note_id) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+fn join_path(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+/// 按树结构分配路径，同一目录下重名时追加序号
+fn assign_paths(
+    nodes: &[NoteTreeNode],
+    dir: &str,
+    extension: &str,
+    entries: &mut Vec<ExportEntry>,
+) {
+    let mut used = HashSet::new();
+    for node in nodes {
+        let base = sanitize_file_name(&node.title);
+        let mut name = base.clone();
+        let mut n = 2;
+        while !used.insert(name.to_lowercase()) {
+            name = format!("{} ({})", base, n);
+            n += 1;
+        }
+        entries.push(ExportEntry {
+            note_id: node.id.clone(),
+            path: join_path(dir, &format!("{}.{}", name, extension)),
+        });
+        if !node.children.is_empty() {
+            assign_paths(&node.children, &join_path(dir, &name), extension, entries);
+        }
+    }
+}
+
+/// 从 `from` 文件所在目录到 `to` 的相对路径，均为压缩包内路径
+fn relative_path(from: &str, to: &str) -> String {
+    let from_dir: Vec<&str> = from.split('/').collect();
+    let from_dir = &from_dir[..from_dir.len() - 1];
+    let to_parts: Vec<&str> = to.split('/').collect();
+    let common = from_dir
+        .iter()
+        .zip(&to_parts[..to_parts.len() - 1])
+        .take_while(|(a, b)| a == b)
+        .count();
+    format!(
+        "{}{}",
+        "../".repeat(from_dir.len() - common),
+        to_parts[common..].join("/")
+    )
+}
+
+/// 编码链接中的空格等字符，Markdown 和 HTML 中都可以直接使用
+fn encode_href(path: &str) -> String {
+    path.replace('%', "%25")
+        .replace(' ', "%20")
+        .replace('#', "%23")
+        .replace('?', "%3F")
+        .replace('(', "%28")
+        .replace(')', "%29")
+}
+
+/// 替换内容中的 `note://<id>`，`resolve` 返回 None 时保持原样
+fn replace_note_urls<F>(content: &str, resolve: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(pos) = rest.find(NOTE_LINK_SCHEME) {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + NOTE_LINK_SCHEME.len()..];
+        let len = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .unwrap_or(after.len());
+        match resolve(&after[..len]) {
+            Some(href) => out.push_str(&href),
+            None => out.push_str(&rest[pos..pos + NOTE_LINK_SCHEME.len() + len]),
+        }
+        rest = &after[len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn html_page(title: &str, body: &str, index_href: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{title}</title>\n<style>{style}</style>\n</head>\n<body>\n<nav><a href=\"{index}\">Index</a></nav>\n<article>\n<h1>{title}</h1>\n{body}</article>\n</body>\n</html>\n",
+        title = escape_html(title),
+        style = HTML_STYLE,
+        index = index_href,
+        body = body
+    )
+}
+
+fn push_index_items(nodes: &[NoteTreeNode], paths: &HashMap<String, String>, out: &mut String) {
+    out.push_str("<ul>\n");
+    for node in nodes {
+        out.push_str("<li>");
+        if let Some(path) = paths.get(&node.id) {
+            out.push_str(&format!(
+                "<a href=\"{}\">{}</a>",
+                encode_href(path),
+                escape_html(&node.title)
+            ));
+        }
+        if !node.children.is_empty() {
+            out.push('\n');
+            push_index_items(&node.children, paths, out);
+        }
+        out.push_str("</li>\n");
+    }
+    out.push_str("</ul>\n");
+}
+
+fn zip_error(e: impl std::fmt::Display) -> String {
+    format!("Failed to write export archive: {}", e)
+}
+
+/// 导出工作区或 `root_note_id` 子树到 `output` 压缩包，`on_progress` 接收已导出数、总数和标题
+pub fn export_notes<F>(
+    db: &DatabaseManager,
+    workspace_id: &str,
+    root_note_id: Option<&str>,
+    format: &NoteExportFormat,
+    output: &Path,
+    on_progress: F,
+) -> Result<NoteExportReport, String>
+where
+    F: FnMut(usize, usize, &str),
+{
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let file = fs::File::create(output)
+        .map_err(|e| format!("Failed to create {}: {}", output.display(), e))?;
+
+    let result = write_export(
+        db,
+        workspace_id,
+        root_note_id,
+        format,
+        ZipWriter::new(file),
+        on_progress,
+    );
+    match result {
+        Ok((notes, attachments)) => Ok(NoteExportReport {
+            notes,
+            attachments,
+            output_path: output.to_string_lossy().to_string(),
+        }),
+        Err(e) => {
+            let _ = fs::remove_file(output);
+            Err(e)
+        }
+    }
+}
+
+fn write_export<F>(
+    db: &DatabaseManager,
+    workspace_id: &str,
+    root_note_id: Option<&str>,
+    format: &NoteExportFormat,
+    mut zip: ZipWriter<fs::File>,
+    mut on_progress: F,
+) -> Result<(usize, usize), String>
+where
+    F: FnMut(usize, usize, &str),
+{
+    let workspace = db
+        .get_workspace(workspace_id)
+        .map_err(|e| format!("Failed to fetch workspace: {}", e))?
+        .ok_or_else(|| format!("Workspace not found: {}", workspace_id))?;
+    let tree = workspace_note_tree(db, workspace_id)?;
+    let roots = match root_note_id {
+        Some(note_id) => vec![
+            find_subtree(tree, note_id)
+                .ok_or_else(|| format!("Note not found in workspace: {}", note_id))?,
+        ],
+        None => tree,
+    };
+
+    let mut entries = Vec::new();
+    assign_paths(&roots, "", format.extension(), &mut entries);
+    let paths: HashMap<String, String> = entries
+        .iter()
+        .map(|e| (e.note_id.clone(), e.path.clone()))
+        .collect();
+    let options = SimpleFileOptions::default();
+    let mut manifest = ExportManifest {
+        version: MANIFEST_VERSION,
+        format: format.clone(),
+        exported_at: Utc::now().timestamp_millis(),
+        workspace_id: workspace.id.clone(),
+        workspace_name: workspace.name.clone(),
+        root_note_id: root_note_id.map(str::to_string),
+        notes: Vec::new(),
+    };
+
+    let mut notes = Vec::new();
+    for entry in &entries {
+        if let Some(note) = db
+            .get_note(&entry.note_id)
+            .map_err(|e| format!("Failed to fetch note: {}", e))?
+        {
+            notes.push((entry, note));
+        }
+    }
+    // HTML 中的 `[[标题]]` 按标题解析，重名时取树中靠前的笔记
+    let mut titles: HashMap<String, String> = HashMap::new();
+    for (entry, note) in &notes {
+        titles
+            .entry(note.title.to_lowercase())
+            .or_insert_with(|| entry.path.clone());
+    }
+
+    let total = notes.len();
+    let mut written_files = HashSet::new();
+    for (i, (entry, note)) in notes.iter().enumerate() {
+        let mut content = note.content.clone();
+        let mut attachment_paths = Vec::new();
+        let files = db
+            .list_note_attachments(&note.id)
+            .map_err(|e| format!("Failed to fetch note attachments: {}", e))?;
+        for file in files {
+            let path = format!("attachments/{}/{}", file.id, sanitize_file_name(&file.name));
+            if written_files.insert(file.id.clone()) {
+                match fs::File::open(&file.stored_path) {
+                    Ok(mut source) => {
+                        zip.start_file(path.as_str(), options).map_err(zip_error)?;
+                        io::copy(&mut source, &mut zip).map_err(zip_error)?;
+                    }
+                    Err(e) => {
+                        log::warn!(
+                            "Skipping missing attachment {}: {}",
+                            file.stored_path.display(),
+                            e
+                        );
+                        continue;
+                    }
+                }
+            }
+            content = content.replace(
+                &file.stored_path.to_string_lossy().to_string(),
+                &relative_path(&entry.path, &path),
+            );
+            attachment_paths.push(path);
+        }
+
+        let note_href = |target: &str| encode_href(&relative_path(&entry.path, target));
+        let body = note_markdown_body(&Note {
+            content,
+            ..note.clone()
+        });
+        let body = replace_note_urls(&body, |id| paths.get(id).map(|p| note_href(p)));
+        let text = match format {
+            NoteExportFormat::Markdown => render_note_markdown(&Note {
+                content: body,
+                ..note.clone()
+            }),
+            NoteExportFormat::Html => {
+                let html = match note.note_type {
+                    NoteType::RichText => body,
+                    _ => markdown_to_html(&body, &|title: &str| {
+                        titles.get(&title.to_lowercase()).map(|p| note_href(p))
+                    }),
+                };
+                html_page(&note.title, &html, &note_href("index.html"))
+            }
+        };
+        zip.start_file(entry.path.as_str(), options)
+            .map_err(zip_error)?;
+        zip.write_all(text.as_bytes()).map_err(zip_error)?;
+
+        manifest.notes.push(ExportManifestNote {
+            id: note.id.clone(),
+            parent_id: note.parent_id.clone(),
+            title: note.title.clone(),
+            note_type: note.note_type.clone(),
+            path: entry.path.clone(),
+            tags: note.tags.clone(),
+            attachments: attachment_paths,
+            is_favorited: note.is_favorited,
+            is_pinned: note.is_pinned,
+            is_archived: note.is_archived,
+            created_at: note.created_at,
+            updated_at: note.updated_at,
+        });
+        on_progress(i + 1, total, &note.title);
+    }
+
+    if *format == NoteExportFormat::Html {
+        let mut index = String::new();
+        push_index_items(&roots, &paths, &mut index);
+        zip.start_file("index.html", options).map_err(zip_error)?;
+        zip.write_all(html_page(&workspace.name, &index, "index.html").as_bytes())
+            .map_err(zip_error)?;
+    }
+
+    let manifest_json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    zip.start_file("manifest.json", options)
+        .map_err(zip_error)?;
+    zip.write_all(manifest_json.as_bytes()).map_err(zip_error)?;
+    zip.finish().map_err(zip_error)?;
+
+    Ok((manifest.notes.len(), written_files.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::Workspace;
+    use std::env;
+    use std::io::Read;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    fn read_entry(archive: &mut zip::ZipArchive<fs::File>, name: &str) -> String {
+        let mut text = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        text
+    }
+
+    #[test]
+    fn test_export_markdown_and_html() {
+        let test_db_path = env::temp_dir().join(format!("test_note_export_{}.db", Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+
+        let new_note = |title: &str, note_type: NoteType, content: &str| {
+            Note::new(
+                workspace.id.clone(),
+                title.to_string(),
+                note_type,
+                content.to_string(),
+                PathBuf::new(),
+            )
+        };
+        let guide = new_note("Guide", NoteType::Markdown, "");
+        let mut child = new_note("Child: 1", NoteType::Markdown, "Back to [[Guide]]");
+        child.parent_id = Some(guide.id.clone());
+        let mut guide = guide;
+        guide.content = format!("See [child](note://{}) and [[Child: 1]]", child.id);
+        let mut duplicate = new_note("Guide", NoteType::Markdown, "second");
+        duplicate.sort_order = 1;
+        let mut map = new_note(
+            "Map",
+            NoteType::MindMap,
+            r#"{"root":{"text":"Root","children":[{"text":"Leaf"}]}}"#,
+        );
+        map.sort_order = 2;
+        for note in [&guide, &child, &duplicate, &map] {
+            db.create_note(note).unwrap();
+        }
+
+        let dir = env::temp_dir().join(format!("test_note_export_{}", Uuid::new_v4()));
+        let output = dir.join("workspace.zip");
+        let report = export_notes(
+            &db,
+            &workspace.id,
+            None,
+            &NoteExportFormat::Markdown,
+            &output,
+            |_, _, _| {},
+        )
+        .unwrap();
+        assert_eq!(report.notes, 4);
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&output).unwrap()).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "Guide (2).md",
+                "Guide.md",
+                "Guide/Child- 1.md",
+                "Map.md",
+                "manifest.json"
+            ]
+        );
+        let guide_md = read_entry(&mut archive, "Guide.md");
+        assert!(guide_md.starts_with(&format!("---\nid: {}\n", guide.id)));
+        assert!(guide_md.ends_with("See [child](Guide/Child-%201.md) and [[Child: 1]]"));
+        assert!(read_entry(&mut archive, "Map.md").ends_with("- Root\n  - Leaf\n"));
+        let manifest: ExportManifest =
+            serde_json::from_str(&read_entry(&mut archive, "manifest.json")).unwrap();
+        assert_eq!(manifest.notes.len(), 4);
+        assert_eq!(manifest.notes[1].path, "Guide/Child- 1.md");
+
+        let report = export_notes(
+            &db,
+            &workspace.id,
+            Some(&guide.id),
+            &NoteExportFormat::Html,
+            &output,
+            |_, _, _| {},
+        )
+        .unwrap();
+        assert_eq!(report.notes, 2);
+        let mut archive = zip::ZipArchive::new(fs::File::open(&output).unwrap()).unwrap();
+        let child_html = read_entry(&mut archive, "Guide/Child- 1.html");
+        assert!(child_html.contains("<a href=\"../index.html\">Index</a>"));
+        assert!(child_html.contains("<a class=\"wiki-link\" href=\"../Guide.html\">Guide</a>"));
+        let index = read_entry(&mut archive, "index.html");
+        assert!(index.contains("<a href=\"Guide/Child-%201.html\">Child: 1</a>"));
+
+        assert!(
+            export_notes(
+                &db,
+                &workspace.id,
+                Some("missing"),
+                &NoteExportFormat::Html,
+                &output,
+                |_, _, _| {},
+            )
+            .is_err()
+        );
+        assert!(!output.exists());
+    }
+}
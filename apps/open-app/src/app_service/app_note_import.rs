@@ -0,0 +1,880 @@
+//! Markdown 目录导入
+//!
+//! 导入 Obsidian 仓库或普通 Markdown 目录：目录生成父笔记，front matter 中的标签写入笔记，
+//! `[[链接]]`、`![[嵌入]]` 和相对路径链接解析为笔记标题或附件。每个源文件记录在
+//! note_import_sources 中，重复导入时更新已有的笔记和附件。
+
+use chrono::Utc;
+use git2::{ObjectType, Oid};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use super::{parse_front_matter, record_note_revision, split_front_matter, sync_note_links};
+use crate::app_state::{
+    DatabaseManager, ImportSource, ImportSourceType, ImportTargetType, ImportedFile, Note,
+    NoteMove, NoteType, NotesConfig,
+};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultImportReport {
+    pub folders: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub attachments: usize,
+    pub unresolved_links: usize,
+}
+
+/// 预解析的 Markdown 文件
+struct VaultNote {
+    path: PathBuf,
+    note_id: String,
+    existing: Option<Note>,
+    title: String,
+    tags: Vec<String>,
+    body: String,
+}
+
+/// 仓库内笔记和附件的查找表，按 Obsidian 的规则用文件名、相对路径或别名解析链接
+struct VaultIndex {
+    root: PathBuf,
+    notes: HashMap<String, usize>,
+    attachments: HashMap<String, PathBuf>,
+}
+
+/// 链接目标的规范化键：小写、统一分隔符、去掉 `.md` 扩展名
+fn link_key(target: &str) -> String {
+    let key = target
+        .trim()
+        .replace('\\', "/")
+        .trim_start_matches("./")
+        .trim_start_matches('/')
+        .to_lowercase();
+    key.strip_suffix(".md").map(str::to_string).unwrap_or(key)
+}
+
+fn relative_key(root: &Path, path: &Path) -> String {
+    link_key(&path.strip_prefix(root).unwrap_or(path).to_string_lossy())
+}
+
+impl VaultIndex {
+    fn new(
+        root: &Path,
+        notes: &[VaultNote],
+        aliases: &[Vec<String>],
+        attachments: &[PathBuf],
+    ) -> Self {
+        let mut index = Self {
+            root: root.to_path_buf(),
+            notes: HashMap::new(),
+            attachments: HashMap::new(),
+        };
+        // 相对路径优先，其次是文件名和别名，重名时取排序靠前的文件
+        for (i, note) in notes.iter().enumerate() {
+            index.notes.insert(relative_key(root, &note.path), i);
+        }
+        for (i, note) in notes.iter().enumerate() {
+            let stem = note.path.file_stem().unwrap_or_default().to_string_lossy();
+            index.notes.entry(link_key(&stem)).or_insert(i);
+            for alias in &aliases[i] {
+                index.notes.entry(link_key(alias)).or_insert(i);
+            }
+        }
+        for path in attachments {
+            index
+                .attachments
+                .insert(relative_key(root, path), path.clone());
+        }
+        for path in attachments {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            index
+                .attachments
+                .entry(name.to_lowercase())
+                .or_insert_with(|| path.clone());
+        }
+        index
+    }
+
+    /// 解析 wiki 链接目标，支持 `目录/笔记` 形式的部分路径
+    fn resolve_wiki(&self, target: &str) -> Option<WikiTarget> {
+        let key = link_key(target);
+        if key.is_empty() {
+            return None;
+        }
+        let last = key.rsplit('/').next().unwrap_or(&key).to_string();
+        for key in [&key, &last] {
+            if let Some(i) = self.notes.get(key) {
+                return Some(WikiTarget::Note(*i));
+            }
+            if let Some(path) = self.attachments.get(key) {
+                return Some(WikiTarget::Attachment(path.clone()));
+            }
+        }
+        None
+    }
+
+    /// 解析相对于笔记所在目录的 Markdown 链接
+    fn resolve_relative(&self, note_dir: &Path, href: &str) -> Option<WikiTarget> {
+        let path = fs::canonicalize(note_dir.join(href)).ok()?;
+        if !path.starts_with(&self.root) || !path.is_file() {
+            return None;
+        }
+        let key = relative_key(&self.root, &path);
+        if path.extension().is_some_and(|ext| ext == "md") {
+            self.notes.get(&key).map(|i| WikiTarget::Note(*i))
+        } else {
+            Some(WikiTarget::Attachment(path))
+        }
+    }
+}
+
+enum WikiTarget {
+    Note(usize),
+    Attachment(PathBuf),
+}
+
+pub(crate) fn hash_text(text: &str) -> String {
+    Oid::hash_object(ObjectType::Blob, text.as_bytes())
+        .map(|oid| oid.to_string())
+        .unwrap_or_default()
+}
+
+pub(crate) fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = bytes.get(i + 1..i + 3)
+            && let Some(byte) = std::str::from_utf8(hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// 复制被引用的附件到 files_dir，同一来源只复制一次
+pub(crate) struct AttachmentImporter<'a> {
+    db: &'a DatabaseManager,
+    workspace_id: &'a str,
+    source_type: ImportSourceType,
+    files_dir: PathBuf,
+    imported: HashMap<PathBuf, ImportedFile>,
+}
+
+impl<'a> AttachmentImporter<'a> {
+    pub(crate) fn new(
+        db: &'a DatabaseManager,
+        workspace_id: &'a str,
+        source_type: ImportSourceType,
+        files_dir: &Path,
+    ) -> Self {
+        Self {
+            db,
+            workspace_id,
+            source_type,
+            files_dir: files_dir.to_path_buf(),
+            imported: HashMap::new(),
+        }
+    }
+
+    /// 已导入的附件数
+    pub(crate) fn count(&self) -> usize {
+        self.imported.len()
+    }
+
+    /// 导入附件，`source_key` 是在多次导入之间保持不变的来源标识
+    pub(crate) fn import(
+        &mut self,
+        source: &Path,
+        source_key: &str,
+    ) -> Result<ImportedFile, String> {
+        if let Some(file) = self.imported.get(source) {
+            return Ok(file.clone());
+        }
+
+        let hash = Oid::hash_file(ObjectType::Blob, source)
+            .map_err(|e| format!("Failed to read attachment: {}", e))?
+            .to_string();
+        let size = fs::metadata(source)
+            .map_err(|e| format!("Failed to read attachment: {}", e))?
+            .len() as i64;
+        let mapping = self
+            .db
+            .get_import_source(self.workspace_id, source_key)
+            .map_err(|e| format!("Failed to fetch import source: {}", e))?
+            .filter(|m| m.target_type == ImportTargetType::Attachment);
+        let existing = match &mapping {
+            Some(m) => self
+                .db
+                .get_imported_file(&m.target_id)
+                .map_err(|e| format!("Failed to fetch imported file: {}", e))?,
+            None => None,
+        };
+
+        let file = match existing {
+            Some(file)
+                if mapping.as_ref().is_some_and(|m| m.content_hash == hash)
+                    && file.stored_path.exists() =>
+            {
+                file
+            }
+            Some(mut file) => {
+                copy_attachment(source, &file.stored_path)?;
+                file.size_bytes = size;
+                self.db
+                    .update_imported_file(&file)
+                    .map_err(|e| format!("Failed to update imported file: {}", e))?;
+                file
+            }
+            None => {
+                let name = source
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                let file_type = source
+                    .extension()
+                    .map(|ext| ext.to_string_lossy().to_lowercase())
+                    .unwrap_or_else(|| "file".to_string());
+                let mut file = ImportedFile::new(
+                    self.workspace_id.to_string(),
+                    name.clone(),
+                    source.to_path_buf(),
+                    PathBuf::new(),
+                    file_type,
+                    size,
+                );
+                file.stored_path = self
+                    .files_dir
+                    .join(self.workspace_id)
+                    .join("attachments")
+                    .join(&file.id)
+                    .join(&name);
+                copy_attachment(source, &file.stored_path)?;
+                self.db
+                    .create_imported_file(&file)
+                    .map_err(|e| format!("Failed to create imported file: {}", e))?;
+                file
+            }
+        };
+
+        self.db
+            .save_import_source(&ImportSource::new(
+                self.workspace_id.to_string(),
+                source_key.to_string(),
+                self.source_type.clone(),
+                ImportTargetType::Attachment,
+                file.id.clone(),
+                hash,
+            ))
+            .map_err(|e| format!("Failed to save import source: {}", e))?;
+        self.imported.insert(source.to_path_buf(), file.clone());
+        Ok(file)
+    }
+}
+
+fn copy_attachment(source: &Path, target: &Path) -> Result<(), String> {
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    fs::copy(source, target)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to copy attachment: {}", e))
+}
+
+pub(crate) fn attachment_link(file: &ImportedFile, label: Option<&str>, embed: bool) -> String {
+    format!(
+        "{}[{}](<{}>)",
+        if embed { "!" } else { "" },
+        label.unwrap_or(&file.name),
+        file.stored_path.display()
+    )
+}
+
+/// 改写一行中的 `[[...]]` 和 `![[...]]`
+fn rewrite_wiki_line(
+    line: &str,
+    index: &VaultIndex,
+    titles: &[String],
+    attachments: &mut AttachmentImporter,
+    referenced: &mut Vec<String>,
+) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find("[[") {
+        let Some(len) = rest[start + 2..].find("]]") else {
+            break;
+        };
+        let inner = &rest[start + 2..start + 2 + len];
+        let embed = rest[..start].ends_with('!');
+        let link_start = if embed { start - 1 } else { start };
+        let end = start + 4 + len;
+
+        let (target, alias) = match inner.split_once('|') {
+            Some((target, alias)) => (target, Some(alias.trim())),
+            None => (inner, None),
+        };
+        let (name, heading) = match target.split_once('#') {
+            Some((name, heading)) => (name, Some(heading)),
+            None => (target, None),
+        };
+
+        let replacement = match index.resolve_wiki(name) {
+            Some(WikiTarget::Note(i)) => {
+                let mut link = titles[i].clone();
+                if let Some(heading) = heading {
+                    link.push('#');
+                    link.push_str(heading);
+                }
+                if let Some(alias) = alias {
+                    link.push('|');
+                    link.push_str(alias);
+                }
+                Some(format!("[[{}]]", link))
+            }
+            Some(WikiTarget::Attachment(path)) => {
+                match attachments.import(&path, &path.to_string_lossy()) {
+                    Ok(file) => {
+                        referenced.push(file.id.clone());
+                        // `![[图片.png|300]]` 中的数字是显示宽度而不是标题
+                        let label = alias.filter(|a| !a.chars().all(|c| c.is_ascii_digit()));
+                        Some(attachment_link(&file, label, embed))
+                    }
+                    Err(e) => {
+                        log::warn!("{}", e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        match replacement {
+            Some(replacement) => {
+                out.push_str(&rest[..link_start]);
+                out.push_str(&replacement);
+            }
+            None => out.push_str(&rest[..end]),
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 逐个改写一行中的 `[文本](链接)` 和 `![说明](链接)`
+///
+/// `f` 接收文本、去掉尖括号/标题/锚点后的链接和是否为图片，返回 None 时保持原样。
+pub(crate) fn rewrite_markdown_links<F>(line: &str, mut f: F) -> String
+where
+    F: FnMut(&str, &str, bool) -> Option<String>,
+{
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(open) = rest.find('[') {
+        let Some(mid) = rest[open..].find("](").map(|i| open + i) else {
+            break;
+        };
+        let label = &rest[open + 1..mid];
+        if label.contains(['[', ']']) {
+            out.push_str(&rest[..open + 1]);
+            rest = &rest[open + 1..];
+            continue;
+        }
+        let Some(close) = rest[mid + 2..].find(')').map(|i| mid + 2 + i) else {
+            break;
+        };
+        let image = rest[..open].ends_with('!');
+        let start = if image { open - 1 } else { open };
+
+        let raw = rest[mid + 2..close].trim();
+        let href = match raw.strip_prefix('<').and_then(|h| h.strip_suffix('>')) {
+            Some(href) => href,
+            None => raw.split_whitespace().next().unwrap_or_default(),
+        };
+        let href = href.split('#').next().unwrap_or_default();
+
+        match f(label, href, image) {
+            Some(replacement) => {
+                out.push_str(&rest[..start]);
+                out.push_str(&replacement);
+            }
+            None => out.push_str(&rest[..close + 1]),
+        }
+        rest = &rest[close + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 逐行改写正文，围栏代码块和不含链接的行保持不变
+pub(crate) fn rewrite_lines_outside_fences<F>(body: &str, mut f: F) -> String
+where
+    F: FnMut(&str) -> String,
+{
+    let mut out = String::with_capacity(body.len());
+    let mut in_fence = false;
+    for line in body.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if in_fence || !line.contains('[') {
+            out.push_str(line);
+        } else {
+            out.push_str(&f(line));
+        }
+    }
+    out
+}
+
+/// 改写一行中指向仓库内文件的相对路径链接
+fn rewrite_markdown_line(
+    line: &str,
+    note_dir: &Path,
+    index: &VaultIndex,
+    titles: &[String],
+    attachments: &mut AttachmentImporter,
+    referenced: &mut Vec<String>,
+) -> String {
+    rewrite_markdown_links(line, |label, href, image| {
+        if href.is_empty() || href.contains("://") || href.starts_with("mailto:") {
+            return None;
+        }
+        match index.resolve_relative(note_dir, &percent_decode(href))? {
+            WikiTarget::Note(i) if label.is_empty() || label == titles[i] => {
+                Some(format!("[[{}]]", titles[i]))
+            }
+            WikiTarget::Note(i) => Some(format!("[[{}|{}]]", titles[i], label)),
+            WikiTarget::Attachment(path) => {
+                match attachments.import(&path, &path.to_string_lossy()) {
+                    Ok(file) => {
+                        referenced.push(file.id.clone());
+                        Some(attachment_link(&file, Some(label), image))
+                    }
+                    Err(e) => {
+                        log::warn!("{}", e);
+                        None
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// 改写正文中的链接，返回新正文和引用的附件 ID，围栏代码块保持不变
+fn rewrite_note_body(
+    note: &VaultNote,
+    index: &VaultIndex,
+    titles: &[String],
+    attachments: &mut AttachmentImporter,
+) -> (String, Vec<String>) {
+    let note_dir = note.path.parent().unwrap_or(&index.root);
+    let mut referenced = Vec::new();
+    let out = rewrite_lines_outside_fences(&note.body, |line| {
+        let line = rewrite_wiki_line(line, index, titles, attachments, &mut referenced);
+        rewrite_markdown_line(&line, note_dir, index, titles, attachments, &mut referenced)
+    });
+
+    referenced.sort();
+    referenced.dedup();
+    (out, referenced)
+}
+
+pub(crate) fn load_mapped_note(
+    db: &DatabaseManager,
+    workspace_id: &str,
+    source_path: &str,
+    target_type: ImportTargetType,
+) -> Result<Option<Note>, String> {
+    let mapping = db
+        .get_import_source(workspace_id, source_path)
+        .map_err(|e| format!("Failed to fetch import source: {}", e))?
+        .filter(|m| m.target_type == target_type);
+    match mapping {
+        Some(m) => db
+            .get_note(&m.target_id)
+            .map_err(|e| format!("Failed to fetch note: {}", e))
+            .map(|note| note.filter(|n| n.workspace_id == workspace_id)),
+        None => Ok(None),
+    }
+}
+
+/// 父节点或同级位置变化时移动已有笔记
+pub(crate) fn place_note(
+    db: &DatabaseManager,
+    note: &Note,
+    parent_id: Option<&String>,
+    sort_order: i32,
+) -> Result<(), String> {
+    if note.parent_id.as_ref() == parent_id && note.sort_order == sort_order {
+        return Ok(());
+    }
+    db.apply_note_moves(&[NoteMove {
+        note_id: note.id.clone(),
+        parent_id: parent_id.cloned(),
+        index: sort_order as usize,
+    }])
+    .map_err(|e| format!("Failed to move note: {}", e))
+}
+
+/// 导入 Markdown 目录，`on_progress` 接收已处理数、总数和当前文件
+pub fn import_markdown_vault<F>(
+    db: &DatabaseManager,
+    config: &NotesConfig,
+    files_dir: &Path,
+    workspace_id: &str,
+    vault: &Path,
+    mut on_progress: F,
+) -> Result<VaultImportReport, String>
+where
+    F: FnMut(usize, usize, &str),
+{
+    let root = fs::canonicalize(vault).map_err(|e| format!("Invalid vault path: {}", e))?;
+    if !root.is_dir() {
+        return Err(format!("Vault path is not a directory: {}", root.display()));
+    }
+    db.get_workspace(workspace_id)
+        .map_err(|e| format!("Failed to fetch workspace: {}", e))?
+        .ok_or_else(|| format!("Workspace not found: {}", workspace_id))?;
+    let source_type = ImportSourceType::Obsidian;
+
+    // 隐藏目录（.obsidian、.trash、.git）不导入
+    let mut dirs = Vec::new();
+    let mut markdown_files = Vec::new();
+    let mut other_files = Vec::new();
+    for entry in WalkBuilder::new(&root).build().flatten() {
+        let path = entry.path().to_path_buf();
+        if path == root {
+            continue;
+        }
+        if path.is_dir() {
+            dirs.push(path);
+        } else if path.extension().is_some_and(|ext| ext == "md") {
+            markdown_files.push(path);
+        } else if path.is_file() {
+            other_files.push(path);
+        }
+    }
+    markdown_files.sort();
+    other_files.sort();
+    // 只为包含 Markdown 文件的目录生成父笔记，附件目录不生成
+    dirs.retain(|dir| markdown_files.iter().any(|file| file.starts_with(dir)));
+    dirs.sort();
+
+    let total = dirs.len() + markdown_files.len();
+    let mut done = 0;
+    let mut report = VaultImportReport::default();
+    let mut folder_ids: HashMap<PathBuf, String> = HashMap::new();
+    let mut next_order: HashMap<PathBuf, i32> = HashMap::new();
+    let mut take_order = |dir: &Path| {
+        let order = next_order.entry(dir.to_path_buf()).or_insert(0);
+        *order += 1;
+        *order - 1
+    };
+
+    // 目录生成父笔记，排序保证父目录先于子目录
+    for dir in &dirs {
+        let source_path = dir.to_string_lossy().to_string();
+        let title = dir
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let parent_dir = dir.parent().unwrap_or(&root);
+        let parent_id = folder_ids.get(parent_dir).cloned();
+        let sort_order = take_order(parent_dir);
+
+        let note = match load_mapped_note(db, workspace_id, &source_path, ImportTargetType::Folder)?
+        {
+            Some(mut note) => {
+                if note.title != title {
+                    note.title = title;
+                    db.update_note(&note)
+                        .map_err(|e| format!("Failed to update note: {}", e))?;
+                }
+                place_note(db, &note, parent_id.as_ref(), sort_order)?;
+                note
+            }
+            None => {
+                let mut note = Note::new(
+                    workspace_id.to_string(),
+                    title,
+                    NoteType::Markdown,
+                    String::new(),
+                    dir.clone(),
+                );
+                note.parent_id = parent_id;
+                note.sort_order = sort_order;
+                db.create_note(&note)
+                    .map_err(|e| format!("Failed to create note: {}", e))?;
+                note
+            }
+        };
+        db.save_import_source(&ImportSource::new(
+            workspace_id.to_string(),
+            source_path.clone(),
+            source_type.clone(),
+            ImportTargetType::Folder,
+            note.id.clone(),
+            hash_text(""),
+        ))
+        .map_err(|e| format!("Failed to save import source: {}", e))?;
+        folder_ids.insert(dir.clone(), note.id);
+        report.folders += 1;
+        done += 1;
+        on_progress(done, total, &source_path);
+    }
+
+    // 先解析全部文件，链接改写需要知道每个文件导入后的标题
+    let mut notes = Vec::new();
+    let mut aliases = Vec::new();
+    for path in &markdown_files {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let (yaml, body) = split_front_matter(&text);
+        let fields = yaml.map(parse_front_matter).unwrap_or_default();
+
+        let stem = path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let title = fields
+            .get("title")
+            .and_then(|v| v.as_text())
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .unwrap_or(stem);
+        let mut tags: Vec<String> = Vec::new();
+        for key in ["tags", "tag"] {
+            for tag in fields.get(key).map(|v| v.as_list()).unwrap_or_default() {
+                let tag = tag.trim_start_matches('#').to_string();
+                if !tag.is_empty() && !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+        }
+        let mut note_aliases = Vec::new();
+        for key in ["aliases", "alias"] {
+            note_aliases.extend(fields.get(key).map(|v| v.as_list()).unwrap_or_default());
+        }
+
+        let existing = load_mapped_note(
+            db,
+            workspace_id,
+            &path.to_string_lossy(),
+            ImportTargetType::Note,
+        )?;
+        notes.push(VaultNote {
+            path: path.clone(),
+            note_id: existing
+                .as_ref()
+                .map(|n| n.id.clone())
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+            existing,
+            title,
+            tags,
+            body: body.trim_start_matches(['\r', '\n']).to_string(),
+        });
+        aliases.push(note_aliases);
+    }
+
+    let index = VaultIndex::new(&root, &notes, &aliases, &other_files);
+    let titles: Vec<String> = notes.iter().map(|n| n.title.clone()).collect();
+    let mut attachments = AttachmentImporter::new(db, workspace_id, source_type.clone(), files_dir);
+
+    for vault_note in &notes {
+        let source_path = vault_note.path.to_string_lossy().to_string();
+        let (content, attachment_ids) =
+            rewrite_note_body(vault_note, &index, &titles, &mut attachments);
+        let parent_dir = vault_note.path.parent().unwrap_or(&root);
+        let parent_id = folder_ids.get(parent_dir).cloned();
+        let sort_order = take_order(parent_dir);
+
+        match &vault_note.existing {
+            Some(existing) => {
+                if existing.title != vault_note.title
+                    || existing.content != content
+                    || existing.tags != vault_note.tags
+                {
+                    let mut note = existing.clone();
+                    note.title = vault_note.title.clone();
+                    note.content = content.clone();
+                    note.tags = vault_note.tags.clone();
+                    note.updated_at = Utc::now().timestamp_millis();
+                    db.update_note(&note)
+                        .map_err(|e| format!("Failed to update note: {}", e))?;
+                    if let Err(e) = record_note_revision(db, config, &note) {
+                        log::warn!("{}", e);
+                    }
+                    report.updated += 1;
+                } else {
+                    report.unchanged += 1;
+                }
+                place_note(db, existing, parent_id.as_ref(), sort_order)?;
+            }
+            None => {
+                let mut note = Note::new(
+                    workspace_id.to_string(),
+                    vault_note.title.clone(),
+                    NoteType::Markdown,
+                    content.clone(),
+                    vault_note.path.clone(),
+                );
+                note.id = vault_note.note_id.clone();
+                note.tags = vault_note.tags.clone();
+                note.parent_id = parent_id;
+                note.sort_order = sort_order;
+                db.create_note(&note)
+                    .map_err(|e| format!("Failed to create note: {}", e))?;
+                if let Err(e) = record_note_revision(db, config, &note) {
+                    log::warn!("{}", e);
+                }
+                report.created += 1;
+            }
+        }
+
+        db.replace_note_attachments(&vault_note.note_id, &attachment_ids)
+            .map_err(|e| format!("Failed to save note attachments: {}", e))?;
+        db.save_import_source(&ImportSource::new(
+            workspace_id.to_string(),
+            source_path.clone(),
+            source_type.clone(),
+            ImportTargetType::Note,
+            vault_note.note_id.clone(),
+            hash_text(&content),
+        ))
+        .map_err(|e| format!("Failed to save import source: {}", e))?;
+
+        done += 1;
+        on_progress(done, total, &source_path);
+    }
+    report.attachments = attachments.count();
+
+    // 所有笔记就位后再解析双链，前面的笔记才能链接到后导入的笔记
+    for vault_note in &notes {
+        let Some(note) = db
+            .get_note(&vault_note.note_id)
+            .map_err(|e| format!("Failed to fetch note: {}", e))?
+        else {
+            continue;
+        };
+        sync_note_links(db, &note)?;
+        let links = db
+            .list_note_links(&note.id)
+            .map_err(|e| format!("Failed to fetch note links: {}", e))?;
+        report.unresolved_links += links.iter().filter(|l| l.target_id.is_none()).count();
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::Workspace;
+    use std::env;
+
+    #[test]
+    fn test_import_vault_is_idempotent() {
+        let test_db_path = env::temp_dir().join(format!("test_vault_import_{}.db", Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+        let config = NotesConfig::default();
+
+        let vault = env::temp_dir().join(format!("test_vault_{}", Uuid::new_v4()));
+        let files_dir = env::temp_dir().join(format!("test_vault_files_{}", Uuid::new_v4()));
+        fs::create_dir_all(vault.join("Projects/assets")).unwrap();
+        fs::create_dir_all(vault.join(".obsidian")).unwrap();
+        fs::write(vault.join(".obsidian/app.json"), "{}").unwrap();
+        fs::write(
+            vault.join("Index.md"),
+            "---\ntags: [home, '#daily']\n---\nSee [[Projects/Roadmap#Q1|plan]], [[rm]], \
+             [spec](Projects/Roadmap.md) and [[Missing]].\n![[diagram.png|300]]\n```\n[[Roadmap]]\n```\n",
+        )
+        .unwrap();
+        fs::write(
+            vault.join("Projects/Roadmap.md"),
+            "---\ntitle: Roadmap 2025\naliases:\n  - rm\n---\n![chart](assets/diagram.png)\n",
+        )
+        .unwrap();
+        fs::write(vault.join("Projects/assets/diagram.png"), [0u8, 1, 2]).unwrap();
+
+        let report = import_markdown_vault(
+            &db,
+            &config,
+            &files_dir,
+            &workspace.id,
+            &vault,
+            |_, _, _| {},
+        )
+        .unwrap();
+        assert_eq!(
+            (report.folders, report.created, report.attachments),
+            (1, 2, 1)
+        );
+        assert_eq!(report.unresolved_links, 1);
+
+        let notes = db.list_notes(&workspace.id).unwrap();
+        assert_eq!(notes.len(), 3);
+        let index = notes.iter().find(|n| n.title == "Index").unwrap();
+        let roadmap = notes.iter().find(|n| n.title == "Roadmap 2025").unwrap();
+        let projects = notes.iter().find(|n| n.title == "Projects").unwrap();
+        assert_eq!(index.tags, vec!["home", "daily"]);
+        assert_eq!(roadmap.parent_id.as_deref(), Some(projects.id.as_str()));
+        assert!(index.content.starts_with(
+            "See [[Roadmap 2025#Q1|plan]], [[Roadmap 2025]], [[Roadmap 2025|spec]] and [[Missing]]."
+        ));
+        assert!(index.content.contains("```\n[[Roadmap]]\n```"));
+
+        let attachments = db.list_note_attachments(&index.id).unwrap();
+        assert_eq!(attachments.len(), 1);
+        assert!(attachments[0].stored_path.exists());
+        assert!(index.content.contains(&format!(
+            "![diagram.png](<{}>)",
+            attachments[0].stored_path.display()
+        )));
+        assert_eq!(
+            db.list_note_attachments(&roadmap.id).unwrap()[0].id,
+            attachments[0].id
+        );
+        assert_eq!(db.list_backlinks(&roadmap.id).unwrap().len(), 3);
+
+        // 再次导入只更新改动的笔记
+        fs::write(
+            vault.join("Projects/Roadmap.md"),
+            "---\ntitle: Roadmap 2025\naliases: [rm]\n---\nv2\n",
+        )
+        .unwrap();
+        let report = import_markdown_vault(
+            &db,
+            &config,
+            &files_dir,
+            &workspace.id,
+            &vault,
+            |_, _, _| {},
+        )
+        .unwrap();
+        assert_eq!((report.created, report.updated), (0, 1));
+        assert_eq!(db.list_notes(&workspace.id).unwrap().len(), 3);
+        assert_eq!(db.list_imported_files(&workspace.id).unwrap().len(), 1);
+
+        let _ = fs::remove_dir_all(&vault);
+        let _ = fs::remove_dir_all(&files_dir);
+    }
+}
@@ -0,0 +1,669 @@
+//! 笔记大纲、字数和摘要
+//!
+//! 笔记保存后由后台任务重新分析：CJK 字符按字计数、其他文字按词计数；Markdown 和富文本
+//! 的标题组成大纲；抽取式摘要取首句和关键词得分最高的句子。配置了 LLM 时再为较长的笔记
+//! 生成 LLM 摘要，失败或内容已变化时保留抽取式摘要。
+
+use chrono::Utc;
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use super::{hash_text, html_to_text, is_cjk, note_markdown_body, rewrite_markdown_links};
+use crate::app_state::{
+    AppState, DatabaseManager, LlmConfig, Note, NoteInsight, NoteOutlineItem, NoteType,
+};
+
+/// 抽取式摘要的最大字符数
+const SUMMARY_MAX_CHARS: usize = 240;
+/// 抽取式摘要最多包含的句子数
+const SUMMARY_MAX_SENTENCES: usize = 3;
+/// 字数少于该值的笔记不生成 LLM 摘要
+const LLM_SUMMARY_MIN_WORDS: i32 = 150;
+/// 同一篇笔记两次 LLM 摘要请求的最小间隔，避免连续编辑时反复请求
+const LLM_SUMMARY_INTERVAL_MS: i64 = 10 * 60 * 1000;
+/// 发送给 LLM 的正文最大字符数
+const LLM_INPUT_MAX_CHARS: usize = 12_000;
+/// 单次 LLM 请求的超时，避免无响应的服务阻塞后台分析
+const LLM_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// 每轮后台分析处理的笔记数
+const INSIGHT_BATCH_SIZE: usize = 50;
+/// 保存后等待连续编辑结束的时间
+const INSIGHT_DEBOUNCE: Duration = Duration::from_secs(2);
+/// 没有保存通知时定期扫描，覆盖导入和文件同步等写入
+const INSIGHT_RESCAN_INTERVAL: Duration = Duration::from_secs(60);
+
+const STOP_WORDS: [&str; 32] = [
+    "the", "and", "for", "are", "but", "not", "you", "all", "any", "can", "had", "her", "was",
+    "one", "our", "out", "has", "have", "this", "that", "with", "from", "they", "will", "would",
+    "there", "their", "what", "about", "which", "when", "into",
+];
+
+lazy_static! {
+    static ref NOTE_INSIGHT_SIGNAL: Notify = Notify::new();
+}
+
+/// CJK 字符每个算一个字，其他字母数字按连续片段算一个词
+pub fn count_words(text: &str) -> usize {
+    let mut count = 0;
+    let mut in_word = false;
+    for c in text.chars() {
+        if is_cjk(c) {
+            in_word = false;
+            if c.is_alphanumeric() {
+                count += 1;
+            }
+        } else if c.is_alphanumeric() || (in_word && matches!(c, '\'' | '’' | '-' | '_')) {
+            if !in_word {
+                count += 1;
+                in_word = true;
+            }
+        } else {
+            in_word = false;
+        }
+    }
+    count
+}
+
+/// GitHub 风格的标题锚点
+fn heading_anchor(text: &str, used: &mut HashMap<String, usize>) -> String {
+    let slug: String = text
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            c if c.is_alphanumeric() || c == '_' || c == '-' => Some(c),
+            c if c.is_whitespace() => Some('-'),
+            _ => None,
+        })
+        .collect();
+    let count = used.entry(slug.clone()).or_insert(0);
+    *count += 1;
+    if *count == 1 {
+        slug
+    } else {
+        format!("{}-{}", slug, *count - 1)
+    }
+}
+
+/// `[[目标|显示文本]]` 只保留显示文本
+fn strip_wiki_links(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find("[[") {
+        let Some(end) = rest[start..].find("]]").map(|i| start + i) else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let inner = &rest[start + 2..end];
+        let label = inner.rsplit('|').next().unwrap_or(inner);
+        out.push_str(label.split('#').next().unwrap_or(label));
+        rest = &rest[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 去掉行内链接地址和强调符号
+fn markdown_inline_text(line: &str) -> String {
+    let line = rewrite_markdown_links(line, |label, _, image| {
+        Some(if image {
+            String::new()
+        } else {
+            label.to_string()
+        })
+    });
+    strip_wiki_links(&line)
+        .replace("**", "")
+        .replace("__", "")
+        .replace("~~", "")
+        .replace('`', "")
+}
+
+/// ATX 标题的级别和文本
+fn markdown_heading(line: &str) -> Option<(u8, &str)> {
+    let trimmed = line.trim_start();
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let level = trimmed.chars().take_while(|c| *c == '#').count();
+    let rest = &trimmed[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    let text = rest.trim().trim_end_matches('#').trim_end();
+    Some((level as u8, text))
+}
+
+/// 富文本中的 `<h1>`..`<h6>`，返回 (级别, 文本, 起始位置, 结束位置)
+fn rich_text_headings(html: &str) -> Vec<(u8, String, usize, usize)> {
+    let lower = html.to_ascii_lowercase();
+    let mut headings = Vec::new();
+    let mut offset = 0;
+    while let Some(pos) = lower[offset..].find("<h") {
+        let start = offset + pos;
+        let level = lower[start + 2..]
+            .chars()
+            .next()
+            .and_then(|c| c.to_digit(10));
+        let boundary = lower[start + 3..].starts_with(['>', ' ', '\t', '\n']);
+        let (Some(level @ 1..=6), true) = (level, boundary) else {
+            offset = start + 2;
+            continue;
+        };
+        let close_tag = format!("</h{}", level);
+        let (Some(open_end), Some(close)) = (
+            lower[start..].find('>').map(|i| start + i + 1),
+            lower[start..].find(&close_tag).map(|i| start + i),
+        ) else {
+            break;
+        };
+        let end = lower[close..]
+            .find('>')
+            .map_or(lower.len(), |i| close + i + 1);
+        if open_end <= close {
+            let text = html_to_text(&html[open_end..close]).trim().to_string();
+            headings.push((level as u8, text, start, end));
+        }
+        offset = end;
+    }
+    headings
+}
+
+/// 笔记的标题大纲，代码笔记没有大纲
+pub fn note_outline(note: &Note) -> Vec<NoteOutlineItem> {
+    let headings: Vec<(u8, String)> = match note.note_type {
+        NoteType::Code => Vec::new(),
+        NoteType::RichText => rich_text_headings(&note.content)
+            .into_iter()
+            .map(|(level, text, _, _)| (level, text))
+            .collect(),
+        _ => {
+            let mut in_fence = false;
+            let mut headings = Vec::new();
+            for line in note_markdown_body(note).lines() {
+                let trimmed = line.trim_start();
+                if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                    in_fence = !in_fence;
+                } else if !in_fence && let Some((level, text)) = markdown_heading(line) {
+                    headings.push((level, markdown_inline_text(text)));
+                }
+            }
+            headings
+        }
+    };
+
+    let mut used = HashMap::new();
+    headings
+        .into_iter()
+        .filter(|(_, text)| !text.is_empty())
+        .map(|(level, text)| NoteOutlineItem {
+            level,
+            anchor: heading_anchor(&text, &mut used),
+            text,
+        })
+        .collect()
+}
+
+/// 用于统计字数的纯文本：链接只保留文本，富文本去掉标签
+fn note_word_text(note: &Note) -> String {
+    match note.note_type {
+        NoteType::RichText => html_to_text(&note.content),
+        NoteType::Code => note.content.clone(),
+        _ => note_markdown_body(note)
+            .lines()
+            .map(markdown_inline_text)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// 用于摘要的段落，跳过标题、代码块和表格，列表项各自成段
+fn note_paragraphs(note: &Note) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut flush = |current: &mut Vec<String>| {
+        if !current.is_empty() {
+            paragraphs.push(current.join(" "));
+            current.clear();
+        }
+    };
+
+    match note.note_type {
+        NoteType::Code => {}
+        NoteType::RichText => {
+            let mut prose = String::new();
+            let mut last = 0;
+            for (_, _, start, end) in rich_text_headings(&note.content) {
+                prose.push_str(&note.content[last..start]);
+                prose.push_str("<p>");
+                last = end;
+            }
+            prose.push_str(&note.content[last..]);
+            for line in html_to_text(&prose).lines() {
+                current.push(line.trim().to_string());
+                flush(&mut current);
+            }
+        }
+        _ => {
+            let mut in_fence = false;
+            for line in note_markdown_body(note).lines() {
+                let trimmed = line.trim();
+                if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                    in_fence = !in_fence;
+                    flush(&mut current);
+                    continue;
+                }
+                if in_fence
+                    || trimmed.is_empty()
+                    || trimmed.starts_with('|')
+                    || markdown_heading(line).is_some()
+                {
+                    flush(&mut current);
+                    continue;
+                }
+
+                let mut text = trimmed.trim_start_matches('>').trim_start();
+                let list_item = ["- ", "* ", "+ "]
+                    .iter()
+                    .find_map(|marker| text.strip_prefix(marker))
+                    .or_else(|| {
+                        let digits = text.chars().take_while(|c| c.is_ascii_digit()).count();
+                        (digits > 0)
+                            .then(|| text[digits..].strip_prefix(". "))
+                            .flatten()
+                    });
+                if let Some(item) = list_item {
+                    flush(&mut current);
+                    text = item;
+                    for task in ["[ ] ", "[x] ", "[X] "] {
+                        text = text.strip_prefix(task).unwrap_or(text);
+                    }
+                }
+                current.push(markdown_inline_text(text).trim().to_string());
+                if list_item.is_some() {
+                    flush(&mut current);
+                }
+            }
+        }
+    }
+    flush(&mut current);
+    paragraphs.retain(|p| count_words(p) > 0);
+    paragraphs
+}
+
+/// 按句末标点拆分句子，英文标点后需要空白
+fn split_sentences(paragraph: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = paragraph.chars().peekable();
+    while let Some(c) = chars.next() {
+        current.push(c);
+        let end = match c {
+            '。' | '！' | '？' => true,
+            '.' | '!' | '?' => chars.peek().is_none_or(|next| next.is_whitespace()),
+            _ => false,
+        };
+        if end {
+            sentences.push(current.trim().to_string());
+            current.clear();
+        }
+    }
+    if !current.trim().is_empty() {
+        sentences.push(current.trim().to_string());
+    }
+    sentences.retain(|s| count_words(s) > 0);
+    sentences
+}
+
+/// 关键词：长度不小于 3 的非停用词，以及 CJK 相邻两字
+fn sentence_terms(text: &str) -> HashSet<String> {
+    let mut terms = HashSet::new();
+    let mut word = String::new();
+    let mut prev_cjk: Option<char> = None;
+    for c in text.chars().chain(std::iter::once(' ')) {
+        if is_cjk(c) && c.is_alphanumeric() {
+            if let Some(prev) = prev_cjk {
+                terms.insert(format!("{}{}", prev, c));
+            }
+            prev_cjk = Some(c);
+        } else {
+            prev_cjk = None;
+        }
+        if c.is_alphanumeric() && !is_cjk(c) {
+            word.extend(c.to_lowercase());
+        } else if !word.is_empty() {
+            if word.chars().count() >= 3 && !STOP_WORDS.contains(&word.as_str()) {
+                terms.insert(std::mem::take(&mut word));
+            }
+            word.clear();
+        }
+    }
+    terms
+}
+
+fn join_sentences(sentences: &[&str]) -> String {
+    let mut out = String::new();
+    for sentence in sentences {
+        let needs_space = out
+            .chars()
+            .next_back()
+            .is_some_and(|c| !is_cjk(c) && !c.is_whitespace());
+        if needs_space {
+            out.push(' ');
+        }
+        out.push_str(sentence);
+    }
+    out
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", truncated.trim_end())
+}
+
+/// 抽取式摘要：首句加上关键词得分最高的句子，按原文顺序排列
+///
+/// 句子得分为所含关键词在全文中的出现次数之和除以关键词数的平方根，标题中的词额外加权。
+pub fn extractive_summary(note: &Note) -> String {
+    let sentences: Vec<String> = note_paragraphs(note)
+        .iter()
+        .flat_map(|p| split_sentences(p))
+        .collect();
+    let Some(first) = sentences.first() else {
+        return String::new();
+    };
+    if first.chars().count() >= SUMMARY_MAX_CHARS || sentences.len() == 1 {
+        return truncate_chars(first, SUMMARY_MAX_CHARS);
+    }
+
+    let terms_by_sentence: Vec<HashSet<String>> =
+        sentences.iter().map(|s| sentence_terms(s)).collect();
+    let mut frequency: HashMap<&str, f64> = HashMap::new();
+    for terms in &terms_by_sentence {
+        for term in terms {
+            *frequency.entry(term.as_str()).or_default() += 1.0;
+        }
+    }
+    let heading_terms: HashSet<String> = note_outline(note)
+        .iter()
+        .flat_map(|item| sentence_terms(&item.text))
+        .collect();
+    for term in &heading_terms {
+        if let Some(freq) = frequency.get_mut(term.as_str()) {
+            *freq += 1.0;
+        }
+    }
+
+    let mut ranked: Vec<(usize, f64)> = terms_by_sentence
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(index, terms)| {
+            let total: f64 = terms.iter().map(|t| frequency[t.as_str()] - 1.0).sum();
+            let score = if terms.is_empty() {
+                0.0
+            } else {
+                total / (terms.len() as f64).sqrt()
+            };
+            (index, score)
+        })
+        .filter(|(_, score)| *score > 0.0)
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut selected = vec![0];
+    let mut length = first.chars().count();
+    for (index, _) in ranked {
+        if selected.len() >= SUMMARY_MAX_SENTENCES {
+            break;
+        }
+        let sentence_len = sentences[index].chars().count() + 1;
+        if length + sentence_len <= SUMMARY_MAX_CHARS {
+            selected.push(index);
+            length += sentence_len;
+        }
+    }
+    selected.sort_unstable();
+    let parts: Vec<&str> = selected.iter().map(|&i| sentences[i].as_str()).collect();
+    join_sentences(&parts)
+}
+
+/// 计算笔记的大纲、字数和抽取式摘要，不含 LLM 摘要
+pub fn analyze_note(note: &Note) -> NoteInsight {
+    NoteInsight {
+        note_id: note.id.clone(),
+        outline: note_outline(note),
+        word_count: count_words(&note_word_text(note)) as i32,
+        extractive_summary: extractive_summary(note),
+        content_hash: hash_text(&format!("{}\n{}", note.note_type.as_str(), note.content)),
+        source_updated_at: note.updated_at,
+        llm_summary: None,
+        llm_model: None,
+        llm_content_hash: None,
+        llm_generated_at: None,
+        computed_at: Utc::now().timestamp_millis(),
+    }
+}
+
+/// 分析并保存，返回包含已有 LLM 摘要的完整结果
+pub fn refresh_note_insight(db: &DatabaseManager, note: &Note) -> Result<NoteInsight, String> {
+    let insight = analyze_note(note);
+    db.save_note_insight(&insight)
+        .map_err(|e| format!("Failed to save note insight: {}", e))?;
+    Ok(db
+        .get_note_insight(&note.id)
+        .map_err(|e| format!("Failed to fetch note insight: {}", e))?
+        .unwrap_or(insight))
+}
+
+/// 重新分析所有过期的笔记，返回处理数
+pub fn refresh_stale_note_insights(db: &DatabaseManager) -> Result<usize, String> {
+    let mut refreshed = 0;
+    loop {
+        let notes = db
+            .list_notes_with_stale_insight(INSIGHT_BATCH_SIZE)
+            .map_err(|e| format!("Failed to fetch notes: {}", e))?;
+        if notes.is_empty() {
+            return Ok(refreshed);
+        }
+        for note in &notes {
+            let insight = analyze_note(note);
+            db.save_note_insight(&insight)
+                .map_err(|e| format!("Failed to save note insight: {}", e))?;
+        }
+        refreshed += notes.len();
+    }
+}
+
+/// 调用 OpenAI 兼容的 `/chat/completions` 接口生成摘要
+pub async fn summarize_with_llm(
+    config: &LlmConfig,
+    title: &str,
+    text: &str,
+) -> Result<String, String> {
+    let url = format!("{}/chat/completions", config.base_url.trim_end_matches('/'));
+    let body = serde_json::json!({
+        "model": config.model,
+        "temperature": 0.2,
+        "messages": [
+            {
+                "role": "system",
+                "content": "Summarize the user's note in at most three sentences. \
+                            Reply in the same language as the note, with the summary only.",
+            },
+            {
+                "role": "user",
+                "content": format!("# {}\n\n{}", title, truncate_chars(text, LLM_INPUT_MAX_CHARS)),
+            },
+        ],
+    });
+
+    let client = reqwest::Client::builder()
+        .timeout(LLM_REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let mut request = client.post(&url).json(&body);
+    if let Some(api_key) = &config.api_key {
+        request = request.bearer_auth(api_key);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach LLM at {}: {}", config.base_url, e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("LLM request failed ({}): {}", status, text));
+    }
+
+    let value: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse LLM response: {}", e))?;
+    value["choices"][0]["message"]["content"]
+        .as_str()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "LLM response has no content".to_string())
+}
+
+/// 为内容有变化的较长笔记生成 LLM 摘要，返回生效的摘要数
+pub async fn refresh_llm_summaries(
+    db: &DatabaseManager,
+    config: &LlmConfig,
+) -> Result<usize, String> {
+    let attempted_before = Utc::now().timestamp_millis() - LLM_SUMMARY_INTERVAL_MS;
+    let candidates = db
+        .list_llm_summary_candidates(LLM_SUMMARY_MIN_WORDS, attempted_before, INSIGHT_BATCH_SIZE)
+        .map_err(|e| format!("Failed to fetch notes: {}", e))?;
+
+    let mut saved = 0;
+    for (note_id, content_hash) in candidates {
+        let Some(note) = db
+            .get_note(&note_id)
+            .map_err(|e| format!("Failed to fetch note: {}", e))?
+        else {
+            continue;
+        };
+        match summarize_with_llm(config, &note.title, &note_word_text(&note)).await {
+            Ok(summary) => {
+                let applied = db
+                    .save_note_llm_summary(&note_id, &content_hash, &summary, &config.model)
+                    .map_err(|e| format!("Failed to save note summary: {}", e))?;
+                if applied {
+                    saved += 1;
+                }
+            }
+            Err(e) => {
+                log::warn!("Failed to summarize note {}: {}", note_id, e);
+                db.mark_note_llm_attempt(&note_id)
+                    .map_err(|e| format!("Failed to save note summary: {}", e))?;
+            }
+        }
+    }
+    Ok(saved)
+}
+
+/// 笔记保存后调用，唤醒后台分析
+pub fn notify_note_saved() {
+    NOTE_INSIGHT_SIGNAL.notify_one();
+}
+
+/// 启动后台分析任务：收到保存通知或定期扫描时重新分析过期的笔记
+pub fn start_note_insight_worker(state: AppState) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let db = state.db();
+            let result =
+                tauri::async_runtime::spawn_blocking(move || refresh_stale_note_insights(&db))
+                    .await;
+            match result {
+                Ok(Ok(count)) if count > 0 => log::debug!("Note insights refreshed: {}", count),
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => log::warn!("{}", e),
+                Err(e) => log::warn!("Note insight task failed: {}", e),
+            }
+
+            if let Some(llm) = state.config().llm.filter(LlmConfig::is_configured)
+                && let Err(e) = refresh_llm_summaries(&state.db(), &llm).await
+            {
+                log::warn!("{}", e);
+            }
+
+            let _ =
+                tokio::time::timeout(INSIGHT_RESCAN_INTERVAL, NOTE_INSIGHT_SIGNAL.notified()).await;
+            tokio::time::sleep(INSIGHT_DEBOUNCE).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn markdown_note(content: &str) -> Note {
+        Note::new(
+            "ws".to_string(),
+            "Note".to_string(),
+            NoteType::Markdown,
+            content.to_string(),
+            PathBuf::from("/notes/Note.md"),
+        )
+    }
+
+    #[test]
+    fn test_word_count_and_outline() {
+        assert_eq!(count_words("Hello, world! It's a well-known fact."), 6);
+        assert_eq!(count_words("异步编程，很有用。Rust async"), 9);
+
+        let note = markdown_note(
+            "# Intro\nSee [the docs](https://example.com/a/b/c) and [[Other note|other]].\n\
+             ```rust\n# not a heading\n```\n## Setup ##\n## Setup\n### 安装 步骤\n",
+        );
+        let outline = note_outline(&note);
+        let anchors: Vec<(u8, &str)> = outline
+            .iter()
+            .map(|item| (item.level, item.anchor.as_str()))
+            .collect();
+        assert_eq!(
+            anchors,
+            [(1, "intro"), (2, "setup"), (2, "setup-1"), (3, "安装-步骤")]
+        );
+        let insight = analyze_note(&note);
+        // Intro See the docs and other rust not a heading Setup Setup 安 装 步 骤
+        assert_eq!(insight.word_count, 16);
+
+        let mut rich = markdown_note("<h2 class=\"x\">Plan</h2><p>Ship it.</p><h3>Risk</h3>");
+        rich.note_type = NoteType::RichText;
+        let texts: Vec<String> = note_outline(&rich).into_iter().map(|i| i.text).collect();
+        assert_eq!(texts, ["Plan", "Risk"]);
+        assert_eq!(extractive_summary(&rich), "Ship it.");
+    }
+
+    #[test]
+    fn test_extractive_summary_prefers_key_sentences() {
+        let note = markdown_note(
+            "# Caching\nThis note describes our plan.\n\n\
+             The weather was nice today. Caching reduces database load for caching clients.\n\
+             - Lunch was fine.\n- Invalidate caching entries when the database changes.\n",
+        );
+        assert_eq!(
+            extractive_summary(&note),
+            "This note describes our plan. Caching reduces database load for caching clients. \
+             Invalidate caching entries when the database changes."
+        );
+
+        let cjk = markdown_note("今天讨论了缓存方案。缓存可以降低数据库压力。午饭不错。");
+        assert_eq!(
+            extractive_summary(&cjk),
+            "今天讨论了缓存方案。缓存可以降低数据库压力。"
+        );
+        assert_eq!(extractive_summary(&markdown_note("```\ncode\n```\n")), "");
+    }
+}
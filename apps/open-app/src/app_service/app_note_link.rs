@@ -0,0 +1,428 @@
+//! 笔记双链
+//!
+//! 从 Markdown 和富文本（HTML）笔记中解析 `[[标题]]`、`[[标题|别名]]`、
+//! `[[标题#小节]]` 链接，维护 note_links 表，并在笔记改名时改写引用它的链接。
+//! 导入时生成的 `[文本](note://<id>)` 链接直接按笔记 ID 解析。
+
+use chrono::Utc;
+
+use super::record_note_revision;
+use crate::app_state::{DatabaseManager, Note, NoteLink, NoteType, NotesConfig};
+
+/// 反向链接上下文保留的最大字符数
+const CONTEXT_MAX_CHARS: usize = 200;
+
+/// 按笔记 ID 链接的 URL 前缀
+pub const NOTE_LINK_SCHEME: &str = "note://";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WikiLink {
+    pub title: String,
+    pub alias: Option<String>,
+    pub heading: Option<String>,
+    pub context: String,
+}
+
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "br",
+    "div",
+    "li",
+    "ul",
+    "ol",
+    "pre",
+    "blockquote",
+    "tr",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+];
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 去掉 HTML 标签，块级元素换行
+pub fn html_to_text(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            out.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim_start_matches('/');
+        let name: String = tag
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if BLOCK_TAGS.contains(&name.as_str()) && !out.ends_with('\n') {
+            out.push('\n');
+        }
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+
+    decode_entities(&out)
+}
+
+/// 笔记的纯文本内容，富文本去掉标签
+pub fn note_plain_text(note_type: &NoteType, content: &str) -> String {
+    match note_type {
+        NoteType::RichText => html_to_text(content),
+        _ => content.to_string(),
+    }
+}
+
+/// 逐行处理，跳过 Markdown 围栏代码块
+fn for_each_line<F>(text: &str, mut f: F)
+where
+    F: FnMut(&str, bool),
+{
+    let mut in_fence = false;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            f(line, true);
+            continue;
+        }
+        f(line, in_fence);
+    }
+}
+
+/// 在一行中查找 `[[...]]`，返回链接内部文本的字节区间
+fn find_links(line: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    while let Some(offset) = line[pos..].find("[[") {
+        let start = pos + offset;
+        let Some(len) = line[start + 2..].find("]]") else {
+            break;
+        };
+        let inner = &line[start + 2..start + 2 + len];
+        if inner.contains("[[") {
+            pos = start + 2;
+            continue;
+        }
+        spans.push((start + 2, start + 2 + len));
+        pos = start + 4 + len;
+    }
+    spans
+}
+
+/// 拆分链接内部文本为 (标题, 小节, 别名)
+fn split_link(inner: &str) -> (&str, Option<&str>, Option<&str>) {
+    let (target, alias) = match inner.split_once('|') {
+        Some((target, alias)) => (target, Some(alias.trim())),
+        None => (inner, None),
+    };
+    let (title, heading) = match target.split_once('#') {
+        Some((title, heading)) => (title, Some(heading.trim())),
+        None => (target, None),
+    };
+    (
+        title.trim(),
+        heading.filter(|h| !h.is_empty()),
+        alias.filter(|a| !a.is_empty()),
+    )
+}
+
+fn truncate_context(line: &str) -> String {
+    let line = line.trim();
+    if line.chars().count() <= CONTEXT_MAX_CHARS {
+        line.to_string()
+    } else {
+        let mut context: String = line.chars().take(CONTEXT_MAX_CHARS).collect();
+        context.push('…');
+        context
+    }
+}
+
+/// 解析笔记中的双链，只处理 Markdown 和富文本笔记
+pub fn parse_wiki_links(note_type: &NoteType, content: &str) -> Vec<WikiLink> {
+    if !matches!(note_type, NoteType::Markdown | NoteType::RichText) {
+        return Vec::new();
+    }
+    let text = note_plain_text(note_type, content);
+
+    let mut links = Vec::new();
+    for_each_line(&text, |line, skipped| {
+        if skipped {
+            return;
+        }
+        for (start, end) in find_links(line) {
+            let (title, heading, alias) = split_link(&line[start..end]);
+            if title.is_empty() {
+                continue;
+            }
+            links.push(WikiLink {
+                title: title.to_string(),
+                alias: alias.map(|s| s.to_string()),
+                heading: heading.map(|s| s.to_string()),
+                context: truncate_context(line),
+            });
+        }
+    });
+    links
+}
+
+/// 解析 `note://<id>` 链接，返回 (笔记 ID, 上下文)
+pub fn parse_id_links(note_type: &NoteType, content: &str) -> Vec<(String, String)> {
+    if !matches!(note_type, NoteType::Markdown | NoteType::RichText) {
+        return Vec::new();
+    }
+
+    let mut links = Vec::new();
+    for_each_line(content, |line, skipped| {
+        if skipped {
+            return;
+        }
+        let mut rest = line;
+        while let Some(pos) = rest.find(NOTE_LINK_SCHEME) {
+            rest = &rest[pos + NOTE_LINK_SCHEME.len()..];
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+                .unwrap_or(rest.len());
+            if len > 0 {
+                let context = note_plain_text(note_type, line);
+                links.push((rest[..len].to_string(), truncate_context(&context)));
+            }
+            rest = &rest[len..];
+        }
+    });
+    links
+}
+
+/// 将指向 `old_title` 的链接改写为 `new_title`，保留小节和别名
+pub fn rewrite_wiki_links(
+    note_type: &NoteType,
+    content: &str,
+    old_title: &str,
+    new_title: &str,
+) -> String {
+    let (old_title, new_title) = match note_type {
+        NoteType::RichText => (escape_html(old_title), escape_html(new_title)),
+        _ => (old_title.to_string(), new_title.to_string()),
+    };
+    let old_lower = old_title.to_lowercase();
+
+    let mut out = String::with_capacity(content.len());
+    for_each_line(content, |line, skipped| {
+        if skipped {
+            out.push_str(line);
+            return;
+        }
+        let mut last = 0;
+        for (start, end) in find_links(line) {
+            let inner = &line[start..end];
+            let target_end = inner.find(['|', '#']).unwrap_or(inner.len());
+            if inner[..target_end].trim().to_lowercase() != old_lower {
+                continue;
+            }
+            out.push_str(&line[last..start]);
+            out.push_str(&new_title);
+            out.push_str(&inner[target_end..]);
+            last = end;
+        }
+        out.push_str(&line[last..]);
+    });
+    out
+}
+
+/// 重新解析笔记的链接并写入数据库，同时补全指向该笔记标题的未解析链接
+pub fn sync_note_links(db: &DatabaseManager, note: &Note) -> Result<usize, String> {
+    let mut links = Vec::new();
+    for parsed in parse_wiki_links(&note.note_type, &note.content) {
+        let mut link = NoteLink::new(note.id.clone(), note.workspace_id.clone(), parsed.title);
+        link.target_id = db
+            .find_note_id_by_title(&note.workspace_id, &link.target_title)
+            .map_err(|e| format!("Failed to resolve note link: {}", e))?;
+        link.alias = parsed.alias;
+        link.heading = parsed.heading;
+        link.context = parsed.context;
+        links.push(link);
+    }
+    for (target_id, context) in parse_id_links(&note.note_type, &note.content) {
+        let Some(target) = db
+            .get_note(&target_id)
+            .map_err(|e| format!("Failed to resolve note link: {}", e))?
+        else {
+            continue;
+        };
+        if target.workspace_id != note.workspace_id {
+            continue;
+        }
+        let mut link = NoteLink::new(note.id.clone(), note.workspace_id.clone(), target.title);
+        link.target_id = Some(target.id);
+        link.context = context;
+        links.push(link);
+    }
+
+    db.replace_note_links(&note.id, &links)
+        .map_err(|e| format!("Failed to save note links: {}", e))?;
+    db.resolve_note_links(&note.workspace_id, &note.title, &note.id)
+        .map_err(|e| format!("Failed to resolve note links: {}", e))?;
+    Ok(links.len())
+}
+
+/// 笔记改名后改写所有引用它的链接，返回被修改的笔记数
+///
+/// 被改写的笔记按普通修改处理：更新 updated_at 并记录修订。
+pub fn rename_note_links(
+    db: &DatabaseManager,
+    config: &NotesConfig,
+    note: &Note,
+    old_title: &str,
+) -> Result<usize, String> {
+    let backlinks = db
+        .list_backlinks(&note.id)
+        .map_err(|e| format!("Failed to fetch backlinks: {}", e))?;
+    let mut source_ids: Vec<String> = backlinks.into_iter().map(|b| b.link.source_id).collect();
+    source_ids.sort();
+    source_ids.dedup();
+
+    let mut updated = 0;
+    for source_id in source_ids {
+        let Some(mut source) = db
+            .get_note(&source_id)
+            .map_err(|e| format!("Failed to fetch note: {}", e))?
+        else {
+            continue;
+        };
+        let content =
+            rewrite_wiki_links(&source.note_type, &source.content, old_title, &note.title);
+        if content == source.content {
+            continue;
+        }
+        source.content = content;
+        source.updated_at = Utc::now().timestamp_millis();
+        db.update_note(&source)
+            .map_err(|e| format!("Failed to update note: {}", e))?;
+        if let Err(e) = record_note_revision(db, config, &source) {
+            log::warn!("{}", e);
+        }
+        sync_note_links(db, &source)?;
+        updated += 1;
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::Workspace;
+    use std::env;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_and_rewrite_wiki_links() {
+        let markdown = "See [[Rust Notes#Async|async]] and [[ 异步编程 ]].\n```\nlet x = [[1]];\n```\n[[]] [[a [[b]]";
+        let links = parse_wiki_links(&NoteType::Markdown, markdown);
+        let titles: Vec<&str> = links.iter().map(|l| l.title.as_str()).collect();
+        assert_eq!(titles, vec!["Rust Notes", "异步编程", "b"]);
+        assert_eq!(links[0].heading.as_deref(), Some("Async"));
+        assert_eq!(links[0].alias.as_deref(), Some("async"));
+        assert!(links[0].context.starts_with("See [[Rust Notes"));
+
+        let html = "<p>Link to <strong>[[R&amp;D]]</strong></p><p>next</p>";
+        let links = parse_wiki_links(&NoteType::RichText, html);
+        assert_eq!(links[0].title, "R&D");
+        assert!(parse_wiki_links(&NoteType::Code, "[[x]]").is_empty());
+
+        assert_eq!(
+            rewrite_wiki_links(&NoteType::Markdown, markdown, "rust notes", "Rust"),
+            markdown.replace("[[Rust Notes#", "[[Rust#")
+        );
+        assert_eq!(
+            rewrite_wiki_links(&NoteType::RichText, html, "R&D", "R&D Team"),
+            "<p>Link to <strong>[[R&amp;D Team]]</strong></p><p>next</p>"
+        );
+
+        let links = parse_id_links(
+            &NoteType::Markdown,
+            "See [Design](note://1f0c-42ab) and note://\n```\n[x](note://skip)\n```",
+        );
+        assert_eq!(
+            links,
+            vec![(
+                "1f0c-42ab".to_string(),
+                "See [Design](note://1f0c-42ab) and note://".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_sync_backlinks_and_rename() {
+        let test_db_path =
+            env::temp_dir().join(format!("test_note_links_{}.db", uuid::Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+
+        let source = Note::new(
+            workspace.id.clone(),
+            "Index".to_string(),
+            NoteType::Markdown,
+            "- [[Design]]\n- [[Design|设计文档]]\n- [[Missing]]".to_string(),
+            PathBuf::from("/tmp/index.md"),
+        );
+        db.create_note(&source).unwrap();
+        sync_note_links(&db, &source).unwrap();
+        assert_eq!(db.list_unresolved_links(&workspace.id).unwrap().len(), 3);
+
+        // 目标笔记创建后补全链接
+        let mut target = Note::new(
+            workspace.id.clone(),
+            "Design".to_string(),
+            NoteType::Markdown,
+            String::new(),
+            PathBuf::from("/tmp/design.md"),
+        );
+        db.create_note(&target).unwrap();
+        sync_note_links(&db, &target).unwrap();
+        let backlinks = db.list_backlinks(&target.id).unwrap();
+        assert_eq!(backlinks.len(), 2);
+        assert_eq!(backlinks[0].source_title, "Index");
+        assert_eq!(db.list_unresolved_links(&workspace.id).unwrap().len(), 1);
+
+        target.title = "Architecture".to_string();
+        db.update_note(&target).unwrap();
+        let config = NotesConfig::default();
+        assert_eq!(
+            rename_note_links(&db, &config, &target, "Design").unwrap(),
+            1
+        );
+        let source = db.get_note(&source.id).unwrap().unwrap();
+        let revisions = db.list_note_revisions(&source.id).unwrap();
+        assert_eq!(revisions[0].content, source.content);
+        assert_eq!(
+            source.content,
+            "- [[Architecture]]\n- [[Architecture|设计文档]]\n- [[Missing]]"
+        );
+        assert_eq!(db.list_backlinks(&target.id).unwrap().len(), 2);
+
+        db.delete_note(&target.id).unwrap();
+        assert_eq!(db.list_unresolved_links(&workspace.id).unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,766 @@
+//! Notion 导出导入
+//!
+//! 导入 Notion 的 “Markdown & CSV” 导出压缩包：页面按目录层级生成笔记树，去掉 Notion
+//! 附加在文件名后的 32 位 ID；数据库 CSV 生成表格笔记，行页面挂在表格笔记下；页面间的
+//! 链接改写为 `note://<id>`，图片等资源作为附件导入。页面 ID 记录在 note_import_sources
+//! 中，重复导入时更新已有笔记。
+
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use uuid::Uuid;
+
+use super::{
+    AttachmentImporter, NOTE_LINK_SCHEME, attachment_link, hash_text, load_mapped_note,
+    percent_decode, place_note, record_note_revision, rewrite_lines_outside_fences,
+    rewrite_markdown_links, sync_note_links,
+};
+use crate::app_state::{
+    DatabaseManager, ImportSource, ImportSourceType, ImportTargetType, Note, NoteType, NotesConfig,
+};
+
+/// Notion 页面 ID 的长度（不含连字符）
+const NOTION_ID_LEN: usize = 32;
+
+/// 嵌套压缩包的最大层数，Notion 大型导出会拆成多个 Part 压缩包
+const MAX_ZIP_DEPTH: usize = 2;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotionImportReport {
+    pub pages: usize,
+    pub tables: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub attachments: usize,
+}
+
+/// 表格笔记的内容格式
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableContent {
+    pub columns: Vec<String>,
+    pub rows: Vec<TableRow>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableRow {
+    /// 行对应的页面笔记
+    pub note_id: Option<String>,
+    pub cells: Vec<String>,
+}
+
+/// 导出中的一个页面或数据库
+struct NotionItem {
+    path: PathBuf,
+    /// 页面或数据库的子项所在目录
+    container: PathBuf,
+    source_key: String,
+    note_type: NoteType,
+    title: String,
+    note_id: String,
+    existing: Option<Note>,
+}
+
+impl NotionItem {
+    fn container_name(&self) -> String {
+        self.container
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    }
+}
+
+/// 拆分 Notion 文件名为 (标题, ID)，如 `Roadmap 0123…cdef` 或 `Roadmap-0123…cdef`
+pub fn split_notion_id(name: &str) -> (String, Option<String>) {
+    let name = name.trim();
+    let Some(split) = name.len().checked_sub(NOTION_ID_LEN) else {
+        return (name.to_string(), None);
+    };
+    if !name.is_char_boundary(split) {
+        return (name.to_string(), None);
+    }
+    let (title, id) = name.split_at(split);
+    if !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return (name.to_string(), None);
+    }
+    if title.is_empty() {
+        return (id.to_string(), Some(id.to_lowercase()));
+    }
+    match title.strip_suffix([' ', '-']) {
+        Some(title) if !title.trim().is_empty() => {
+            (title.trim_end().to_string(), Some(id.to_lowercase()))
+        }
+        _ => (name.to_string(), None),
+    }
+}
+
+/// 从 notion.so 链接中取出页面 ID
+fn notion_url_id(href: &str) -> Option<String> {
+    let rest = href
+        .strip_prefix("https://www.notion.so/")
+        .or_else(|| href.strip_prefix("https://notion.so/"))?;
+    let path = rest.split(['?', '#']).next().unwrap_or_default();
+    let segment = path.trim_end_matches('/').rsplit('/').next()?;
+    let id: String = segment.chars().filter(|c| *c != '-').collect();
+    let id = id.get(id.len().checked_sub(NOTION_ID_LEN)?..)?;
+    id.chars()
+        .all(|c| c.is_ascii_hexdigit())
+        .then(|| id.to_lowercase())
+}
+
+/// 规范化路径中的 `.` 和 `..`，不访问文件系统
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// 解析 CSV，支持 BOM、引号和单元格内换行
+pub fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    cell.push('"');
+                    chars.next();
+                }
+                '"' => quoted = false,
+                _ => cell.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if cell.is_empty() => quoted = true,
+            ',' => row.push(std::mem::take(&mut cell)),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => cell.push(c),
+        }
+    }
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        rows.push(row);
+    }
+    rows.retain(|row| row.iter().any(|cell| !cell.is_empty()));
+    rows
+}
+
+/// 解压导出文件，嵌套的压缩包解压到同一目录
+pub fn extract_notion_zip(zip_path: &Path, dest: &Path) -> Result<(), String> {
+    extract_zip(zip_path, dest, 0)
+}
+
+fn extract_zip(zip_path: &Path, dest: &Path, depth: usize) -> Result<(), String> {
+    let file = fs::File::open(zip_path)
+        .map_err(|e| format!("Failed to open {}: {}", zip_path.display(), e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read zip archive: {}", e))?;
+
+    let mut nested = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read zip entry: {}", e))?;
+        // 跳过解压后会落在目标目录之外的条目
+        let Some(name) = entry.enclosed_name() else {
+            log::warn!("Skipping unsafe zip entry: {}", entry.name());
+            continue;
+        };
+        let target = dest.join(&name);
+        if entry.is_dir() {
+            fs::create_dir_all(&target)
+                .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let mut out = fs::File::create(&target)
+            .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+        io::copy(&mut entry, &mut out)
+            .map_err(|e| format!("Failed to extract {}: {}", name.display(), e))?;
+        if depth < MAX_ZIP_DEPTH && name.extension().is_some_and(|ext| ext == "zip") {
+            nested.push(target);
+        }
+    }
+
+    for path in nested {
+        extract_zip(&path, dest, depth + 1)?;
+        let _ = fs::remove_file(&path);
+    }
+    Ok(())
+}
+
+/// 导出目录中页面和数据库的查找表
+struct NotionIndex {
+    root: PathBuf,
+    /// 相对路径（含 `.md`/`.csv`）到笔记 ID
+    paths: HashMap<PathBuf, String>,
+    /// Notion 页面 ID 到笔记 ID
+    ids: HashMap<String, String>,
+    assets: HashSet<PathBuf>,
+}
+
+impl NotionIndex {
+    fn resolve_href(&self, page_dir: &Path, href: &str) -> Option<NotionTarget> {
+        if let Some(id) = notion_url_id(href) {
+            return self.ids.get(&id).cloned().map(NotionTarget::Note);
+        }
+        if href.is_empty() || href.contains("://") || href.starts_with("mailto:") {
+            return None;
+        }
+        let path = normalize_path(&page_dir.join(percent_decode(href)));
+        let relative = path.strip_prefix(&self.root).ok()?;
+        if let Some(note_id) = self.paths.get(relative) {
+            return Some(NotionTarget::Note(note_id.clone()));
+        }
+        self.assets
+            .contains(&path)
+            .then_some(NotionTarget::Asset(path))
+    }
+}
+
+enum NotionTarget {
+    Note(String),
+    Asset(PathBuf),
+}
+
+fn source_key(root: &Path, path: &Path, id: Option<&str>) -> String {
+    match id {
+        Some(id) => format!("notion:{}", id),
+        None => format!(
+            "notion:{}",
+            path.strip_prefix(root).unwrap_or(path).to_string_lossy()
+        ),
+    }
+}
+
+/// 页面正文：去掉与标题相同的一级标题，改写链接
+fn rewrite_page_body(
+    text: &str,
+    page_dir: &Path,
+    index: &NotionIndex,
+    attachments: &mut AttachmentImporter,
+    referenced: &mut Vec<String>,
+) -> (Option<String>, String) {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let (heading, body) = match text.strip_prefix("# ") {
+        Some(rest) => {
+            let (line, body) = rest.split_once('\n').unwrap_or((rest, ""));
+            (Some(line.trim().to_string()), body)
+        }
+        None => (None, text),
+    };
+
+    let body = rewrite_lines_outside_fences(body.trim_start_matches(['\r', '\n']), |line| {
+        rewrite_markdown_links(line, |label, href, image| {
+            match index.resolve_href(page_dir, href)? {
+                NotionTarget::Note(note_id) => {
+                    Some(format!("[{}]({}{})", label, NOTE_LINK_SCHEME, note_id))
+                }
+                NotionTarget::Asset(path) => {
+                    let key = source_key(&index.root, &path, None);
+                    match attachments.import(&path, &key) {
+                        Ok(file) => {
+                            referenced.push(file.id.clone());
+                            Some(attachment_link(&file, Some(label), image))
+                        }
+                        Err(e) => {
+                            log::warn!("{}", e);
+                            None
+                        }
+                    }
+                }
+            }
+        })
+    });
+    (heading.filter(|h| !h.is_empty()), body)
+}
+
+/// 由 CSV 生成表格内容，首列与数据库目录中的行页面标题对应
+fn table_content(text: &str, row_pages: &[(String, String)]) -> Result<String, String> {
+    let mut rows = parse_csv(text).into_iter();
+    let mut table = TableContent {
+        columns: rows.next().unwrap_or_default(),
+        rows: Vec::new(),
+    };
+    let mut used = HashSet::new();
+    for cells in rows {
+        let title = cells.first().map(|c| c.trim()).unwrap_or_default();
+        let note_id = row_pages
+            .iter()
+            .find(|(page_title, id)| page_title == title && !used.contains(id))
+            .map(|(_, id)| id.clone());
+        if let Some(id) = &note_id {
+            used.insert(id.clone());
+        }
+        table.rows.push(TableRow { note_id, cells });
+    }
+    serde_json::to_string(&table).map_err(|e| format!("Failed to serialize table: {}", e))
+}
+
+/// 导入 Notion 导出压缩包，解压到 `cache_dir` 下的临时目录，完成后删除
+#[allow(clippy::too_many_arguments)]
+pub fn import_notion_export<F>(
+    db: &DatabaseManager,
+    config: &NotesConfig,
+    files_dir: &Path,
+    cache_dir: &Path,
+    workspace_id: &str,
+    zip_path: &Path,
+    on_progress: F,
+) -> Result<NotionImportReport, String>
+where
+    F: FnMut(usize, usize, &str),
+{
+    let temp_dir = cache_dir.join(format!("notion-import-{}", Uuid::new_v4()));
+    fs::create_dir_all(&temp_dir)
+        .map_err(|e| format!("Failed to create {}: {}", temp_dir.display(), e))?;
+    let result = extract_notion_zip(zip_path, &temp_dir).and_then(|_| {
+        import_notion_dir(db, config, files_dir, workspace_id, &temp_dir, on_progress)
+    });
+    if let Err(e) = fs::remove_dir_all(&temp_dir) {
+        log::warn!("Failed to remove {}: {}", temp_dir.display(), e);
+    }
+    result
+}
+
+/// 导入已解压的 Notion 导出目录
+pub fn import_notion_dir<F>(
+    db: &DatabaseManager,
+    config: &NotesConfig,
+    files_dir: &Path,
+    workspace_id: &str,
+    export_dir: &Path,
+    mut on_progress: F,
+) -> Result<NotionImportReport, String>
+where
+    F: FnMut(usize, usize, &str),
+{
+    let root = fs::canonicalize(export_dir).map_err(|e| format!("Invalid export path: {}", e))?;
+    db.get_workspace(workspace_id)
+        .map_err(|e| format!("Failed to fetch workspace: {}", e))?
+        .ok_or_else(|| format!("Workspace not found: {}", workspace_id))?;
+    let source_type = ImportSourceType::Notion;
+
+    let mut pages = Vec::new();
+    let mut csv_files = Vec::new();
+    let mut assets = HashSet::new();
+    for entry in WalkBuilder::new(&root)
+        .standard_filters(false)
+        .build()
+        .flatten()
+    {
+        let path = entry.path().to_path_buf();
+        if !path.is_file() {
+            continue;
+        }
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("md") => pages.push(path),
+            Some("csv") => csv_files.push(path),
+            _ => {
+                assets.insert(path);
+            }
+        }
+    }
+
+    // 同一数据库导出 `X.csv` 和 `X_all.csv` 时取包含全部行的 `_all`
+    let mut tables: Vec<PathBuf> = Vec::new();
+    for path in &csv_files {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        match stem.strip_suffix("_all") {
+            Some(base) => {
+                let plain = path.with_file_name(format!("{}.csv", base));
+                tables.retain(|p| *p != plain);
+                tables.push(path.clone());
+            }
+            None => {
+                let all = path.with_file_name(format!("{}_all.csv", stem));
+                if !csv_files.contains(&all) {
+                    tables.push(path.clone());
+                }
+            }
+        }
+    }
+
+    // 先为全部页面和数据库分配笔记 ID，链接可能指向后导入的页面
+    let mut items = Vec::new();
+    for (path, note_type) in pages
+        .iter()
+        .map(|p| (p, NoteType::Markdown))
+        .chain(tables.iter().map(|p| (p, NoteType::Table)))
+    {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let stem = match note_type {
+            NoteType::Table => stem.strip_suffix("_all").unwrap_or(&stem).to_string(),
+            _ => stem.to_string(),
+        };
+        let (title, id) = split_notion_id(&stem);
+        let source_key = source_key(&root, path, id.as_deref());
+        let target_type = ImportTargetType::Note;
+        let existing = load_mapped_note(db, workspace_id, &source_key, target_type)?;
+        items.push(NotionItem {
+            container: path.with_file_name(&stem),
+            path: path.clone(),
+            source_key,
+            note_type,
+            title,
+            note_id: existing
+                .as_ref()
+                .map(|n| n.id.clone())
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+            existing,
+        });
+    }
+    // 父级先于子级，同级按文件名排序
+    items.sort_by(|a, b| {
+        let depth = |item: &NotionItem| item.path.components().count();
+        depth(a).cmp(&depth(b)).then_with(|| a.path.cmp(&b.path))
+    });
+
+    let mut index = NotionIndex {
+        root: root.clone(),
+        paths: HashMap::new(),
+        ids: HashMap::new(),
+        assets,
+    };
+    let mut containers: HashMap<PathBuf, String> = HashMap::new();
+    for item in &items {
+        let relative = item.path.strip_prefix(&root).unwrap_or(&item.path);
+        index
+            .paths
+            .insert(relative.to_path_buf(), item.note_id.clone());
+        // 数据库的链接可能指向 `X.csv` 或 `X_all.csv`
+        if item.note_type == NoteType::Table {
+            let csv = relative.with_file_name(format!("{}.csv", item.container_name()));
+            let all = relative.with_file_name(format!("{}_all.csv", item.container_name()));
+            index.paths.insert(csv, item.note_id.clone());
+            index.paths.insert(all, item.note_id.clone());
+        }
+        if let Some(id) = item.source_key.strip_prefix("notion:") {
+            index.ids.insert(id.to_string(), item.note_id.clone());
+        }
+        // 页面和数据库同名时子项挂在数据库下
+        if item.note_type == NoteType::Table || !containers.contains_key(&item.container) {
+            containers.insert(item.container.clone(), item.note_id.clone());
+        }
+    }
+    let parent_of = |path: &Path| {
+        path.ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&root) && *dir != root)
+            .find_map(|dir| containers.get(dir).cloned())
+    };
+
+    let total = items.len();
+    let mut report = NotionImportReport::default();
+    let mut attachments = AttachmentImporter::new(db, workspace_id, source_type.clone(), files_dir);
+    let mut note_attachments: HashMap<String, Vec<String>> = HashMap::new();
+    let mut next_order: HashMap<Option<String>, i32> = HashMap::new();
+    // 页面标题，用于把数据库行对应到行页面
+    let mut page_titles: HashMap<String, String> = HashMap::new();
+
+    for (done, item) in items.iter().enumerate() {
+        let parent_id = parent_of(&item.path);
+        let order = next_order.entry(parent_id.clone()).or_insert(0);
+        let sort_order = *order;
+        *order += 1;
+
+        let text = fs::read_to_string(&item.path)
+            .map_err(|e| format!("Failed to read {}: {}", item.path.display(), e))?;
+        let (title, content) = match item.note_type {
+            NoteType::Table => {
+                report.tables += 1;
+                let row_pages: Vec<(String, String)> = items
+                    .iter()
+                    .filter(|row| {
+                        row.note_type == NoteType::Markdown
+                            && row.path.parent() == Some(item.container.as_path())
+                    })
+                    .map(|row| {
+                        let title = page_titles.get(&row.note_id).unwrap_or(&row.title);
+                        (title.clone(), row.note_id.clone())
+                    })
+                    .collect();
+                (item.title.clone(), table_content(&text, &row_pages)?)
+            }
+            _ => {
+                report.pages += 1;
+                let mut referenced = Vec::new();
+                let page_dir = item.path.parent().unwrap_or(&root);
+                let (heading, body) =
+                    rewrite_page_body(&text, page_dir, &index, &mut attachments, &mut referenced);
+                note_attachments
+                    .entry(item.note_id.clone())
+                    .or_default()
+                    .extend(referenced);
+                (heading.unwrap_or_else(|| item.title.clone()), body)
+            }
+        };
+        page_titles.insert(item.note_id.clone(), title.clone());
+
+        match &item.existing {
+            Some(existing) => {
+                if existing.title != title || existing.content != content {
+                    let mut note = existing.clone();
+                    note.title = title.clone();
+                    note.content = content.clone();
+                    db.update_note(&note)
+                        .map_err(|e| format!("Failed to update note: {}", e))?;
+                    if let Err(e) = record_note_revision(db, config, &note) {
+                        log::warn!("{}", e);
+                    }
+                    report.updated += 1;
+                } else {
+                    report.unchanged += 1;
+                }
+                place_note(db, existing, parent_id.as_ref(), sort_order)?;
+            }
+            None => {
+                let mut note = Note::new(
+                    workspace_id.to_string(),
+                    title.clone(),
+                    item.note_type.clone(),
+                    content.clone(),
+                    item.path
+                        .strip_prefix(&root)
+                        .unwrap_or(&item.path)
+                        .to_path_buf(),
+                );
+                note.id = item.note_id.clone();
+                note.parent_id = parent_id;
+                note.sort_order = sort_order;
+                db.create_note(&note)
+                    .map_err(|e| format!("Failed to create note: {}", e))?;
+                if let Err(e) = record_note_revision(db, config, &note) {
+                    log::warn!("{}", e);
+                }
+                report.created += 1;
+            }
+        }
+
+        db.save_import_source(&ImportSource::new(
+            workspace_id.to_string(),
+            item.source_key.clone(),
+            source_type.clone(),
+            ImportTargetType::Note,
+            item.note_id.clone(),
+            hash_text(&content),
+        ))
+        .map_err(|e| format!("Failed to save import source: {}", e))?;
+        on_progress(done + 1, total, &title);
+    }
+
+    // 正文未引用的资源挂到所在页面下
+    let mut unreferenced: Vec<&PathBuf> = index.assets.iter().collect();
+    unreferenced.sort();
+    for path in unreferenced {
+        let Some(note_id) = parent_of(path) else {
+            continue;
+        };
+        let key = source_key(&root, path, None);
+        match attachments.import(path, &key) {
+            Ok(file) => note_attachments.entry(note_id).or_default().push(file.id),
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+    report.attachments = attachments.count();
+
+    for item in &items {
+        let mut file_ids = note_attachments.remove(&item.note_id).unwrap_or_default();
+        file_ids.sort();
+        file_ids.dedup();
+        db.replace_note_attachments(&item.note_id, &file_ids)
+            .map_err(|e| format!("Failed to save note attachments: {}", e))?;
+    }
+
+    for item in &items {
+        if let Some(note) = db
+            .get_note(&item.note_id)
+            .map_err(|e| format!("Failed to fetch note: {}", e))?
+        {
+            sync_note_links(db, &note)?;
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::Workspace;
+    use std::env;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    const ROADMAP_ID: &str = "0123456789abcdef0123456789abcdef";
+    const TASKS_ID: &str = "11111111111111111111111111111111";
+    const ROW_ID: &str = "22222222222222222222222222222222";
+
+    #[test]
+    fn test_split_notion_id_and_parse_csv() {
+        assert_eq!(
+            split_notion_id(&format!("Road map {}", ROADMAP_ID)),
+            ("Road map".to_string(), Some(ROADMAP_ID.to_string()))
+        );
+        assert_eq!(split_notion_id("Plain"), ("Plain".to_string(), None));
+        assert_eq!(
+            notion_url_id(&format!(
+                "https://www.notion.so/team/Road-map-{}?pvs=4",
+                ROADMAP_ID
+            )),
+            Some(ROADMAP_ID.to_string())
+        );
+
+        let rows =
+            parse_csv("\u{feff}Name,Notes\r\n\"Ship, v1\",\"line 1\nline \"\"2\"\"\"\r\n\r\n");
+        assert_eq!(
+            rows,
+            vec![
+                vec!["Name".to_string(), "Notes".to_string()],
+                vec!["Ship, v1".to_string(), "line 1\nline \"2\"".to_string()],
+            ]
+        );
+    }
+
+    fn write_export(zip_path: &Path, roadmap_body: &str) {
+        let file = fs::File::create(zip_path).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        let options = SimpleFileOptions::default();
+        let roadmap = format!("Road map {}", ROADMAP_ID);
+        let tasks = format!("Tasks {}", TASKS_ID);
+        let entries = [
+            (format!("{}.md", roadmap), roadmap_body.to_string()),
+            (format!("{}/diagram.png", roadmap), "png".to_string()),
+            (
+                format!("{}/{}.csv", roadmap, tasks),
+                "Name\nShip\n".to_string(),
+            ),
+            (
+                format!("{}/{}_all.csv", roadmap, tasks),
+                "Name,Status\nShip,Done\nPlan,Todo\n".to_string(),
+            ),
+            (
+                format!("{}/{}/Ship {}.md", roadmap, tasks, ROW_ID),
+                format!(
+                    "# Ship\n\nStatus: Done\n\nBack to [Road map](../../Road%20map%20{}.md)\n",
+                    ROADMAP_ID
+                ),
+            ),
+            ("../escape.md".to_string(), "x".to_string()),
+        ];
+        for (name, content) in entries {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_import_notion_export() {
+        let test_db_path =
+            env::temp_dir().join(format!("test_notion_import_{}.db", Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+        let config = NotesConfig::default();
+
+        let temp = env::temp_dir().join(format!("test_notion_{}", Uuid::new_v4()));
+        let files_dir = temp.join("files");
+        let cache_dir = temp.join("cache");
+        fs::create_dir_all(&temp).unwrap();
+        let zip_path = temp.join("export.zip");
+        let body = format!(
+            "# Road map\n\n![](Road%20map%20{id}/diagram.png)\n\nSee [Tasks](Road%20map%20{id}/Tasks%20{tasks}.csv) and [Ship](https://www.notion.so/Ship-{row})\n",
+            id = ROADMAP_ID,
+            tasks = TASKS_ID,
+            row = ROW_ID
+        );
+        write_export(&zip_path, &body);
+
+        let report = import_notion_export(
+            &db,
+            &config,
+            &files_dir,
+            &cache_dir,
+            &workspace.id,
+            &zip_path,
+            |_, _, _| {},
+        )
+        .unwrap();
+        assert_eq!((report.pages, report.tables, report.created), (2, 1, 3));
+        assert_eq!(report.attachments, 1);
+        assert_eq!(fs::read_dir(&cache_dir).unwrap().count(), 0);
+
+        let notes = db.list_notes(&workspace.id).unwrap();
+        let roadmap = notes.iter().find(|n| n.title == "Road map").unwrap();
+        let tasks = notes.iter().find(|n| n.title == "Tasks").unwrap();
+        let ship = notes.iter().find(|n| n.title == "Ship").unwrap();
+        assert_eq!(roadmap.parent_id, None);
+        assert_eq!(tasks.parent_id.as_ref(), Some(&roadmap.id));
+        assert_eq!(ship.parent_id.as_ref(), Some(&tasks.id));
+        assert!(!notes.iter().any(|n| n.title == "escape"));
+
+        assert!(roadmap.content.starts_with("![]("));
+        assert!(roadmap.content.contains(&format!(
+            "[Tasks](note://{}) and [Ship](note://{})",
+            tasks.id, ship.id
+        )));
+        assert!(
+            ship.content
+                .contains(&format!("[Road map](note://{})", roadmap.id))
+        );
+        assert_eq!(db.list_note_attachments(&roadmap.id).unwrap().len(), 1);
+        assert_eq!(db.list_backlinks(&roadmap.id).unwrap().len(), 1);
+
+        let table: TableContent = serde_json::from_str(&tasks.content).unwrap();
+        assert_eq!(table.columns, vec!["Name", "Status"]);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].note_id.as_ref(), Some(&ship.id));
+        assert_eq!(table.rows[1].note_id, None);
+
+        // 再次导入同一导出时复用已有笔记
+        let report = import_notion_export(
+            &db,
+            &config,
+            &files_dir,
+            &cache_dir,
+            &workspace.id,
+            &zip_path,
+            |_, _, _| {},
+        )
+        .unwrap();
+        assert_eq!(
+            (report.created, report.updated, report.unchanged),
+            (0, 0, 3)
+        );
+        assert_eq!(db.list_notes(&workspace.id).unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,325 @@
+//! 笔记内容渲染
+//!
+//! 将各类型笔记转换为可读的 Markdown：表格渲染为 Markdown 表格，思维导图渲染为嵌套列表，
+//! 流程图和画布渲染为节点与连线列表。另提供导出 HTML 用的 Markdown 渲染。
+
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag, TagEnd, html};
+use serde_json::Value;
+
+use super::{TableContent, escape_html};
+use crate::app_state::{Note, NoteType};
+
+/// 节点文本可能使用的字段
+const LABEL_KEYS: &[&str] = &["topic", "text", "title", "label", "name", "content"];
+
+fn node_label(node: &Value) -> Option<String> {
+    for key in LABEL_KEYS {
+        if let Some(text) = node.get(key).and_then(Value::as_str) {
+            return Some(text.trim().to_string());
+        }
+    }
+    // React Flow 等格式把文本放在 data 中
+    node.get("data")
+        .filter(|d| d.is_object())
+        .and_then(node_label)
+}
+
+fn push_mindmap_node(node: &Value, depth: usize, out: &mut String) {
+    if let Some(label) = node_label(node) {
+        out.push_str(&"  ".repeat(depth));
+        out.push_str("- ");
+        out.push_str(&label.replace('\n', " "));
+        out.push('\n');
+    }
+    if let Some(children) = node.get("children").and_then(Value::as_array) {
+        for child in children {
+            push_mindmap_node(child, depth + 1, out);
+        }
+    }
+}
+
+/// 思维导图渲染为嵌套列表，支持 `{root|data|nodeData: {topic, children}}` 等常见结构
+pub fn mindmap_to_markdown(content: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(content).ok()?;
+    let root = ["root", "nodeData", "data"]
+        .iter()
+        .find_map(|key| value.get(key).filter(|v| v.is_object() || v.is_array()))
+        .unwrap_or(&value);
+
+    let mut out = String::new();
+    match root {
+        Value::Array(nodes) => {
+            for node in nodes {
+                push_mindmap_node(node, 0, &mut out);
+            }
+        }
+        node => push_mindmap_node(node, 0, &mut out),
+    }
+    (!out.is_empty()).then_some(out)
+}
+
+/// 连线端点可能是节点 ID，也可能是 `{cell: id}` 这样的对象
+fn edge_end(edge: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| match edge.get(key)? {
+        Value::String(id) => Some(id.clone()),
+        Value::Object(end) => ["cell", "id", "node"]
+            .iter()
+            .find_map(|k| end.get(*k).and_then(Value::as_str))
+            .map(str::to_string),
+        _ => None,
+    })
+}
+
+/// 流程图和画布渲染为节点列表和连线列表
+pub fn graph_to_markdown(content: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(content).ok()?;
+    let nodes = ["nodes", "cells", "elements"]
+        .iter()
+        .find_map(|key| value.get(key).and_then(Value::as_array))?;
+    let edges = ["edges", "links", "connections"]
+        .iter()
+        .find_map(|key| value.get(key).and_then(Value::as_array));
+
+    let mut labels = Vec::new();
+    let mut edge_items = Vec::new();
+    for node in nodes {
+        // 部分格式把连线和节点放在同一个数组中
+        if edge_end(node, &["source", "from", "fromNode"]).is_some()
+            && edge_end(node, &["target", "to", "toNode"]).is_some()
+        {
+            edge_items.push(node);
+            continue;
+        }
+        let id = node.get("id").and_then(Value::as_str).unwrap_or_default();
+        let label = node_label(node).unwrap_or_else(|| id.to_string());
+        labels.push((id.to_string(), label.replace('\n', " ")));
+    }
+    edge_items.extend(edges.into_iter().flatten());
+
+    let label_of = |id: &str| {
+        labels
+            .iter()
+            .find(|(node_id, _)| node_id == id)
+            .map(|(_, label)| label.clone())
+            .unwrap_or_else(|| id.to_string())
+    };
+
+    let mut out = String::new();
+    for (_, label) in &labels {
+        if !label.is_empty() {
+            out.push_str(&format!("- {}\n", label));
+        }
+    }
+    let mut connections = String::new();
+    for edge in edge_items {
+        let (Some(from), Some(to)) = (
+            edge_end(edge, &["source", "from", "fromNode"]),
+            edge_end(edge, &["target", "to", "toNode"]),
+        ) else {
+            continue;
+        };
+        connections.push_str(&format!("- {} → {}", label_of(&from), label_of(&to)));
+        if let Some(label) = node_label(edge).filter(|l| !l.is_empty()) {
+            connections.push_str(&format!(" ({})", label));
+        }
+        connections.push('\n');
+    }
+    if !connections.is_empty() {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&connections);
+    }
+    (!out.is_empty()).then_some(out)
+}
+
+fn table_cell(cell: &str) -> String {
+    cell.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+/// 表格笔记渲染为 Markdown 表格
+pub fn table_to_markdown(content: &str) -> Option<String> {
+    let table: TableContent = serde_json::from_str(content).ok()?;
+    let width = table
+        .rows
+        .iter()
+        .map(|row| row.cells.len())
+        .chain([table.columns.len()])
+        .max()
+        .filter(|w| *w > 0)?;
+
+    let row_line = |cells: &[String]| {
+        let cells: Vec<String> = (0..width)
+            .map(|i| table_cell(cells.get(i).map(String::as_str).unwrap_or_default()))
+            .collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+    let mut out = row_line(&table.columns);
+    out.push_str(&format!("|{}\n", " --- |".repeat(width)));
+    for row in &table.rows {
+        out.push_str(&row_line(&row.cells));
+    }
+    Some(out)
+}
+
+fn fenced(lang: &str, content: &str) -> String {
+    let fence = if content.contains("```") {
+        "~~~~"
+    } else {
+        "```"
+    };
+    format!(
+        "{}{}\n{}\n{}\n",
+        fence,
+        lang,
+        content.trim_end_matches('\n'),
+        fence
+    )
+}
+
+/// 笔记正文的可读 Markdown，无法识别的结构化内容放入代码块
+pub fn note_markdown_body(note: &Note) -> String {
+    let content = note.content.as_str();
+    let rendered = match note.note_type {
+        NoteType::Markdown | NoteType::RichText => return content.to_string(),
+        NoteType::Code => {
+            let lang = note
+                .file_path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_string())
+                .unwrap_or_default();
+            return fenced(&lang, content);
+        }
+        NoteType::Table => table_to_markdown(content),
+        NoteType::MindMap => mindmap_to_markdown(content),
+        NoteType::Flowchart | NoteType::Canvas => graph_to_markdown(content),
+    };
+    match rendered {
+        Some(text) => text,
+        None if content.trim().is_empty() => String::new(),
+        // Mermaid 等文本格式本身可读
+        None if serde_json::from_str::<Value>(content).is_err() => {
+            let first = content.trim_start();
+            if first.starts_with("graph") || first.starts_with("flowchart") {
+                fenced("mermaid", content)
+            } else {
+                content.to_string()
+            }
+        }
+        None => fenced("json", content),
+    }
+}
+
+/// 行内 HTML 中仅保留换行标签，表格单元格中的换行会渲染为 `<br>`
+fn is_line_break(html: &str) -> bool {
+    matches!(html.trim(), "<br>" | "<br/>" | "<br />")
+}
+
+/// 将 Markdown 渲染为 HTML，`resolve_wiki` 将 `[[标题]]` 解析为链接地址
+///
+/// 笔记中的原始 HTML 会被转义为文本，`![[标题]]` 嵌入渲染为指向笔记的链接
+pub fn markdown_to_html<F>(text: &str, resolve_wiki: &F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_WIKILINKS;
+    // 每个打开的链接 / 图片对应的闭合标签，`None` 表示交由默认渲染
+    let mut closers: Vec<Option<&'static str>> = Vec::new();
+    let events = Parser::new_ext(text, options).map(|event| match event {
+        Event::Start(Tag::HtmlBlock) => Event::Html("<pre class=\"raw-html\">".into()),
+        Event::End(TagEnd::HtmlBlock) => Event::Html("</pre>\n".into()),
+        Event::Html(raw) => Event::Text(raw),
+        Event::InlineHtml(raw) if !is_line_break(&raw) => Event::Text(raw),
+        Event::Start(
+            Tag::Link {
+                link_type: LinkType::WikiLink { .. },
+                dest_url,
+                ..
+            }
+            | Tag::Image {
+                link_type: LinkType::WikiLink { .. },
+                dest_url,
+                ..
+            },
+        ) => {
+            let title = dest_url.split('#').next().unwrap_or_default().trim();
+            match resolve_wiki(title) {
+                Some(href) => {
+                    closers.push(Some("</a>"));
+                    Event::Html(
+                        format!("<a class=\"wiki-link\" href=\"{}\">", escape_html(&href)).into(),
+                    )
+                }
+                None => {
+                    closers.push(Some("</span>"));
+                    Event::Html("<span class=\"wiki-link unresolved\">".into())
+                }
+            }
+        }
+        Event::Start(tag @ (Tag::Link { .. } | Tag::Image { .. })) => {
+            closers.push(None);
+            Event::Start(tag)
+        }
+        Event::End(end @ (TagEnd::Link | TagEnd::Image)) => match closers.pop().flatten() {
+            Some(closer) => Event::Html(closer.into()),
+            None => Event::End(end),
+        },
+        other => other,
+    });
+
+    let mut out = String::with_capacity(text.len() * 2);
+    html::push_html(&mut out, events);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structured_notes_to_markdown() {
+        let mindmap = r#"{"nodeData":{"topic":"Root","children":[{"topic":"A","children":[{"topic":"A1"}]},{"topic":"B"}]}}"#;
+        assert_eq!(
+            mindmap_to_markdown(mindmap).unwrap(),
+            "- Root\n  - A\n    - A1\n  - B\n"
+        );
+
+        let flowchart = r#"{"nodes":[{"id":"1","data":{"label":"Start"}},{"id":"2","text":"End"}],"edges":[{"source":"1","target":"2","label":"go"}]}"#;
+        assert_eq!(
+            graph_to_markdown(flowchart).unwrap(),
+            "- Start\n- End\n\n- Start → End (go)\n"
+        );
+
+        let table =
+            r#"{"columns":["Name","Note"],"rows":[{"noteId":null,"cells":["a|b","x\ny"]}]}"#;
+        assert_eq!(
+            table_to_markdown(table).unwrap(),
+            "| Name | Note |\n| --- | --- |\n| a\\|b | x<br>y |\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_to_html() {
+        let markdown = "# Title\n\nSome **bold** and `<code>` with [[Other|link]], [[Missing]] and ![[Other]].\n\n- [x] done\n- item\n\n| A | B |\n| --- | --- |\n| 1 | x<br>y |\n\n```rust\nlet x = 1 < 2;\n```\n\n<script>alert(1)</script>\n\ninline <img src=x onerror=alert(1)>";
+        let html = markdown_to_html(markdown, &|title| {
+            (title == "Other").then(|| "Other.html".to_string())
+        });
+        assert!(html.contains("<h1>Title</h1>"));
+        assert!(html.contains("<strong>bold</strong> and <code>&lt;code&gt;</code>"));
+        assert!(html.contains("<a class=\"wiki-link\" href=\"Other.html\">link</a>"));
+        assert!(html.contains("<span class=\"wiki-link unresolved\">Missing</span>"));
+        assert!(html.contains("<a class=\"wiki-link\" href=\"Other.html\">Other</a>"));
+        assert!(html.contains("checked=\"\"/>\ndone</li>"));
+        assert!(html.contains("<td>x<br>y</td>"));
+        assert!(html.contains("<code class=\"language-rust\">let x = 1 &lt; 2;\n</code>"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(html.contains("inline &lt;img src=x onerror=alert(1)&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(!html.contains("<img"));
+    }
+}
@@ -0,0 +1,162 @@
+//! 笔记修订历史
+//!
+//! 按配置的合并间隔和保留数量记录版本，提供任意两个版本之间的差异和版本恢复。
+
+use git2::{DiffOptions, Patch};
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::{DatabaseManager, Note, NoteRevision, NotesConfig};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteRevisionDiff {
+    pub from_revision_id: String,
+    pub to_revision_id: String,
+    pub title_changed: bool,
+    pub additions: usize,
+    pub deletions: usize,
+    /// 正文的统一 diff
+    pub diff: String,
+}
+
+/// 按配置记录笔记当前内容的版本
+pub fn record_note_revision(
+    db: &DatabaseManager,
+    config: &NotesConfig,
+    note: &Note,
+) -> Result<NoteRevision, String> {
+    db.save_note_revision(
+        note,
+        config.revision_interval_secs as i64 * 1000,
+        config.max_revisions,
+    )
+    .map_err(|e| format!("Failed to save note revision: {}", e))
+}
+
+fn load_revision(db: &DatabaseManager, id: &str) -> Result<NoteRevision, String> {
+    db.get_note_revision(id)
+        .map_err(|e| format!("Failed to fetch note revision: {}", e))?
+        .ok_or_else(|| format!("Note revision not found: {}", id))
+}
+
+fn diff_revision_contents(
+    from: &NoteRevision,
+    to: &NoteRevision,
+) -> Result<NoteRevisionDiff, String> {
+    let mut opts = DiffOptions::new();
+    opts.context_lines(3);
+    let mut patch = Patch::from_buffers(
+        from.content.as_bytes(),
+        Some(std::path::Path::new(&from.title)),
+        to.content.as_bytes(),
+        Some(std::path::Path::new(&to.title)),
+        Some(&mut opts),
+    )
+    .map_err(|e| format!("Failed to diff revisions: {}", e))?;
+
+    let (_, additions, deletions) = patch
+        .line_stats()
+        .map_err(|e| format!("Failed to diff revisions: {}", e))?;
+    let diff = patch
+        .to_buf()
+        .map_err(|e| format!("Failed to diff revisions: {}", e))?
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+
+    Ok(NoteRevisionDiff {
+        from_revision_id: from.id.clone(),
+        to_revision_id: to.id.clone(),
+        title_changed: from.title != to.title,
+        additions,
+        deletions,
+        diff,
+    })
+}
+
+/// 比较同一笔记的两个版本
+pub fn compare_note_revisions(
+    db: &DatabaseManager,
+    from_id: &str,
+    to_id: &str,
+) -> Result<NoteRevisionDiff, String> {
+    let from = load_revision(db, from_id)?;
+    let to = load_revision(db, to_id)?;
+    if from.note_id != to.note_id {
+        return Err("Revisions belong to different notes".to_string());
+    }
+    diff_revision_contents(&from, &to)
+}
+
+/// 将笔记恢复到指定版本，恢复结果作为一个新版本保存
+pub fn restore_note_to_revision(
+    db: &DatabaseManager,
+    config: &NotesConfig,
+    revision_id: &str,
+) -> Result<Note, String> {
+    let revision = load_revision(db, revision_id)?;
+    let mut note = db
+        .get_note(&revision.note_id)
+        .map_err(|e| format!("Failed to fetch note: {}", e))?
+        .ok_or_else(|| format!("Note not found: {}", revision.note_id))?;
+
+    note.title = revision.title;
+    note.content = revision.content;
+    db.update_note(&note)
+        .map_err(|e| format!("Failed to update note: {}", e))?;
+
+    // 恢复操作不与最近的编辑合并，保证恢复前的内容仍有独立版本
+    db.save_note_revision(&note, 0, config.max_revisions)
+        .map_err(|e| format!("Failed to save note revision: {}", e))?;
+
+    db.get_note(&note.id)
+        .map_err(|e| format!("Failed to fetch note: {}", e))?
+        .ok_or_else(|| format!("Note not found: {}", note.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::{NoteType, Workspace};
+    use std::env;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_diff_and_restore_revision() {
+        let test_db_path =
+            env::temp_dir().join(format!("test_note_restore_{}.db", uuid::Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+        let config = NotesConfig {
+            revision_interval_secs: 0,
+            max_revisions: 10,
+            ..Default::default()
+        };
+
+        let mut note = Note::new(
+            workspace.id.clone(),
+            "Plan".to_string(),
+            NoteType::Markdown,
+            "a\nb\nc\n".to_string(),
+            PathBuf::from("/tmp/plan.md"),
+        );
+        db.create_note(&note).unwrap();
+        let original = record_note_revision(&db, &config, &note).unwrap();
+
+        note.content = "a\nB\nc\nd\n".to_string();
+        db.update_note(&note).unwrap();
+        let edited = record_note_revision(&db, &config, &note).unwrap();
+
+        let diff = compare_note_revisions(&db, &original.id, &edited.id).unwrap();
+        assert_eq!((diff.additions, diff.deletions), (2, 1));
+        assert!(diff.diff.contains("-b\n"));
+        assert!(diff.diff.contains("+B\n"));
+
+        let restored = restore_note_to_revision(&db, &config, &original.id).unwrap();
+        assert_eq!(restored.content, "a\nb\nc\n");
+        let revisions = db.list_note_revisions(&note.id).unwrap();
+        assert_eq!(revisions.len(), 3);
+        assert_eq!(revisions[0].content, "a\nb\nc\n");
+    }
+}
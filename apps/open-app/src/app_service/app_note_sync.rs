@@ -0,0 +1,1007 @@
+//! 笔记与 Markdown 文件双向同步
+//!
+//! 笔记镜像为 `notebook_dir/<工作空间>/<标题>.md`，文件头部的 YAML front matter 记录
+//! id、标题、类型和标签。外部编辑通过文件监听导入；笔记和文件在上次同步后都被修改时
+//! 标记为冲突，两侧内容均保持不动，由用户选择保留哪一侧。
+
+use chrono::Utc;
+use git2::{ObjectType, Oid};
+use ignore::WalkBuilder;
+use lazy_static::lazy_static;
+use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+use super::{record_note_revision, rename_note_links, sync_note_links};
+use crate::app_state::{
+    AppConfig, DatabaseManager, Note, NoteSyncState, NoteSyncStatus, NoteType, NotesConfig,
+};
+
+lazy_static! {
+    /// 导出和导入互斥，避免监听器读到写了一半的文件
+    static ref SYNC_LOCK: Mutex<()> = Mutex::new(());
+    static ref NOTE_SYNC_WATCHER: Mutex<Option<RecommendedWatcher>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrontMatterValue {
+    Text(String),
+    List(Vec<String>),
+}
+
+impl FrontMatterValue {
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            FrontMatterValue::Text(text) => Some(text),
+            FrontMatterValue::List(_) => None,
+        }
+    }
+
+    /// 列表原样返回，文本按逗号拆分
+    pub fn as_list(&self) -> Vec<String> {
+        match self {
+            FrontMatterValue::Text(text) => text
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            FrontMatterValue::List(items) => items.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteSyncOutcome {
+    Unchanged,
+    Exported,
+    Imported,
+    Created,
+    Conflict,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteSyncChange {
+    pub note_id: String,
+    pub file_path: String,
+    pub outcome: NoteSyncOutcome,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteSyncReport {
+    pub exported: usize,
+    pub imported: usize,
+    pub created: usize,
+    pub conflicts: usize,
+    pub unchanged: usize,
+}
+
+impl NoteSyncReport {
+    fn add(&mut self, outcome: NoteSyncOutcome) {
+        match outcome {
+            NoteSyncOutcome::Unchanged => self.unchanged += 1,
+            NoteSyncOutcome::Exported => self.exported += 1,
+            NoteSyncOutcome::Imported => self.imported += 1,
+            NoteSyncOutcome::Created => self.created += 1,
+            NoteSyncOutcome::Conflict => self.conflicts += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteSyncConflict {
+    pub note: Note,
+    pub file_path: String,
+    /// 文件当前内容，文件已被删除时为空
+    pub file_content: Option<String>,
+}
+
+/// 解决冲突时保留的一侧
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteSyncSide {
+    Note,
+    File,
+}
+
+/// 拆分 front matter 和正文，没有 front matter 时返回全文
+pub fn split_front_matter(text: &str) -> (Option<&str>, &str) {
+    let Some(rest) = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))
+    else {
+        return (None, text);
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed == "---" || trimmed == "..." {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    (None, text)
+}
+
+fn unquote(value: &str) -> String {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        serde_json::from_str(value).unwrap_or_else(|_| value[1..value.len() - 1].to_string())
+    } else if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        value[1..value.len() - 1].replace("''", "'")
+    } else {
+        value.to_string()
+    }
+}
+
+/// 按逗号拆分行内列表，忽略引号内的逗号
+fn split_inline_list(inner: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    for c in inner.chars() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            (',', None) => {
+                items.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    items.push(current);
+
+    items
+        .iter()
+        .map(|item| unquote(item.trim()))
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// 解析 front matter 中的标量和列表字段，不支持的 YAML 结构会被忽略
+pub fn parse_front_matter(yaml: &str) -> HashMap<String, FrontMatterValue> {
+    let mut fields = HashMap::new();
+    let mut list_key: Option<String> = None;
+
+    for line in yaml.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some(key) = &list_key
+            && let Some(item) = trimmed.strip_prefix('-')
+        {
+            let item = unquote(item.trim());
+            if let Some(FrontMatterValue::List(items)) = fields.get_mut(key)
+                && !item.is_empty()
+            {
+                items.push(item);
+            }
+            continue;
+        }
+        list_key = None;
+
+        if line.starts_with([' ', '\t']) {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim().to_string();
+        let value = value.trim();
+
+        if value.is_empty() {
+            fields.insert(key.clone(), FrontMatterValue::List(Vec::new()));
+            list_key = Some(key);
+        } else if value.starts_with('[') && value.ends_with(']') {
+            let items = split_inline_list(&value[1..value.len() - 1]);
+            fields.insert(key, FrontMatterValue::List(items));
+        } else {
+            fields.insert(key, FrontMatterValue::Text(unquote(value)));
+        }
+    }
+    fields
+}
+
+/// 将笔记渲染为带 front matter 的 Markdown 文件内容
+pub fn render_note_markdown(note: &Note) -> String {
+    let quote = |s: &str| serde_json::to_string(s).unwrap_or_default();
+    let tags: Vec<String> = note.tags.iter().map(|t| quote(t)).collect();
+    format!(
+        "---\nid: {}\ntitle: {}\ntype: {}\ntags: [{}]\n---\n{}",
+        note.id,
+        quote(&note.title),
+        note.note_type.as_str(),
+        tags.join(", "),
+        note.content
+    )
+}
+
+fn content_hash(text: &str) -> String {
+    Oid::hash_object(ObjectType::Blob, text.as_bytes())
+        .map(|oid| oid.to_string())
+        .unwrap_or_default()
+}
+
+/// 替换文件名中不允许的字符
+pub fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+    let sanitized = sanitized.trim().trim_matches('.').trim();
+    if sanitized.is_empty() {
+        "Untitled".to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+/// 创建笔记同步根目录并返回规范化路径，与文件监听事件中的路径保持一致
+pub fn notebook_root(notebook_dir: &str) -> Result<PathBuf, String> {
+    fs::create_dir_all(notebook_dir)
+        .map_err(|e| format!("Failed to create notebook directory: {}", e))?;
+    fs::canonicalize(notebook_dir)
+        .map_err(|e| format!("Failed to resolve notebook directory: {}", e))
+}
+
+fn workspace_dir(db: &DatabaseManager, root: &Path, workspace_id: &str) -> Result<PathBuf, String> {
+    let workspace = db
+        .get_workspace(workspace_id)
+        .map_err(|e| format!("Failed to fetch workspace: {}", e))?
+        .ok_or_else(|| format!("Workspace not found: {}", workspace_id))?;
+    Ok(root.join(sanitize_file_name(&workspace.name)))
+}
+
+/// 笔记对应的文件路径，标题重名时追加笔记 ID 前缀
+fn note_file_path(
+    db: &DatabaseManager,
+    root: &Path,
+    note: &Note,
+    current: Option<&Path>,
+) -> Result<PathBuf, String> {
+    let dir = workspace_dir(db, root, &note.workspace_id)?;
+    let name = sanitize_file_name(&note.title);
+    let candidate = dir.join(format!("{}.md", name));
+    if current == Some(candidate.as_path()) {
+        return Ok(candidate);
+    }
+
+    let claimed = db
+        .find_note_sync_state_by_path(&candidate.to_string_lossy())
+        .map_err(|e| format!("Failed to fetch note sync state: {}", e))?
+        .is_some_and(|state| state.note_id != note.id);
+    if claimed || candidate.exists() {
+        let short_id: String = note.id.chars().take(8).collect();
+        return Ok(dir.join(format!("{} ({}).md", name, short_id)));
+    }
+    Ok(candidate)
+}
+
+/// 先写临时文件再重命名，监听器不会看到写了一半的内容
+fn write_file_atomic(path: &Path, content: &str) -> Result<(), String> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| format!("Invalid note file path: {}", path.display()))?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp_path = dir.join(format!(".{}.tmp", file_name));
+    fs::write(&tmp_path, content).map_err(|e| format!("Failed to write note file: {}", e))?;
+    fs::rename(&tmp_path, path).map_err(|e| format!("Failed to write note file: {}", e))
+}
+
+fn save_sync_state(
+    db: &DatabaseManager,
+    note: &Note,
+    path: &Path,
+    note_hash: String,
+    file_hash: String,
+    status: NoteSyncStatus,
+) -> Result<(), String> {
+    let path = path.to_string_lossy().to_string();
+    db.set_note_file_path(&note.id, &path)
+        .map_err(|e| format!("Failed to update note: {}", e))?;
+
+    let mut state = NoteSyncState::new(
+        note.id.clone(),
+        note.workspace_id.clone(),
+        path,
+        note_hash,
+        file_hash,
+    );
+    state.status = status;
+    db.save_note_sync_state(&state)
+        .map_err(|e| format!("Failed to save note sync state: {}", e))
+}
+
+fn export_note(
+    db: &DatabaseManager,
+    root: &Path,
+    note: &Note,
+    previous: Option<&NoteSyncState>,
+) -> Result<(), String> {
+    let rendered = render_note_markdown(note);
+    let previous_path = previous.map(|state| PathBuf::from(&state.file_path));
+    let target = note_file_path(db, root, note, previous_path.as_deref())?;
+    write_file_atomic(&target, &rendered)?;
+
+    // 改名后删除旧文件，旧文件有未导入的外部修改时保留
+    if let (Some(state), Some(old_path)) = (previous, previous_path)
+        && old_path != target
+        && fs::read_to_string(&old_path).is_ok_and(|text| content_hash(&text) == state.file_hash)
+    {
+        let _ = fs::remove_file(&old_path);
+    }
+
+    let hash = content_hash(&rendered);
+    save_sync_state(
+        db,
+        note,
+        &target,
+        hash.clone(),
+        hash,
+        NoteSyncStatus::Synced,
+    )
+}
+
+/// 用 front matter 和正文更新笔记字段
+fn apply_file_to_note(note: &mut Note, text: &str, path: &Path) {
+    let (yaml, body) = split_front_matter(text);
+    let fields = yaml.map(parse_front_matter).unwrap_or_default();
+
+    note.title = fields
+        .get("title")
+        .and_then(|v| v.as_text())
+        .filter(|t| !t.trim().is_empty())
+        .map(|t| t.trim().to_string())
+        .or_else(|| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+        .unwrap_or_else(|| note.title.clone());
+    if let Some(note_type) = fields
+        .get("type")
+        .and_then(|v| v.as_text())
+        .and_then(NoteType::parse)
+    {
+        note.note_type = note_type;
+    }
+    if let Some(tags) = fields.get("tags") {
+        note.tags = tags
+            .as_list()
+            .into_iter()
+            .map(|t| t.trim_start_matches('#').to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+    }
+    note.content = body.to_string();
+}
+
+/// 导入后刷新索引类数据：修订、双链以及改名后的引用
+fn after_note_imported(
+    db: &DatabaseManager,
+    config: &NotesConfig,
+    note: &Note,
+    old_title: Option<&str>,
+) {
+    if let Err(e) = record_note_revision(db, config, note) {
+        log::warn!("{}", e);
+    }
+    if let Some(old_title) = old_title
+        && old_title != note.title
+        && let Err(e) = rename_note_links(db, config, note, old_title)
+    {
+        log::warn!("{}", e);
+    }
+    if let Err(e) = sync_note_links(db, note) {
+        log::warn!("{}", e);
+    }
+}
+
+fn import_into_note(
+    db: &DatabaseManager,
+    config: &NotesConfig,
+    note: &Note,
+    text: &str,
+    path: &Path,
+) -> Result<Note, String> {
+    let mut updated = note.clone();
+    apply_file_to_note(&mut updated, text, path);
+    updated.updated_at = Utc::now().timestamp_millis();
+    db.update_note(&updated)
+        .map_err(|e| format!("Failed to update note: {}", e))?;
+    after_note_imported(db, config, &updated, Some(&note.title));
+
+    let updated = db
+        .get_note(&note.id)
+        .map_err(|e| format!("Failed to fetch note: {}", e))?
+        .ok_or_else(|| format!("Note not found: {}", note.id))?;
+    save_sync_state(
+        db,
+        &updated,
+        path,
+        content_hash(&render_note_markdown(&updated)),
+        content_hash(text),
+        NoteSyncStatus::Synced,
+    )?;
+    Ok(updated)
+}
+
+/// 比较笔记和文件相对上次同步的修改，决定导出、导入或标记冲突
+///
+/// `file` 为文件实际所在路径，默认取同步状态中记录的路径。
+fn reconcile(
+    db: &DatabaseManager,
+    root: &Path,
+    config: &NotesConfig,
+    note: &Note,
+    file: Option<&Path>,
+) -> Result<NoteSyncOutcome, String> {
+    let state = db
+        .get_note_sync_state(&note.id)
+        .map_err(|e| format!("Failed to fetch note sync state: {}", e))?;
+    let rendered = render_note_markdown(note);
+    let note_hash = content_hash(&rendered);
+
+    let path = match (file, &state) {
+        (Some(path), _) => path.to_path_buf(),
+        (None, Some(state)) => PathBuf::from(&state.file_path),
+        (None, None) => {
+            export_note(db, root, note, None)?;
+            return Ok(NoteSyncOutcome::Exported);
+        }
+    };
+    let Ok(text) = fs::read_to_string(&path) else {
+        export_note(db, root, note, state.as_ref())?;
+        return Ok(NoteSyncOutcome::Exported);
+    };
+    let file_hash = content_hash(&text);
+
+    // 两侧内容一致（包括冲突后手动改成一致）时直接视为已同步
+    if text == rendered {
+        let unchanged = state.as_ref().is_some_and(|s| {
+            s.status == NoteSyncStatus::Synced
+                && s.file_path == path.to_string_lossy()
+                && s.note_hash == note_hash
+                && s.file_hash == file_hash
+        });
+        if !unchanged {
+            save_sync_state(
+                db,
+                note,
+                &path,
+                note_hash,
+                file_hash,
+                NoteSyncStatus::Synced,
+            )?;
+        }
+        return Ok(NoteSyncOutcome::Unchanged);
+    }
+
+    let Some(state) = state else {
+        // 没有同步基线，无法判断哪一侧更新
+        save_sync_state(
+            db,
+            note,
+            &path,
+            note_hash,
+            file_hash,
+            NoteSyncStatus::Conflict,
+        )?;
+        return Ok(NoteSyncOutcome::Conflict);
+    };
+    if state.status == NoteSyncStatus::Conflict {
+        return Ok(NoteSyncOutcome::Conflict);
+    }
+
+    let note_changed = note_hash != state.note_hash;
+    let file_changed = file_hash != state.file_hash;
+    match (note_changed, file_changed) {
+        (false, false) => {
+            if state.file_path != path.to_string_lossy() {
+                save_sync_state(
+                    db,
+                    note,
+                    &path,
+                    note_hash,
+                    file_hash,
+                    NoteSyncStatus::Synced,
+                )?;
+            }
+            Ok(NoteSyncOutcome::Unchanged)
+        }
+        (true, false) => {
+            let mut previous = state;
+            previous.file_path = path.to_string_lossy().to_string();
+            export_note(db, root, note, Some(&previous))?;
+            Ok(NoteSyncOutcome::Exported)
+        }
+        (false, true) => {
+            import_into_note(db, config, note, &text, &path)?;
+            Ok(NoteSyncOutcome::Imported)
+        }
+        (true, true) => {
+            let mut conflict = state;
+            conflict.status = NoteSyncStatus::Conflict;
+            db.save_note_sync_state(&conflict)
+                .map_err(|e| format!("Failed to save note sync state: {}", e))?;
+            Ok(NoteSyncOutcome::Conflict)
+        }
+    }
+}
+
+/// 同步单篇笔记
+pub fn sync_note_file(
+    db: &DatabaseManager,
+    root: &Path,
+    config: &NotesConfig,
+    note: &Note,
+) -> Result<NoteSyncOutcome, String> {
+    let _guard = SYNC_LOCK.lock().unwrap();
+    reconcile(db, root, config, note, None)
+}
+
+fn is_markdown_file(path: &Path) -> bool {
+    let hidden = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+    !hidden && path.extension().is_some_and(|ext| ext == "md")
+}
+
+/// 按文件所在的工作空间目录创建新笔记
+fn create_note_from_file(
+    db: &DatabaseManager,
+    root: &Path,
+    config: &NotesConfig,
+    path: &Path,
+    text: &str,
+    id: Option<String>,
+) -> Result<Note, String> {
+    let dir_name = path
+        .strip_prefix(root)
+        .ok()
+        .and_then(|relative| relative.components().next())
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .filter(|_| path.parent() != Some(root))
+        .ok_or_else(|| {
+            format!(
+                "Note file is not in a workspace directory: {}",
+                path.display()
+            )
+        })?;
+    let workspace = db
+        .list_workspaces()
+        .map_err(|e| format!("Failed to fetch workspaces: {}", e))?
+        .into_iter()
+        .find(|ws| sanitize_file_name(&ws.name) == dir_name)
+        .ok_or_else(|| format!("No workspace matches directory: {}", dir_name))?;
+
+    let mut note = Note::new(
+        workspace.id,
+        String::new(),
+        NoteType::Markdown,
+        String::new(),
+        path.to_path_buf(),
+    );
+    if let Some(id) = id {
+        note.id = id;
+    }
+    apply_file_to_note(&mut note, text, path);
+    note.word_count = note.content.split_whitespace().count() as i32;
+    db.create_note(&note)
+        .map_err(|e| format!("Failed to create note: {}", e))?;
+    after_note_imported(db, config, &note, None);
+
+    save_sync_state(
+        db,
+        &note,
+        path,
+        content_hash(&render_note_markdown(&note)),
+        content_hash(text),
+        NoteSyncStatus::Synced,
+    )?;
+    Ok(note)
+}
+
+/// 处理一个 Markdown 文件的变化，文件监听和全量同步共用
+///
+/// 文件已同步过或 front matter 中的 id 对应已有笔记时按三方比较处理，否则新建笔记。
+pub fn import_note_file(
+    db: &DatabaseManager,
+    root: &Path,
+    config: &NotesConfig,
+    path: &Path,
+) -> Result<Option<NoteSyncChange>, String> {
+    if !is_markdown_file(path) || !path.starts_with(root) {
+        return Ok(None);
+    }
+    let _guard = SYNC_LOCK.lock().unwrap();
+    // 文件被删除时不删除笔记
+    let Ok(text) = fs::read_to_string(path) else {
+        return Ok(None);
+    };
+
+    let path_str = path.to_string_lossy().to_string();
+    let state = db
+        .find_note_sync_state_by_path(&path_str)
+        .map_err(|e| format!("Failed to fetch note sync state: {}", e))?;
+    let front_matter_id = split_front_matter(&text)
+        .0
+        .map(parse_front_matter)
+        .and_then(|fields| {
+            fields
+                .get("id")
+                .and_then(|v| v.as_text().map(|s| s.to_string()))
+        });
+
+    let note_id = state.map(|s| s.note_id).or(front_matter_id.clone());
+    let existing = match &note_id {
+        Some(id) => db
+            .get_note(id)
+            .map_err(|e| format!("Failed to fetch note: {}", e))?,
+        None => None,
+    };
+
+    let (note_id, outcome) = match existing {
+        Some(note) => {
+            let outcome = reconcile(db, root, config, &note, Some(path))?;
+            (note.id, outcome)
+        }
+        None => {
+            let note = create_note_from_file(db, root, config, path, &text, front_matter_id)?;
+            (note.id, NoteSyncOutcome::Created)
+        }
+    };
+
+    if outcome == NoteSyncOutcome::Unchanged {
+        return Ok(None);
+    }
+    Ok(Some(NoteSyncChange {
+        note_id,
+        file_path: path_str,
+        outcome,
+    }))
+}
+
+/// 全量同步一个工作空间：先同步所有笔记，再导入目录中新增的文件
+pub fn sync_workspace_note_files(
+    db: &DatabaseManager,
+    root: &Path,
+    config: &NotesConfig,
+    workspace_id: &str,
+) -> Result<NoteSyncReport, String> {
+    let mut report = NoteSyncReport::default();
+    let notes = db
+        .list_notes(workspace_id)
+        .map_err(|e| format!("Failed to fetch notes: {}", e))?;
+    for note in &notes {
+        report.add(sync_note_file(db, root, config, note)?);
+    }
+
+    let dir = workspace_dir(db, root, workspace_id)?;
+    if !dir.is_dir() {
+        return Ok(report);
+    }
+    for entry in WalkBuilder::new(&dir).build().flatten() {
+        let path = entry.path();
+        if !path.is_file() || !is_markdown_file(path) {
+            continue;
+        }
+        let known = db
+            .find_note_sync_state_by_path(&path.to_string_lossy())
+            .map_err(|e| format!("Failed to fetch note sync state: {}", e))?
+            .is_some();
+        if known {
+            continue;
+        }
+        match import_note_file(db, root, config, path) {
+            Ok(Some(change)) => report.add(change.outcome),
+            Ok(None) => {}
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+    Ok(report)
+}
+
+/// 全量同步所有工作空间
+pub fn sync_all_note_files(
+    db: &DatabaseManager,
+    root: &Path,
+    config: &NotesConfig,
+) -> Result<NoteSyncReport, String> {
+    let workspaces = db
+        .list_workspaces()
+        .map_err(|e| format!("Failed to fetch workspaces: {}", e))?;
+
+    let mut report = NoteSyncReport::default();
+    for workspace in workspaces {
+        let workspace_report = sync_workspace_note_files(db, root, config, &workspace.id)?;
+        report.exported += workspace_report.exported;
+        report.imported += workspace_report.imported;
+        report.created += workspace_report.created;
+        report.conflicts += workspace_report.conflicts;
+        report.unchanged += workspace_report.unchanged;
+    }
+    Ok(report)
+}
+
+/// 删除笔记前删除其文件，文件有未导入的外部修改时保留
+pub fn remove_note_file(db: &DatabaseManager, note_id: &str) -> Result<(), String> {
+    let _guard = SYNC_LOCK.lock().unwrap();
+    let Some(state) = db
+        .get_note_sync_state(note_id)
+        .map_err(|e| format!("Failed to fetch note sync state: {}", e))?
+    else {
+        return Ok(());
+    };
+
+    let path = PathBuf::from(&state.file_path);
+    if fs::read_to_string(&path).is_ok_and(|text| content_hash(&text) == state.file_hash) {
+        fs::remove_file(&path).map_err(|e| format!("Failed to delete note file: {}", e))?;
+    }
+    Ok(())
+}
+
+pub fn note_sync_conflicts(
+    db: &DatabaseManager,
+    workspace_id: &str,
+) -> Result<Vec<NoteSyncConflict>, String> {
+    let states = db
+        .list_note_sync_conflicts(workspace_id)
+        .map_err(|e| format!("Failed to fetch note sync conflicts: {}", e))?;
+
+    let mut conflicts = Vec::new();
+    for state in states {
+        let Some(note) = db
+            .get_note(&state.note_id)
+            .map_err(|e| format!("Failed to fetch note: {}", e))?
+        else {
+            continue;
+        };
+        conflicts.push(NoteSyncConflict {
+            note,
+            file_content: fs::read_to_string(&state.file_path).ok(),
+            file_path: state.file_path,
+        });
+    }
+    Ok(conflicts)
+}
+
+/// 保留一侧内容解决冲突，另一侧被覆盖
+pub fn resolve_note_file_conflict(
+    db: &DatabaseManager,
+    config: &NotesConfig,
+    note_id: &str,
+    keep: NoteSyncSide,
+) -> Result<Note, String> {
+    let _guard = SYNC_LOCK.lock().unwrap();
+    let state = db
+        .get_note_sync_state(note_id)
+        .map_err(|e| format!("Failed to fetch note sync state: {}", e))?
+        .filter(|state| state.status == NoteSyncStatus::Conflict)
+        .ok_or_else(|| format!("Note has no sync conflict: {}", note_id))?;
+    let note = db
+        .get_note(note_id)
+        .map_err(|e| format!("Failed to fetch note: {}", e))?
+        .ok_or_else(|| format!("Note not found: {}", note_id))?;
+
+    let path = PathBuf::from(&state.file_path);
+    match (keep, fs::read_to_string(&path)) {
+        (NoteSyncSide::File, Ok(text)) => import_into_note(db, config, &note, &text, &path),
+        (NoteSyncSide::File, Err(e)) => Err(format!("Failed to read note file: {}", e)),
+        (NoteSyncSide::Note, _) => {
+            let rendered = render_note_markdown(&note);
+            write_file_atomic(&path, &rendered)?;
+            let hash = content_hash(&rendered);
+            save_sync_state(db, &note, &path, hash.clone(), hash, NoteSyncStatus::Synced)?;
+            db.get_note(note_id)
+                .map_err(|e| format!("Failed to fetch note: {}", e))?
+                .ok_or_else(|| format!("Note not found: {}", note_id))
+        }
+    }
+}
+
+/// 监听同步根目录，将外部修改导入笔记并通知前端
+pub fn start_note_file_watcher(
+    app_handle: AppHandle,
+    db: Arc<DatabaseManager>,
+    root: PathBuf,
+    config: NotesConfig,
+) -> Result<(), String> {
+    let watch_root = root.clone();
+    let mut watcher = RecommendedWatcher::new(
+        move |res: Result<Event, notify::Error>| {
+            let Ok(event) = res else {
+                return;
+            };
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                return;
+            }
+            for path in event.paths {
+                match import_note_file(&db, &root, &config, &path) {
+                    Ok(Some(change)) => {
+                        if change.outcome == NoteSyncOutcome::Conflict {
+                            log::warn!("Note file conflicts with note: {}", change.file_path);
+                        }
+                        let _ = app_handle.emit(
+                            "note-sync-state-change",
+                            serde_json::json!({
+                                "noteId": change.note_id,
+                                "filePath": change.file_path,
+                                "outcome": change.outcome,
+                                "timestamp": Utc::now().timestamp_millis(),
+                            }),
+                        );
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::warn!("{}", e),
+                }
+            }
+        },
+        Config::default(),
+    )
+    .map_err(|e| format!("Failed to create note file watcher: {}", e))?;
+
+    watcher
+        .watch(&watch_root, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch notebook directory: {}", e))?;
+
+    *NOTE_SYNC_WATCHER.lock().unwrap() = Some(watcher);
+    Ok(())
+}
+
+/// 开启 Markdown 同步：全量同步所有工作空间后监听 notebook_dir 中的外部修改
+///
+/// 全量同步会读写所有笔记文件，需要在阻塞线程中调用。
+pub fn start_markdown_sync(
+    app_handle: AppHandle,
+    db: Arc<DatabaseManager>,
+    config: &AppConfig,
+) -> Result<NoteSyncReport, String> {
+    let notes_config = config.notes.clone().unwrap_or_default();
+    let root = notebook_root(&config.notebook_dir)?;
+    let report = sync_all_note_files(&db, &root, &notes_config)?;
+    start_note_file_watcher(app_handle, db, root, notes_config)?;
+    log::info!(
+        "Note sync started: {} exported, {} imported, {} created, {} conflicts",
+        report.exported,
+        report.imported,
+        report.created,
+        report.conflicts
+    );
+    Ok(report)
+}
+
+pub fn stop_note_file_watcher() {
+    NOTE_SYNC_WATCHER.lock().unwrap().take();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::Workspace;
+    use std::env;
+
+    #[test]
+    fn test_front_matter_round_trip() {
+        let mut note = Note::new(
+            "ws".to_string(),
+            "Say \"hi\": notes".to_string(),
+            NoteType::Markdown,
+            "# Body\n---\nmore".to_string(),
+            PathBuf::from("/tmp/a.md"),
+        );
+        note.tags = vec!["rust".to_string(), "a, b".to_string()];
+
+        let rendered = render_note_markdown(&note);
+        let (yaml, body) = split_front_matter(&rendered);
+        assert_eq!(body, note.content);
+        let fields = parse_front_matter(yaml.unwrap());
+        assert_eq!(fields["id"].as_text(), Some(note.id.as_str()));
+        assert_eq!(fields["title"].as_text(), Some(note.title.as_str()));
+        assert_eq!(fields["tags"].as_list(), note.tags);
+
+        let fields = parse_front_matter("tags:\n  - one\n  - 'it''s'\naliases: x, y\n");
+        assert_eq!(fields["tags"].as_list(), vec!["one", "it's"]);
+        assert_eq!(fields["aliases"].as_list(), vec!["x", "y"]);
+        assert_eq!(split_front_matter("no front matter").0, None);
+    }
+
+    #[test]
+    fn test_sync_import_and_conflict() {
+        let test_db_path =
+            env::temp_dir().join(format!("test_note_sync_{}.db", uuid::Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let root_dir = env::temp_dir().join(format!("test_notebook_{}", uuid::Uuid::new_v4()));
+        let root = notebook_root(&root_dir.to_string_lossy()).unwrap();
+        let config = NotesConfig::default();
+        let workspace = Workspace::new("Work/Space".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+
+        let mut note = Note::new(
+            workspace.id.clone(),
+            "Plan".to_string(),
+            NoteType::Markdown,
+            "v1\n".to_string(),
+            PathBuf::from("/notes/Plan.md"),
+        );
+        db.create_note(&note).unwrap();
+        assert_eq!(
+            sync_note_file(&db, &root, &config, &note).unwrap(),
+            NoteSyncOutcome::Exported
+        );
+        let path = root.join("Work-Space").join("Plan.md");
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            render_note_markdown(&note)
+        );
+
+        // 外部编辑导入笔记，重复事件不会重复导入
+        let edited = fs::read_to_string(&path).unwrap().replace("v1", "v2");
+        fs::write(&path, &edited).unwrap();
+        let change = import_note_file(&db, &root, &config, &path)
+            .unwrap()
+            .unwrap();
+        assert_eq!(change.outcome, NoteSyncOutcome::Imported);
+        assert!(
+            import_note_file(&db, &root, &config, &path)
+                .unwrap()
+                .is_none()
+        );
+        note = db.get_note(&note.id).unwrap().unwrap();
+        assert_eq!(note.content, "v2\n");
+        assert_eq!(note.file_path, path);
+
+        // 新文件按目录导入到对应工作空间
+        let new_path = root.join("Work-Space").join("Idea.md");
+        fs::write(&new_path, "---\ntags: [x]\n---\nidea").unwrap();
+        let change = import_note_file(&db, &root, &config, &new_path)
+            .unwrap()
+            .unwrap();
+        assert_eq!(change.outcome, NoteSyncOutcome::Created);
+        let created = db.get_note(&change.note_id).unwrap().unwrap();
+        assert_eq!(
+            (created.title.as_str(), created.tags.clone()),
+            ("Idea", vec!["x".to_string()])
+        );
+
+        // 两侧同时修改时标记冲突，保留文件一侧解决
+        note.content = "from app\n".to_string();
+        db.update_note(&note).unwrap();
+        fs::write(&path, edited.replace("v2", "from vim")).unwrap();
+        assert_eq!(
+            sync_note_file(&db, &root, &config, &note).unwrap(),
+            NoteSyncOutcome::Conflict
+        );
+        assert_eq!(note_sync_conflicts(&db, &workspace.id).unwrap().len(), 1);
+        let resolved =
+            resolve_note_file_conflict(&db, &config, &note.id, NoteSyncSide::File).unwrap();
+        assert_eq!(resolved.content, "from vim\n");
+        assert!(note_sync_conflicts(&db, &workspace.id).unwrap().is_empty());
+
+        // 改名后文件随之改名
+        let mut renamed = resolved;
+        renamed.title = "Roadmap".to_string();
+        db.update_note(&renamed).unwrap();
+        assert_eq!(
+            sync_note_file(&db, &root, &config, &renamed).unwrap(),
+            NoteSyncOutcome::Exported
+        );
+        assert!(!path.exists());
+        assert!(root.join("Work-Space").join("Roadmap.md").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,354 @@
+//! 笔记模板渲染
+//!
+//! 模板标题和内容中的 `{{变量}}` 在创建笔记时替换。内置变量为 `date`、`time`、`datetime`、
+//! `title` 和 `workspace`，日期类变量可以写成 `{{date:%Y/%m/%d}}` 指定格式；其他变量作为
+//! 需要用户填写的字段。`templates_dir` 下的文件模板通过 front matter 声明名称、类型、
+//! 标题、标签和字段。
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, TimeZone};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{parse_front_matter, split_front_matter};
+use crate::app_state::{DatabaseManager, Note, NoteTemplate, NoteType, TemplateField};
+
+/// 文件模板 ID 的前缀
+pub const FILE_TEMPLATE_PREFIX: &str = "file:";
+
+const BUILTIN_VARIABLES: [&str; 5] = ["date", "time", "datetime", "title", "workspace"];
+
+/// 渲染模板时使用的变量值
+#[derive(Debug, Clone)]
+pub struct TemplateContext<Tz: TimeZone> {
+    pub title: String,
+    pub workspace: String,
+    pub now: DateTime<Tz>,
+    /// 用户填写的字段值
+    pub values: HashMap<String, String>,
+}
+
+/// 按 `{{ ... }}` 拆分出变量名和格式，变量名只允许字母、数字、`_` 和 `-`
+fn parse_placeholder(inner: &str) -> Option<(&str, Option<&str>)> {
+    let inner = inner.trim();
+    let (name, format) = match inner.split_once(':') {
+        Some((name, format)) => (name.trim(), Some(format)),
+        None => (inner, None),
+    };
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    valid.then_some((name, format))
+}
+
+/// 依次处理文本中的占位符，`f` 返回 None 时保留原文
+fn replace_placeholders<F>(text: &str, mut f: F) -> String
+where
+    F: FnMut(&str, Option<&str>) -> Option<String>,
+{
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            output.push_str(&rest[start..]);
+            return output;
+        };
+        let replaced = parse_placeholder(&after[..end]).and_then(|(name, fmt)| f(name, fmt));
+        match replaced {
+            Some(value) => output.push_str(&value),
+            None => output.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// 格式串无效时使用默认格式，避免 chrono 格式化时 panic
+pub(crate) fn format_date<Tz: TimeZone>(
+    now: &DateTime<Tz>,
+    format: Option<&str>,
+    default: &str,
+) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let items: Vec<Item> = StrftimeItems::new(format.unwrap_or(default)).collect();
+    if items.contains(&Item::Error) {
+        return now.format(default).to_string();
+    }
+    now.format_with_items(items.into_iter()).to_string()
+}
+
+/// 替换模板中的变量，未填写的字段替换为空字符串
+pub fn render_template<Tz: TimeZone>(text: &str, context: &TemplateContext<Tz>) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    replace_placeholders(text, |name, format| {
+        let value = match name {
+            "date" => format_date(&context.now, format, "%Y-%m-%d"),
+            "time" => format_date(&context.now, format, "%H:%M"),
+            "datetime" => format_date(&context.now, format, "%Y-%m-%d %H:%M"),
+            "title" => context.title.clone(),
+            "workspace" => context.workspace.clone(),
+            _ => context.values.get(name).cloned().unwrap_or_default(),
+        };
+        Some(value)
+    })
+}
+
+/// 模板需要用户填写的字段：先是声明的字段，然后是标题和内容中出现的其他变量
+pub fn template_fields(template: &NoteTemplate) -> Vec<TemplateField> {
+    let mut fields = template.fields.clone();
+    for text in [&template.title, &template.content] {
+        replace_placeholders(text, |name, _| {
+            if !BUILTIN_VARIABLES.contains(&name) && !fields.iter().any(|f| f.name == name) {
+                fields.push(TemplateField::new(name));
+            }
+            None
+        });
+    }
+    fields
+}
+
+/// 渲染模板生成新笔记，`title` 为空时使用渲染后的模板标题，日期变量取 `now`
+///
+/// 模板标题中的 `{{title}}` 替换为模板名称，内容中的 `{{title}}` 替换为最终的笔记标题。
+pub fn build_note_from_template(
+    template: &NoteTemplate,
+    workspace_id: &str,
+    workspace_name: &str,
+    title: Option<&str>,
+    values: &HashMap<String, String>,
+    now: DateTime<Local>,
+) -> Note {
+    let mut context = TemplateContext {
+        title: template.name.clone(),
+        workspace: workspace_name.to_string(),
+        now,
+        values: template_fields(template)
+            .into_iter()
+            .filter_map(|field| {
+                let value = values.get(&field.name).cloned().or(field.default_value)?;
+                Some((field.name, value))
+            })
+            .collect(),
+    };
+
+    let title = match title.map(str::trim).filter(|t| !t.is_empty()) {
+        Some(title) => title.to_string(),
+        None => render_template(&template.title, &context)
+            .trim()
+            .to_string(),
+    };
+    let title = if title.is_empty() {
+        template.name.clone()
+    } else {
+        title
+    };
+    context.title = title.clone();
+
+    let content = render_template(&template.content, &context);
+    let file_path = PathBuf::from(format!("/notes/{}.md", title));
+    let mut note = Note::new(
+        workspace_id.to_string(),
+        title,
+        template.note_type.clone(),
+        content,
+        file_path,
+    );
+    note.tags = template
+        .tags
+        .iter()
+        .map(|tag| render_template(tag, &context))
+        .filter(|tag| !tag.trim().is_empty())
+        .collect();
+    note
+}
+
+fn note_type_for_extension(path: &Path) -> NoteType {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("md" | "markdown") => NoteType::Markdown,
+        Some("html" | "htm") => NoteType::RichText,
+        Some("mmd" | "mermaid") => NoteType::Flowchart,
+        _ => NoteType::Code,
+    }
+}
+
+/// 读取单个模板文件，front matter 中的 `fields` 为字段名列表
+fn load_file_template(path: &Path) -> Result<NoteTemplate, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read template {}: {}", path.display(), e))?;
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| file_name.clone());
+
+    let (yaml, body) = split_front_matter(&text);
+    let fields = yaml.map(parse_front_matter).unwrap_or_default();
+    let text_field = |key: &str| {
+        fields
+            .get(key)
+            .and_then(|v| v.as_text())
+            .map(str::to_string)
+            .filter(|s| !s.is_empty())
+    };
+    let list_field = |key: &str| fields.get(key).map(|v| v.as_list()).unwrap_or_default();
+
+    let modified = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(|t| DateTime::<Local>::from(t).timestamp_millis())
+        .unwrap_or_default();
+    let name = text_field("name").unwrap_or(stem);
+    Ok(NoteTemplate {
+        id: format!("{}{}", FILE_TEMPLATE_PREFIX, file_name),
+        workspace_id: None,
+        title: text_field("title").unwrap_or_else(|| name.clone()),
+        name,
+        description: text_field("description"),
+        note_type: text_field("type")
+            .and_then(|t| NoteType::parse(&t))
+            .unwrap_or_else(|| note_type_for_extension(path)),
+        content: body.to_string(),
+        tags: list_field("tags"),
+        fields: list_field("fields")
+            .iter()
+            .map(|name| TemplateField::new(name))
+            .collect(),
+        created_at: modified,
+        updated_at: modified,
+    })
+}
+
+/// 列出 `templates_dir` 下的模板文件，目录不存在时返回空列表
+pub fn list_file_templates(templates_dir: &str) -> Result<Vec<NoteTemplate>, String> {
+    let entries = match fs::read_dir(templates_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read templates directory: {}", e)),
+    };
+
+    let mut templates = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if hidden || !path.is_file() {
+            continue;
+        }
+        match load_file_template(&path) {
+            Ok(template) => templates.push(template),
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+    templates.sort_by_key(|t| t.name.to_lowercase());
+    Ok(templates)
+}
+
+/// 工作空间模板和文件模板，工作空间模板在前；`fields` 包含内容中出现的所有字段
+pub fn list_note_templates(
+    db: &DatabaseManager,
+    templates_dir: &str,
+    workspace_id: &str,
+) -> Result<Vec<NoteTemplate>, String> {
+    let mut templates = db
+        .list_note_templates(workspace_id)
+        .map_err(|e| format!("Failed to fetch templates: {}", e))?;
+    templates.extend(list_file_templates(templates_dir)?);
+    for template in &mut templates {
+        template.fields = template_fields(template);
+    }
+    Ok(templates)
+}
+
+/// 按 ID 查找模板，`file:` 开头的 ID 从 `templates_dir` 读取
+pub fn find_note_template(
+    db: &DatabaseManager,
+    templates_dir: &str,
+    id: &str,
+) -> Result<NoteTemplate, String> {
+    if let Some(file_name) = id.strip_prefix(FILE_TEMPLATE_PREFIX) {
+        let path = Path::new(templates_dir).join(file_name);
+        if file_name.contains(['/', '\\']) || !path.is_file() {
+            return Err(format!("Template not found: {}", id));
+        }
+        return load_file_template(&path);
+    }
+    db.get_note_template(id)
+        .map_err(|e| format!("Failed to fetch template: {}", e))?
+        .ok_or_else(|| format!("Template not found: {}", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_render_template_variables() {
+        let context = TemplateContext {
+            title: "Weekly sync".to_string(),
+            workspace: "Work".to_string(),
+            now: Utc.with_ymd_and_hms(2024, 3, 5, 9, 30, 0).unwrap(),
+            values: HashMap::from([("client".to_string(), "Acme".to_string())]),
+        };
+        let text = "# {{title}} ({{ workspace }})\n{{date}} {{time}} {{date:%d/%m}} {{date:%Q}}\n\
+                    Client: {{client}}, owner: {{owner}}, {{not a var}}, {{unclosed";
+        assert_eq!(
+            render_template(text, &context),
+            "# Weekly sync (Work)\n2024-03-05 09:30 05/03 2024-03-05\n\
+             Client: Acme, owner: , {{not a var}}, {{unclosed"
+        );
+    }
+
+    #[test]
+    fn test_template_fields_and_file_template() {
+        let dir = std::env::temp_dir().join(format!("template-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("meeting.md"),
+            "---\nname: Meeting\ntitle: \"{{title}} {{date}}\"\ntags: [meeting]\nfields: [agenda]\n---\n\
+             # {{title}}\nAttendees: {{attendees}}\n{{agenda}}\n",
+        )
+        .unwrap();
+
+        let templates = list_file_templates(dir.to_str().unwrap()).unwrap();
+        assert_eq!(templates.len(), 1);
+        let template = &templates[0];
+        assert_eq!(template.id, "file:meeting.md");
+        assert_eq!(template.note_type, NoteType::Markdown);
+        let names: Vec<String> = template_fields(template)
+            .into_iter()
+            .map(|f| f.name)
+            .collect();
+        assert_eq!(names, ["agenda", "attendees"]);
+
+        let values = HashMap::from([("agenda".to_string(), "- Roadmap".to_string())]);
+        let note = build_note_from_template(template, "ws", "Work", None, &values, Local::now());
+        assert!(note.title.starts_with("Meeting 20"));
+        assert_eq!(
+            note.content,
+            format!("# {}\nAttendees: \n- Roadmap\n", note.title)
+        );
+        assert_eq!(note.tags, ["meeting"]);
+
+        let note = build_note_from_template(
+            template,
+            "ws",
+            "Work",
+            Some("Kickoff"),
+            &values,
+            Local::now(),
+        );
+        assert_eq!(note.title, "Kickoff");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
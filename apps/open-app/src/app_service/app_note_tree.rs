@@ -0,0 +1,173 @@
+//! 笔记树
+//!
+//! 将平铺的笔记组装为嵌套树，并在移动前校验目标父节点和循环引用。
+
+use std::collections::{HashMap, HashSet};
+
+use crate::app_state::{DatabaseManager, NoteMove, NoteTreeNode};
+
+/// 将按同级顺序排列的节点组装为树，父节点缺失的笔记放在根级
+pub fn build_note_tree(nodes: Vec<NoteTreeNode>) -> Vec<NoteTreeNode> {
+    let ids: HashSet<String> = nodes.iter().map(|n| n.id.clone()).collect();
+    let mut children: HashMap<Option<String>, Vec<NoteTreeNode>> = HashMap::new();
+    for node in nodes {
+        let parent = node.parent_id.clone().filter(|p| ids.contains(p));
+        children.entry(parent).or_default().push(node);
+    }
+
+    fn attach(
+        mut node: NoteTreeNode,
+        children: &mut HashMap<Option<String>, Vec<NoteTreeNode>>,
+    ) -> NoteTreeNode {
+        if let Some(items) = children.remove(&Some(node.id.clone())) {
+            node.children = items
+                .into_iter()
+                .map(|child| attach(child, children))
+                .collect();
+        }
+        node
+    }
+
+    let roots = children.remove(&None).unwrap_or_default();
+    roots
+        .into_iter()
+        .map(|root| attach(root, &mut children))
+        .collect()
+}
+
+pub fn workspace_note_tree(
+    db: &DatabaseManager,
+    workspace_id: &str,
+) -> Result<Vec<NoteTreeNode>, String> {
+    let nodes = db
+        .list_note_tree_nodes(workspace_id)
+        .map_err(|e| format!("Failed to fetch notes: {}", e))?;
+    Ok(build_note_tree(nodes))
+}
+
+/// 校验并执行一组移动，全部成功或全部不生效
+pub fn validate_and_move_notes(db: &DatabaseManager, moves: &[NoteMove]) -> Result<(), String> {
+    // 笔记 ID -> (工作空间, 父节点)，随移动依次更新以校验后续移动
+    let mut notes: HashMap<String, (String, Option<String>)> = HashMap::new();
+    let mut loaded = HashSet::new();
+
+    for note_move in moves {
+        let note = db
+            .get_note(&note_move.note_id)
+            .map_err(|e| format!("Failed to fetch note: {}", e))?
+            .ok_or_else(|| format!("Note not found: {}", note_move.note_id))?;
+
+        if loaded.insert(note.workspace_id.clone()) {
+            let nodes = db
+                .list_note_tree_nodes(&note.workspace_id)
+                .map_err(|e| format!("Failed to fetch notes: {}", e))?;
+            for node in nodes {
+                notes
+                    .entry(node.id)
+                    .or_insert((note.workspace_id.clone(), node.parent_id));
+            }
+        }
+
+        if let Some(parent_id) = &note_move.parent_id {
+            match notes.get(parent_id) {
+                Some((workspace_id, _)) if *workspace_id == note.workspace_id => {}
+                _ => return Err(format!("Parent note not found: {}", parent_id)),
+            }
+
+            let mut current = Some(parent_id.clone());
+            let mut steps = 0;
+            while let Some(id) = current {
+                if id == note.id {
+                    return Err(format!(
+                        "Cannot move note {} into its own descendant",
+                        note.id
+                    ));
+                }
+                steps += 1;
+                if steps > notes.len() {
+                    break;
+                }
+                current = notes.get(&id).and_then(|(_, parent)| parent.clone());
+            }
+        }
+
+        notes.insert(
+            note.id.clone(),
+            (note.workspace_id.clone(), note_move.parent_id.clone()),
+        );
+    }
+
+    db.apply_note_moves(moves)
+        .map_err(|e| format!("Failed to move notes: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::{Note, NoteType, Workspace};
+    use std::env;
+    use std::path::PathBuf;
+
+    fn titles(nodes: &[NoteTreeNode]) -> Vec<&str> {
+        nodes.iter().map(|n| n.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_move_notes_and_build_tree() {
+        let test_db_path =
+            env::temp_dir().join(format!("test_note_tree_{}.db", uuid::Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+
+        let mut ids = Vec::new();
+        for (i, title) in ["A", "B", "C", "D"].iter().enumerate() {
+            let mut note = Note::new(
+                workspace.id.clone(),
+                title.to_string(),
+                NoteType::Markdown,
+                String::new(),
+                PathBuf::from(format!("/tmp/{}.md", title)),
+            );
+            note.sort_order = i as i32;
+            db.create_note(&note).unwrap();
+            ids.push(note.id);
+        }
+        let (a, b, c, d) = (&ids[0], &ids[1], &ids[2], &ids[3]);
+
+        let move_to = |id: &String, parent: Option<&String>, index| NoteMove {
+            note_id: id.clone(),
+            parent_id: parent.cloned(),
+            index,
+        };
+        validate_and_move_notes(
+            &db,
+            &[
+                move_to(c, Some(a), 0),
+                move_to(b, Some(a), 0),
+                move_to(d, Some(b), 9),
+            ],
+        )
+        .unwrap();
+
+        let tree = workspace_note_tree(&db, &workspace.id).unwrap();
+        assert_eq!(titles(&tree), vec!["A"]);
+        assert_eq!(titles(&tree[0].children), vec!["B", "C"]);
+        assert_eq!(titles(&tree[0].children[0].children), vec!["D"]);
+        let note = db.get_note(c).unwrap().unwrap();
+        assert_eq!(note.parent_id.as_deref(), Some(a.as_str()));
+        assert_eq!(note.sort_order, 1);
+
+        // 移动到自己的后代会被拒绝，整批操作不生效
+        let err = validate_and_move_notes(&db, &[move_to(c, None, 0), move_to(a, Some(d), 0)])
+            .unwrap_err();
+        assert!(err.contains("descendant"));
+        let tree = workspace_note_tree(&db, &workspace.id).unwrap();
+        assert_eq!(titles(&tree), vec!["A"]);
+
+        validate_and_move_notes(&db, &[move_to(d, None, 0)]).unwrap();
+        let tree = workspace_note_tree(&db, &workspace.id).unwrap();
+        assert_eq!(titles(&tree), vec!["D", "A"]);
+        assert!(tree[0].children.is_empty());
+    }
+}
@@ -0,0 +1,354 @@
+//! 仓库删除清理
+//!
+//! 删除本地克隆目录并统计释放的磁盘空间，同时清除 open-node 为仓库写入的
+//! 符号（symbol.db）、依赖边（edge.db / reverse_edge.db）、SurrealDB 全文检索符号和 Qdrant 向量。
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use super::KeyvStore;
+use crate::app_state::{AppConfig, QdrantConfig, SurrealDbConfig};
+
+/// open-node 写入代码向量的集合，与其 QdrantService 保持一致
+const SYMBOL_COLLECTION: &str = "code_symbols";
+/// open-node 写入全文检索符号的命名空间和数据库，与其 SurrealDBService 保持一致
+const SURREAL_NAMESPACE: &str = "code_index";
+const SURREAL_DATABASE: &str = "open_context";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryPurgeReport {
+    pub repository_id: String,
+    /// 一并清理的子模块仓库
+    pub submodule_ids: Vec<String>,
+    pub local_path_removed: bool,
+    pub freed_bytes: u64,
+    pub files_removed: usize,
+    pub symbols_removed: usize,
+    pub edges_removed: usize,
+    pub vectors_purged: bool,
+    pub full_text_purged: bool,
+    /// 未能完成的清理步骤，不影响仓库记录的删除
+    pub warnings: Vec<String>,
+}
+
+/// 目录占用的字节数，不跟随符号链接
+pub fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| !entry.file_type().is_dir())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// 路径是否位于应用管理的工作空间目录下
+///
+/// 只有由应用克隆的仓库才允许删除磁盘目录，手动导入的本地仓库不受影响。
+pub fn is_managed_clone(config: &AppConfig, path: &Path) -> bool {
+    let (Ok(root), Ok(path)) = (
+        Path::new(&config.workspace_dir).canonicalize(),
+        path.canonicalize(),
+    ) else {
+        return false;
+    };
+    path != root && path.starts_with(&root)
+}
+
+fn remove_file(path: &Path) -> std::io::Result<()> {
+    // .git/objects 下的文件是只读的，Windows 上需要先去掉只读属性才能删除
+    #[cfg(target_os = "windows")]
+    if let Ok(metadata) = fs::symlink_metadata(path) {
+        let mut permissions = metadata.permissions();
+        if permissions.readonly() {
+            permissions.set_readonly(false);
+            let _ = fs::set_permissions(path, permissions);
+        }
+    }
+    fs::remove_file(path)
+}
+
+/// 逐个删除目录中的文件，返回 (释放的字节数, 删除的文件数)
+///
+/// 每删除一个文件调用 `on_file(已释放字节数)`，返回 false 时停止；
+/// 已删除的文件无法恢复。
+pub fn remove_dir_tracked<F>(path: &Path, mut on_file: F) -> Result<(u64, usize), String>
+where
+    F: FnMut(u64) -> bool,
+{
+    let mut freed = 0;
+    let mut files = 0;
+
+    for entry in WalkDir::new(path).follow_links(false).contents_first(true) {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if entry.file_type().is_dir() {
+            fs::remove_dir(entry.path())
+                .map_err(|e| format!("Failed to remove {}: {}", entry.path().display(), e))?;
+            continue;
+        }
+
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        remove_file(entry.path())
+            .map_err(|e| format!("Failed to remove {}: {}", entry.path().display(), e))?;
+        freed += size;
+        files += 1;
+
+        if !on_file(freed) {
+            return Err("Repository deletion cancelled".to_string());
+        }
+    }
+
+    Ok((freed, files))
+}
+
+/// 删除 Keyv 存储中属于仓库的键，数据库不存在时视为无数据
+fn purge_keyv(db_path: &Path, prefix: &str) -> Result<usize, String> {
+    if !db_path.exists() {
+        return Ok(0);
+    }
+    KeyvStore::open(db_path)?.delete_prefix(prefix)
+}
+
+/// 从反向边中移除来自仓库的调用方，返回删除的键数
+///
+/// 反向边以 `{to}:{type}` 为键、调用方列表为值，未解析的调用目标 `{to}` 只是裸名称
+/// （如 `resolve`），不带仓库前缀，只能逐个检查值。列表清空后删除该键。
+fn purge_reverse_edges(db_path: &Path, prefix: &str) -> Result<usize, String> {
+    if !db_path.exists() {
+        return Ok(0);
+    }
+    let store = KeyvStore::open(db_path)?;
+    let mut removed = store.delete_prefix(prefix)?;
+    for (key, value) in store.scan_prefix("")? {
+        let Some(froms) = value.as_array() else {
+            continue;
+        };
+        let kept: Vec<serde_json::Value> = froms
+            .iter()
+            .filter(|from| !from.as_str().is_some_and(|f| f.starts_with(prefix)))
+            .cloned()
+            .collect();
+        if kept.len() == froms.len() {
+            continue;
+        }
+        if kept.is_empty() {
+            store.delete(&key)?;
+            removed += 1;
+        } else {
+            store.set(&key, &serde_json::Value::Array(kept))?;
+        }
+    }
+    Ok(removed)
+}
+
+/// 清除仓库的符号和依赖边，返回 (符号数, 边的键数)
+///
+/// open-node 的符号 ID 形如 `{workspace}/{repo}/{file}#{name}`，符号以 `symbol:{id}`
+/// 为键，边以 `{from}:{type}` 为键。
+pub fn purge_symbol_stores(workspace_id: &str, repo_id: &str) -> Result<(usize, usize), String> {
+    purge_symbol_stores_at(
+        &AppConfig::sqlite_symbol_db_path(),
+        &AppConfig::sqlite_edge_db_path(),
+        &AppConfig::sqlite_reverse_edge_db_path(),
+        workspace_id,
+        repo_id,
+    )
+}
+
+fn purge_symbol_stores_at(
+    symbol_db: &Path,
+    edge_db: &Path,
+    reverse_edge_db: &Path,
+    workspace_id: &str,
+    repo_id: &str,
+) -> Result<(usize, usize), String> {
+    let prefix = format!("{}/{}/", workspace_id, repo_id);
+    let symbols = purge_keyv(symbol_db, &format!("symbol:{}", prefix))?;
+    let edges = purge_keyv(edge_db, &prefix)? + purge_reverse_edges(reverse_edge_db, &prefix)?;
+    Ok((symbols, edges))
+}
+
+/// 删除 SurrealDB 中属于仓库的全文检索符号
+pub async fn purge_repository_full_text(
+    config: &SurrealDbConfig,
+    repo_id: &str,
+) -> Result<(), String> {
+    let url = format!("{}/sql", config.url.trim_end_matches('/'));
+    // JSON 字符串同时是合法的 SurrealQL 字符串字面量
+    let query = format!(
+        "LET $repo_id = {}; DELETE symbol WHERE repo_id = $repo_id;",
+        serde_json::Value::from(repo_id)
+    );
+
+    let response = reqwest::Client::new()
+        .post(&url)
+        .basic_auth(&config.username, Some(&config.password))
+        .header("Accept", "application/json")
+        .header("Surreal-NS", SURREAL_NAMESPACE)
+        .header("Surreal-DB", SURREAL_DATABASE)
+        .body(query)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach SurrealDB at {}: {}", config.url, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("Failed to delete symbols ({}): {}", status, text));
+    }
+    // 每条语句单独返回执行状态
+    let results: Vec<serde_json::Value> = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse SurrealDB response: {}", e))?;
+    match results.iter().find(|result| result["status"] != "OK") {
+        Some(failed) => Err(format!("Failed to delete symbols: {}", failed["result"])),
+        None => Ok(()),
+    }
+}
+
+/// 删除 Qdrant 中属于仓库的向量
+pub async fn purge_repository_vectors(config: &QdrantConfig, repo_id: &str) -> Result<(), String> {
+    let url = format!(
+        "{}/collections/{}/points/delete?wait=true",
+        config.url.trim_end_matches('/'),
+        SYMBOL_COLLECTION
+    );
+    let body = serde_json::json!({
+        "filter": {
+            "must": [{ "key": "repo_id", "match": { "value": repo_id } }]
+        }
+    });
+
+    let mut request = reqwest::Client::new().post(&url).json(&body);
+    if let Some(api_key) = &config.api_key {
+        request = request.header("api-key", api_key);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Qdrant at {}: {}", config.url, e))?;
+
+    let status = response.status();
+    // 集合不存在说明还没有写入过向量
+    if status.is_success() || status.as_u16() == 404 {
+        Ok(())
+    } else {
+        let text = response.text().await.unwrap_or_default();
+        Err(format!("Failed to delete vectors ({}): {}", status, text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    /// 创建 @keyv/sqlite 格式的数据库并写入条目
+    fn keyv_db(path: &Path, entries: &[(&str, serde_json::Value)]) {
+        let conn = rusqlite::Connection::open(path).unwrap();
+        conn.execute(
+            "CREATE TABLE keyv (key VARCHAR(255) PRIMARY KEY, value TEXT)",
+            [],
+        )
+        .unwrap();
+        drop(conn);
+        let store = KeyvStore::open(path).unwrap();
+        for (key, value) in entries {
+            store.set(key, value).unwrap();
+        }
+    }
+
+    fn keys(path: &Path) -> Vec<String> {
+        let store = KeyvStore::open_read_only(path).unwrap();
+        let mut keys: Vec<String> = store
+            .scan_prefix("")
+            .unwrap()
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn test_purge_symbol_stores_removes_open_node_keys() {
+        use serde_json::json;
+
+        let dir = env::temp_dir().join(format!("test_purge_keyv_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let (symbol_db, edge_db, reverse_db) = (
+            dir.join("symbol.db"),
+            dir.join("edge.db"),
+            dir.join("reverse_edge.db"),
+        );
+        let main = "ws/repo/src/main.ts#main";
+        let other = "ws/other/src/lib.ts#run";
+        keyv_db(
+            &symbol_db,
+            &[
+                (&format!("symbol:{}", main), json!({"name": "main"})),
+                (&format!("symbol:{}", other), json!({"name": "run"})),
+                ("connection_test", json!(true)),
+            ],
+        );
+        keyv_db(
+            &edge_db,
+            &[
+                (&format!("{}:CALLS", main), json!(["resolve"])),
+                (&format!("{}:CALLS", other), json!(["resolve"])),
+            ],
+        );
+        keyv_db(
+            &reverse_db,
+            &[
+                ("resolve:CALLS", json!([main, other])),
+                ("helper:CALLS", json!([main])),
+                (&format!("{}:CALLS", main), json!([other])),
+            ],
+        );
+
+        let (symbols, edges) =
+            purge_symbol_stores_at(&symbol_db, &edge_db, &reverse_db, "ws", "repo").unwrap();
+        assert_eq!(symbols, 1);
+        assert_eq!(edges, 3);
+        assert_eq!(
+            keys(&symbol_db),
+            ["connection_test", &format!("symbol:{}", other)]
+        );
+        assert_eq!(keys(&edge_db), [format!("{}:CALLS", other)]);
+        assert_eq!(keys(&reverse_db), ["resolve:CALLS"]);
+        let store = KeyvStore::open_read_only(&reverse_db).unwrap();
+        assert_eq!(store.get("resolve:CALLS").unwrap(), Some(json!([other])));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_remove_dir_tracked_reports_freed_bytes() {
+        let root = env::temp_dir().join(format!("test_cleanup_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::write(root.join("a/one.txt"), "12345").unwrap();
+        fs::write(root.join("a/b/two.txt"), "123").unwrap();
+        fs::write(root.join("three.txt"), "12").unwrap();
+        assert_eq!(dir_size(&root), 10);
+
+        let mut calls = 0;
+        let result = remove_dir_tracked(&root, |_| {
+            calls += 1;
+            calls < 2
+        });
+        assert!(result.is_err());
+        assert!(root.exists());
+
+        let remaining = dir_size(&root);
+        let (freed, files) = remove_dir_tracked(&root, |_| true).unwrap();
+        assert_eq!(freed, remaining);
+        assert_eq!(files, 1);
+        assert!(!root.exists());
+    }
+}
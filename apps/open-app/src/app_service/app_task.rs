@@ -470,6 +470,14 @@ impl TaskManager {
         }
     }
 
+    /// 任务是否已被取消（供长时间运行的任务在步骤之间检查）
+    pub fn is_cancelled(&self, task_id: &str) -> bool {
+        let tasks = self.tasks.lock().unwrap();
+        tasks
+            .get(task_id)
+            .is_some_and(|task| task.status == TaskStatus::Cancelled)
+    }
+
     pub fn cancel(&self, task_id: &str) -> bool {
         let mut tasks = self.tasks.lock().unwrap();
         if let Some(task) = tasks.get_mut(task_id)
@@ -1,9 +1,51 @@
+mod app_context_pack;
 mod app_file_tree;
+mod app_git;
+mod app_git_analytics;
+mod app_git_branch;
+mod app_git_history;
+mod app_git_status;
+mod app_graph;
+mod app_keyv;
+mod app_manifest;
+mod app_note_daily;
+mod app_note_export;
+mod app_note_import;
+mod app_note_insight;
+mod app_note_link;
+mod app_note_notion;
+mod app_note_render;
+mod app_note_revision;
+mod app_note_sync;
+mod app_note_template;
+mod app_note_tree;
+mod app_repo_cleanup;
 mod app_runtime;
 mod app_sidecar;
 mod app_task;
 
+pub use app_context_pack::*;
 pub use app_file_tree::*;
+pub use app_git::*;
+pub use app_git_analytics::*;
+pub use app_git_branch::*;
+pub use app_git_history::*;
+pub use app_git_status::*;
+pub use app_graph::*;
+pub use app_keyv::*;
+pub use app_manifest::*;
+pub use app_note_daily::*;
+pub use app_note_export::*;
+pub use app_note_import::*;
+pub use app_note_insight::*;
+pub use app_note_link::*;
+pub use app_note_notion::*;
+pub use app_note_render::*;
+pub use app_note_revision::*;
+pub use app_note_sync::*;
+pub use app_note_template::*;
+pub use app_note_tree::*;
+pub use app_repo_cleanup::*;
 pub use app_runtime::*;
 pub use app_sidecar::*;
 pub use app_task::*;
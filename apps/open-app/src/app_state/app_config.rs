@@ -95,6 +95,62 @@ impl Default for NodeServerConfig {
         }
     }
 }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotesConfig {
+    /// 在该时间（秒）内的连续保存合并为一个修订版本，0 表示每次保存都生成版本
+    pub revision_interval_secs: u64,
+    /// 每篇笔记保留的修订版本数，0 表示不限制
+    pub max_revisions: usize,
+    /// 是否将笔记同步为 notebook_dir 下的 Markdown 文件
+    #[serde(default)]
+    pub markdown_sync: bool,
+    /// 日记标题的 chrono 日期格式
+    #[serde(default = "default_daily_title_format")]
+    pub daily_title_format: String,
+    /// 日记所在父笔记的标题，为空时日记放在根级
+    #[serde(default = "default_daily_parent_title")]
+    pub daily_parent_title: Option<String>,
+    /// 创建日记时使用的模板 ID
+    #[serde(default)]
+    pub daily_template_id: Option<String>,
+}
+
+fn default_daily_title_format() -> String {
+    "%Y-%m-%d".to_string()
+}
+
+fn default_daily_parent_title() -> Option<String> {
+    Some("Daily Notes".to_string())
+}
+
+impl Default for NotesConfig {
+    fn default() -> Self {
+        Self {
+            revision_interval_secs: 60,
+            max_revisions: 100,
+            markdown_sync: false,
+            daily_title_format: default_daily_title_format(),
+            daily_parent_title: default_daily_parent_title(),
+            daily_template_id: None,
+        }
+    }
+}
+
+/// OpenAI 兼容的对话接口，用于生成笔记摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmConfig {
+    /// 接口地址，如 `https://api.openai.com/v1`
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+}
+
+impl LlmConfig {
+    pub fn is_configured(&self) -> bool {
+        !self.base_url.trim().is_empty() && !self.model.trim().is_empty()
+    }
+}
+
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -114,10 +170,18 @@ pub struct AppConfig {
     pub skills_dir: String,
     pub rules_dir: String,
     pub hooks_dir: String,
+    /// 笔记模板文件目录，对所有工作空间可用
+    #[serde(default)]
+    pub templates_dir: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub database: Option<DatabaseConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub node_server: Option<NodeServerConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<NotesConfig>,
+    /// 未配置时不生成 LLM 摘要
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub llm: Option<LlmConfig>,
 }
 
 impl Default for AppConfig {
@@ -140,8 +204,11 @@ impl Default for AppConfig {
             skills_dir: format!("{}/skills", app_data_dir),
             rules_dir: format!("{}/rules", app_data_dir),
             hooks_dir: format!("{}/hooks", app_data_dir),
+            templates_dir: format!("{}/templates", app_data_dir),
             database: Some(DatabaseConfig::default()),
             node_server: Some(NodeServerConfig::default()),
+            notes: Some(NotesConfig::default()),
+            llm: None,
         }
     }
 }
@@ -213,6 +280,21 @@ impl AppConfig {
         Self::database_dir().join("sqlite").join("app.db")
     }
 
+    /// 符号数据库（由 open-node 索引时写入）
+    pub fn sqlite_symbol_db_path() -> PathBuf {
+        Self::database_dir().join("sqlite").join("symbol.db")
+    }
+
+    /// 依赖图正向边数据库（由 open-node 索引时写入）
+    pub fn sqlite_edge_db_path() -> PathBuf {
+        Self::database_dir().join("sqlite").join("edge.db")
+    }
+
+    /// 依赖图反向边数据库（由 open-node 索引时写入）
+    pub fn sqlite_reverse_edge_db_path() -> PathBuf {
+        Self::database_dir().join("sqlite").join("reverse_edge.db")
+    }
+
     /// Load configuration from file
     pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
         let default_config = AppConfig::default();
@@ -283,6 +365,9 @@ impl AppConfig {
         if loaded.hooks_dir.is_empty() {
             loaded.hooks_dir = default.hooks_dir;
         }
+        if loaded.templates_dir.is_empty() {
+            loaded.templates_dir = default.templates_dir;
+        }
 
         // 确保可选配置存在
         if loaded.database.is_none() {
@@ -291,6 +376,9 @@ impl AppConfig {
         if loaded.node_server.is_none() {
             loaded.node_server = default.node_server;
         }
+        if loaded.notes.is_none() {
+            loaded.notes = default.notes;
+        }
 
         loaded
     }
@@ -363,6 +451,7 @@ pub fn init_app_dirs() -> Result<(), Box<dyn std::error::Error>> {
         "skills",
         "rules",
         "hooks",
+        "templates",
     ];
 
     for dir in dirs {
@@ -168,11 +168,12 @@ impl DatabaseManager {
 
         conn.execute(
             "UPDATE imported_files
-             SET name = ?1, file_type = ?2, mime_type = ?3, updated_at = ?4
-             WHERE id = ?5",
+             SET name = ?1, file_type = ?2, size_bytes = ?3, mime_type = ?4, updated_at = ?5
+             WHERE id = ?6",
             params![
                 file.name,
                 file.file_type,
+                file.size_bytes,
                 file.mime_type,
                 updated_at,
                 file.id,
@@ -0,0 +1,300 @@
+//! Git 远程凭据管理模块
+//!
+//! 按远程地址（主机或 URL 前缀）保存 SSH 密钥、ssh-agent 或 HTTPS 令牌配置。
+//! 凭据保存在 app.db 中，不会写入 config.json。
+//!
+//! 注意：SSH 私钥口令和 HTTPS 令牌以明文存放在 app.db 的 secret 列，没有使用系统钥匙串，
+//! 能读取应用数据目录的用户或程序都能读到。SSH 密钥推荐使用 ssh-agent，HTTPS 推荐使用
+//! 权限受限的令牌。更新凭据时传入 `clearSecret` 可删除已保存的口令 / 令牌。
+
+use chrono::Utc;
+use rusqlite::{Result as SqliteResult, Row, params};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::app_state::DatabaseManager;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GitCredentialKind {
+    /// SSH 私钥文件（可带口令）
+    SshKey,
+    /// 使用 ssh-agent 中的密钥
+    SshAgent,
+    /// HTTPS 用户名 + 令牌
+    HttpsToken,
+}
+
+impl GitCredentialKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            GitCredentialKind::SshKey => "ssh_key",
+            GitCredentialKind::SshAgent => "ssh_agent",
+            GitCredentialKind::HttpsToken => "https_token",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "ssh_key" => Some(GitCredentialKind::SshKey),
+            "ssh_agent" => Some(GitCredentialKind::SshAgent),
+            "https_token" => Some(GitCredentialKind::HttpsToken),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCredential {
+    pub id: String,
+    /// 匹配的远程地址，如 `github.com` 或 `github.com/my-org`
+    pub remote_pattern: String,
+    pub kind: GitCredentialKind,
+    pub username: Option<String>,
+    pub ssh_key_path: Option<String>,
+    /// SSH 私钥口令或 HTTPS 令牌，不会返回给前端
+    #[serde(skip_serializing, default)]
+    pub secret: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl GitCredential {
+    pub fn new(remote_pattern: String, kind: GitCredentialKind) -> Self {
+        let now = Utc::now().timestamp_millis();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            remote_pattern: normalize_remote(&remote_pattern),
+            kind,
+            username: None,
+            ssh_key_path: None,
+            secret: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// 凭据是否适用于该远程地址
+    pub fn matches_url(&self, url: &str) -> bool {
+        let remote = normalize_remote(url);
+        remote == self.remote_pattern || remote.starts_with(&format!("{}/", self.remote_pattern))
+    }
+}
+
+/// 将远程地址规范化为 `host/path` 形式，去掉协议、用户名、端口和 `.git` 后缀
+///
+/// `git@github.com:org/repo.git`、`https://user@github.com/org/repo`
+/// 都会得到 `github.com/org/repo`。
+pub fn normalize_remote(url: &str) -> String {
+    let url = url.trim();
+    let (rest, scp_like) = match url.split_once("://") {
+        Some((_, rest)) => (rest, false),
+        None => (url, true),
+    };
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    let rest = match rest[..authority_end].rfind('@') {
+        Some(at) => &rest[at + 1..],
+        None => rest,
+    };
+
+    let (host, path) = match rest.find(['/', ':']) {
+        Some(idx) => (&rest[..idx], &rest[idx + 1..]),
+        None => (rest, ""),
+    };
+    // URL 形式中冒号后面是端口
+    let path = if !scp_like && rest[host.len()..].starts_with(':') {
+        path.split_once('/').map(|(_, p)| p).unwrap_or("")
+    } else {
+        path
+    };
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if path.is_empty() {
+        host.to_lowercase()
+    } else {
+        format!("{}/{}", host.to_lowercase(), path)
+    }
+}
+
+/// 选出与远程地址匹配且最具体（模式最长）的凭据
+pub fn select_git_credential<'a>(
+    credentials: &'a [GitCredential],
+    url: &str,
+) -> Option<&'a GitCredential> {
+    credentials
+        .iter()
+        .filter(|c| c.matches_url(url))
+        .max_by_key(|c| c.remote_pattern.len())
+}
+
+fn row_to_credential(row: &Row) -> SqliteResult<GitCredential> {
+    let kind_str: String = row.get(2)?;
+    Ok(GitCredential {
+        id: row.get(0)?,
+        remote_pattern: row.get(1)?,
+        kind: GitCredentialKind::parse(&kind_str).unwrap_or(GitCredentialKind::SshAgent),
+        username: row.get(3)?,
+        ssh_key_path: row.get(4)?,
+        secret: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+impl DatabaseManager {
+    pub fn create_git_credential(&self, credential: &GitCredential) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        conn.execute(
+            "INSERT INTO git_credentials (id, remote_pattern, kind, username, ssh_key_path, secret, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                credential.id,
+                credential.remote_pattern,
+                credential.kind.as_str(),
+                credential.username,
+                credential.ssh_key_path,
+                credential.secret,
+                credential.created_at,
+                credential.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_git_credential(&self, id: &str) -> SqliteResult<Option<GitCredential>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, remote_pattern, kind, username, ssh_key_path, secret, created_at, updated_at
+             FROM git_credentials WHERE id = ?1",
+        )?;
+
+        let mut rows = stmt.query(params![id])?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(row_to_credential(row)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn list_git_credentials(&self) -> SqliteResult<Vec<GitCredential>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, remote_pattern, kind, username, ssh_key_path, secret, created_at, updated_at
+             FROM git_credentials ORDER BY remote_pattern",
+        )?;
+
+        let rows = stmt.query_map([], row_to_credential)?;
+        let mut credentials = Vec::new();
+        for credential in rows {
+            credentials.push(credential?);
+        }
+        Ok(credentials)
+    }
+
+    pub fn update_git_credential(&self, credential: &GitCredential) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let updated_at = Utc::now().timestamp_millis();
+
+        conn.execute(
+            "UPDATE git_credentials
+             SET remote_pattern = ?1, kind = ?2, username = ?3, ssh_key_path = ?4, secret = ?5, updated_at = ?6
+             WHERE id = ?7",
+            params![
+                credential.remote_pattern,
+                credential.kind.as_str(),
+                credential.username,
+                credential.ssh_key_path,
+                credential.secret,
+                updated_at,
+                credential.id,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_git_credential(&self, id: &str) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        conn.execute("DELETE FROM git_credentials WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// 查找适用于远程地址的凭据
+    pub fn find_git_credential_for_url(&self, url: &str) -> SqliteResult<Option<GitCredential>> {
+        let credentials = self.list_git_credentials()?;
+        Ok(select_git_credential(&credentials, url).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_normalize_remote() {
+        assert_eq!(
+            normalize_remote("git@github.com:org/repo.git"),
+            "github.com/org/repo"
+        );
+        assert_eq!(
+            normalize_remote("https://user@GitHub.com/org/repo/"),
+            "github.com/org/repo"
+        );
+        assert_eq!(
+            normalize_remote("ssh://git@gitlab.example.com:2222/team/app.git"),
+            "gitlab.example.com/team/app"
+        );
+        assert_eq!(normalize_remote("github.com"), "github.com");
+    }
+
+    #[test]
+    fn test_credential_crud_and_matching() {
+        let test_db_path =
+            env::temp_dir().join(format!("test_credential_{}.db", uuid::Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+
+        let mut host = GitCredential::new("github.com".to_string(), GitCredentialKind::SshAgent);
+        host.username = Some("git".to_string());
+        db.create_git_credential(&host).unwrap();
+
+        let mut org = GitCredential::new(
+            "https://github.com/my-org".to_string(),
+            GitCredentialKind::HttpsToken,
+        );
+        org.username = Some("bot".to_string());
+        org.secret = Some("token".to_string());
+        db.create_git_credential(&org).unwrap();
+
+        let found = db
+            .find_git_credential_for_url("https://github.com/my-org/private.git")
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, org.id);
+        assert_eq!(found.secret.as_deref(), Some("token"));
+
+        let found = db
+            .find_git_credential_for_url("git@github.com:other/repo.git")
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, host.id);
+
+        assert!(
+            db.find_git_credential_for_url("https://gitlab.com/my-org/app.git")
+                .unwrap()
+                .is_none()
+        );
+
+        let json = serde_json::to_value(&org).unwrap();
+        assert!(json.get("secret").is_none());
+
+        db.delete_git_credential(&org.id).unwrap();
+        assert_eq!(db.list_git_credentials().unwrap().len(), 1);
+    }
+}
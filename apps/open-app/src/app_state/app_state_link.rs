@@ -7,7 +7,7 @@ use rusqlite::{Result as SqliteResult, params};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::app_state::DatabaseManager;
+use crate::app_state::{DatabaseManager, TagEntityType, set_entity_tags_conn};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -56,9 +56,10 @@ impl DatabaseManager {
     pub fn create_web_link(&self, link: &WebLink) -> SqliteResult<()> {
         let conn_arc = self.conn();
         let conn = conn_arc.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
         let tags_json = serde_json::to_string(&link.tags).unwrap_or_else(|_| "[]".to_string());
 
-        conn.execute(
+        tx.execute(
             "INSERT INTO web_links (id, workspace_id, title, url, description, favicon_url, thumbnail_url, tags, content, is_favorited, is_archived, visit_count, last_visited_at, created_at, updated_at)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
             params![
@@ -79,7 +80,14 @@ impl DatabaseManager {
                 link.updated_at,
             ],
         )?;
-        Ok(())
+        set_entity_tags_conn(
+            &tx,
+            &TagEntityType::Link,
+            &link.id,
+            &link.workspace_id,
+            &link.tags,
+        )?;
+        tx.commit()
     }
 
     pub fn get_web_link(&self, id: &str) -> SqliteResult<Option<WebLink>> {
@@ -158,10 +166,11 @@ impl DatabaseManager {
     pub fn update_web_link(&self, link: &WebLink) -> SqliteResult<()> {
         let conn_arc = self.conn();
         let conn = conn_arc.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
         let updated_at = Utc::now().timestamp_millis();
         let tags_json = serde_json::to_string(&link.tags).unwrap_or_else(|_| "[]".to_string());
 
-        conn.execute(
+        tx.execute(
             "UPDATE web_links SET title = ?1, url = ?2, description = ?3, favicon_url = ?4, thumbnail_url = ?5, tags = ?6, content = ?7, is_favorited = ?8, is_archived = ?9, visit_count = ?10, last_visited_at = ?11, updated_at = ?12 WHERE id = ?13",
             params![
                 link.title,
@@ -179,7 +188,14 @@ impl DatabaseManager {
                 link.id,
             ],
         )?;
-        Ok(())
+        set_entity_tags_conn(
+            &tx,
+            &TagEntityType::Link,
+            &link.id,
+            &link.workspace_id,
+            &link.tags,
+        )?;
+        tx.commit()
     }
 
     pub fn delete_web_link(&self, id: &str) -> SqliteResult<()> {
@@ -0,0 +1,299 @@
+//! 项目清单管理模块
+//!
+//! 索引时识别的 Cargo.toml / package.json / pyproject.toml / go.mod，
+//! 记录项目类型、工作区成员、依赖和脚本，依赖可在工作空间内按名称检索。
+
+use chrono::Utc;
+use rusqlite::{Result as SqliteResult, Row, params};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::app_state::DatabaseManager;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectType {
+    Cargo,
+    Npm,
+    Python,
+    Go,
+}
+
+impl ProjectType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ProjectType::Cargo => "cargo",
+            ProjectType::Npm => "npm",
+            ProjectType::Python => "python",
+            ProjectType::Go => "go",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "cargo" => Some(ProjectType::Cargo),
+            "npm" => Some(ProjectType::Npm),
+            "python" => Some(ProjectType::Python),
+            "go" => Some(ProjectType::Go),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestDependency {
+    pub name: String,
+    /// 声明的版本要求，如 `1.35`、`^18.2.0`、`>=2.0`；路径或 git 依赖为空
+    pub version: Option<String>,
+    /// normal / dev / build / peer / optional
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectManifest {
+    pub id: String,
+    pub repo_id: String,
+    pub workspace_id: String,
+    /// 清单文件在仓库内的相对路径
+    pub path: String,
+    pub project_type: ProjectType,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub workspace_members: Vec<String>,
+    pub dependencies: Vec<ManifestDependency>,
+    pub scripts: BTreeMap<String, String>,
+    pub created_at: i64,
+}
+
+impl ProjectManifest {
+    pub fn new(path: String, project_type: ProjectType) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            repo_id: String::new(),
+            workspace_id: String::new(),
+            path,
+            project_type,
+            name: None,
+            version: None,
+            workspace_members: Vec::new(),
+            dependencies: Vec::new(),
+            scripts: BTreeMap::new(),
+            created_at: Utc::now().timestamp_millis(),
+        }
+    }
+}
+
+/// 依赖检索结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyMatch {
+    pub repository_id: String,
+    pub repository_name: String,
+    pub manifest_path: String,
+    pub project_type: ProjectType,
+    pub project_name: Option<String>,
+    pub dependency: ManifestDependency,
+}
+
+fn row_to_manifest(row: &Row) -> SqliteResult<ProjectManifest> {
+    let project_type_str: String = row.get(4)?;
+    let members: String = row.get(7)?;
+    let scripts: String = row.get(8)?;
+    Ok(ProjectManifest {
+        id: row.get(0)?,
+        repo_id: row.get(1)?,
+        workspace_id: row.get(2)?,
+        path: row.get(3)?,
+        project_type: ProjectType::parse(&project_type_str).unwrap_or(ProjectType::Cargo),
+        name: row.get(5)?,
+        version: row.get(6)?,
+        workspace_members: serde_json::from_str(&members).unwrap_or_default(),
+        dependencies: Vec::new(),
+        scripts: serde_json::from_str(&scripts).unwrap_or_default(),
+        created_at: row.get(9)?,
+    })
+}
+
+impl DatabaseManager {
+    /// 用新识别的清单替换仓库原有的记录
+    pub fn replace_project_manifests(
+        &self,
+        repo_id: &str,
+        workspace_id: &str,
+        manifests: &[ProjectManifest],
+    ) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+
+        tx.execute(
+            "DELETE FROM project_manifests WHERE repo_id = ?1",
+            params![repo_id],
+        )?;
+        for manifest in manifests {
+            tx.execute(
+                "INSERT INTO project_manifests (id, repo_id, workspace_id, path, project_type, name, version, workspace_members, scripts, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    manifest.id,
+                    repo_id,
+                    workspace_id,
+                    manifest.path,
+                    manifest.project_type.as_str(),
+                    manifest.name,
+                    manifest.version,
+                    serde_json::to_string(&manifest.workspace_members).unwrap_or_default(),
+                    serde_json::to_string(&manifest.scripts).unwrap_or_default(),
+                    manifest.created_at,
+                ],
+            )?;
+            for dependency in &manifest.dependencies {
+                tx.execute(
+                    "INSERT INTO manifest_dependencies (manifest_id, repo_id, workspace_id, name, version, kind)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        manifest.id,
+                        repo_id,
+                        workspace_id,
+                        dependency.name,
+                        dependency.version,
+                        dependency.kind,
+                    ],
+                )?;
+            }
+        }
+
+        tx.commit()
+    }
+
+    pub fn list_project_manifests(&self, repo_id: &str) -> SqliteResult<Vec<ProjectManifest>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, repo_id, workspace_id, path, project_type, name, version, workspace_members, scripts, created_at
+             FROM project_manifests WHERE repo_id = ?1 ORDER BY path",
+        )?;
+        let rows = stmt.query_map(params![repo_id], row_to_manifest)?;
+        let mut manifests = Vec::new();
+        for manifest in rows {
+            manifests.push(manifest?);
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT name, version, kind FROM manifest_dependencies
+             WHERE manifest_id = ?1 ORDER BY kind, name",
+        )?;
+        for manifest in &mut manifests {
+            let rows = stmt.query_map(params![manifest.id], |row| {
+                Ok(ManifestDependency {
+                    name: row.get(0)?,
+                    version: row.get(1)?,
+                    kind: row.get(2)?,
+                })
+            })?;
+            for dependency in rows {
+                manifest.dependencies.push(dependency?);
+            }
+        }
+        Ok(manifests)
+    }
+
+    /// 按依赖名称（大小写不敏感）检索工作空间内的声明
+    pub fn search_manifest_dependencies(
+        &self,
+        workspace_id: &str,
+        name: &str,
+    ) -> SqliteResult<Vec<DependencyMatch>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT d.repo_id, r.name, m.path, m.project_type, m.name, d.name, d.version, d.kind
+             FROM manifest_dependencies d
+             JOIN project_manifests m ON m.id = d.manifest_id
+             JOIN git_repositories r ON r.id = d.repo_id
+             WHERE d.workspace_id = ?1 AND d.name = ?2 COLLATE NOCASE
+             ORDER BY r.name, m.path",
+        )?;
+
+        let rows = stmt.query_map(params![workspace_id, name], |row| {
+            let project_type_str: String = row.get(3)?;
+            Ok(DependencyMatch {
+                repository_id: row.get(0)?,
+                repository_name: row.get(1)?,
+                manifest_path: row.get(2)?,
+                project_type: ProjectType::parse(&project_type_str).unwrap_or(ProjectType::Cargo),
+                project_name: row.get(4)?,
+                dependency: ManifestDependency {
+                    name: row.get(5)?,
+                    version: row.get(6)?,
+                    kind: row.get(7)?,
+                },
+            })
+        })?;
+
+        let mut matches = Vec::new();
+        for item in rows {
+            matches.push(item?);
+        }
+        Ok(matches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::{GitRepository, Workspace};
+    use std::env;
+
+    #[test]
+    fn test_replace_and_search_manifests() {
+        let test_db_path =
+            env::temp_dir().join(format!("test_manifest_{}.db", uuid::Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+        let repo = GitRepository::new(
+            workspace.id.clone(),
+            "svc".to_string(),
+            "https://github.com/user/svc.git".to_string(),
+            std::path::PathBuf::from("/local/svc"),
+            "main".to_string(),
+        );
+        db.create_git_repository(&repo).unwrap();
+
+        let mut manifest = ProjectManifest::new("Cargo.toml".to_string(), ProjectType::Cargo);
+        manifest.name = Some("svc".to_string());
+        manifest.workspace_members = vec!["crates/*".to_string()];
+        manifest.dependencies.push(ManifestDependency {
+            name: "tokio".to_string(),
+            version: Some("1.35".to_string()),
+            kind: "normal".to_string(),
+        });
+        db.replace_project_manifests(&repo.id, &workspace.id, &[manifest.clone()])
+            .unwrap();
+        // 重新索引时替换旧记录
+        db.replace_project_manifests(&repo.id, &workspace.id, &[manifest])
+            .unwrap();
+
+        let manifests = db.list_project_manifests(&repo.id).unwrap();
+        assert_eq!(manifests.len(), 1);
+        assert_eq!(manifests[0].workspace_members, vec!["crates/*"]);
+        assert_eq!(manifests[0].dependencies.len(), 1);
+
+        let matches = db
+            .search_manifest_dependencies(&workspace.id, "Tokio")
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].repository_name, "svc");
+
+        db.delete_git_repository(&repo.id).unwrap();
+        assert!(
+            db.search_manifest_dependencies(&workspace.id, "tokio")
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -1,10 +1,12 @@
 use chrono::Utc;
-use rusqlite::{Result as SqliteResult, params};
+use rusqlite::{Result as SqliteResult, Row, params};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use uuid::Uuid;
 
-use crate::app_state::DatabaseManager;
+use crate::app_state::{
+    DatabaseManager, TagEntityType, set_entity_tags_conn, sync_note_todos_conn,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -106,31 +108,83 @@ impl Note {
     }
 }
 
+/// 按 `SELECT id, workspace_id, parent_id, title, note_type, content, summary, file_path, tags,
+/// word_count, sort_order, is_favorited, is_pinned, is_archived, last_viewed_at, created_at, updated_at`
+/// 的列顺序读取笔记
+pub(crate) fn row_to_note(row: &Row) -> SqliteResult<Note> {
+    let note_type_str: String = row.get(4)?;
+    let tags_json: Option<String> = row.get(8)?;
+    let tags: Vec<String> = tags_json
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    Ok(Note {
+        id: row.get(0)?,
+        workspace_id: row.get(1)?,
+        parent_id: row.get(2)?,
+        title: row.get(3)?,
+        note_type: NoteType::parse(&note_type_str).unwrap_or(NoteType::RichText),
+        content: row.get(5)?,
+        summary: row.get(6)?,
+        file_path: PathBuf::from(row.get::<_, String>(7)?),
+        tags,
+        word_count: row.get(9)?,
+        sort_order: row.get(10)?,
+        is_favorited: row.get::<_, i32>(11)? != 0,
+        is_pinned: row.get::<_, i32>(12)? != 0,
+        is_archived: row.get::<_, i32>(13)? != 0,
+        last_viewed_at: row.get(14)?,
+        created_at: row.get(15)?,
+        updated_at: row.get(16)?,
+    })
+}
+
 /// Note management operations
 impl DatabaseManager {
     /// Create a new note
     pub fn create_note(&self, note: &Note) -> SqliteResult<()> {
         let conn_arc = self.conn();
         let conn = conn_arc.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
         let tags_json = serde_json::to_string(&note.tags).unwrap();
 
-        conn.execute(
-            "INSERT INTO notes (id, workspace_id, title, note_type, content, file_path, tags, is_favorited, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        tx.execute(
+            "INSERT INTO notes (id, workspace_id, parent_id, title, note_type, content, summary, file_path, tags, word_count, sort_order, is_favorited, is_pinned, is_archived, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
             params![
                 note.id,
                 note.workspace_id,
+                note.parent_id,
                 note.title,
                 note.note_type.as_str(),
                 note.content,
+                note.summary,
                 note.file_path.to_str().unwrap(),
                 tags_json,
+                note.word_count,
+                note.sort_order,
                 note.is_favorited as i32,
+                note.is_pinned as i32,
+                note.is_archived as i32,
                 note.created_at,
                 note.updated_at,
             ],
         )?;
-        Ok(())
+        set_entity_tags_conn(
+            &tx,
+            &TagEntityType::Note,
+            &note.id,
+            &note.workspace_id,
+            &note.tags,
+        )?;
+        sync_note_todos_conn(
+            &tx,
+            &note.id,
+            &note.workspace_id,
+            &note.note_type,
+            &note.content,
+        )?;
+        tx.commit()
     }
 
     /// Get note by ID
@@ -138,36 +192,13 @@ impl DatabaseManager {
         let conn_arc = self.conn();
         let conn = conn_arc.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, workspace_id, title, note_type, content, file_path, tags, is_favorited, created_at, updated_at
+            "SELECT id, workspace_id, parent_id, title, note_type, content, summary, file_path, tags, word_count, sort_order, is_favorited, is_pinned, is_archived, last_viewed_at, created_at, updated_at
              FROM notes WHERE id = ?1",
         )?;
 
         let mut rows = stmt.query(params![id])?;
         if let Some(row) = rows.next()? {
-            let note_type_str: String = row.get(3)?;
-            let tags_json: String = row.get(6)?;
-            let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
-            let is_favorited: i32 = row.get(7)?;
-
-            Ok(Some(Note {
-                id: row.get(0)?,
-                workspace_id: row.get(1)?,
-                parent_id: None,
-                title: row.get(2)?,
-                note_type: NoteType::parse(&note_type_str).unwrap_or(NoteType::RichText),
-                content: row.get(4)?,
-                summary: None,
-                file_path: PathBuf::from(row.get::<_, String>(5)?),
-                tags,
-                word_count: 0,
-                sort_order: 0,
-                is_favorited: is_favorited != 0,
-                is_pinned: false,
-                is_archived: false,
-                last_viewed_at: None,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
-            }))
+            Ok(Some(row_to_note(row)?))
         } else {
             Ok(None)
         }
@@ -178,36 +209,11 @@ impl DatabaseManager {
         let conn_arc = self.conn();
         let conn = conn_arc.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, workspace_id, title, note_type, content, file_path, tags, is_favorited, created_at, updated_at
+            "SELECT id, workspace_id, parent_id, title, note_type, content, summary, file_path, tags, word_count, sort_order, is_favorited, is_pinned, is_archived, last_viewed_at, created_at, updated_at
              FROM notes WHERE workspace_id = ?1 ORDER BY updated_at DESC",
         )?;
 
-        let rows = stmt.query_map(params![workspace_id], |row| {
-            let note_type_str: String = row.get(3)?;
-            let tags_json: String = row.get(6)?;
-            let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
-            let is_favorited: i32 = row.get(7)?;
-
-            Ok(Note {
-                id: row.get(0)?,
-                workspace_id: row.get(1)?,
-                parent_id: None,
-                title: row.get(2)?,
-                note_type: NoteType::parse(&note_type_str).unwrap_or(NoteType::RichText),
-                content: row.get(4)?,
-                summary: None,
-                file_path: PathBuf::from(row.get::<_, String>(5)?),
-                tags,
-                word_count: 0,
-                sort_order: 0,
-                is_favorited: is_favorited != 0,
-                is_pinned: false,
-                is_archived: false,
-                last_viewed_at: None,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
-            })
-        })?;
+        let rows = stmt.query_map(params![workspace_id], row_to_note)?;
 
         let mut notes = Vec::new();
         for note in rows {
@@ -225,36 +231,11 @@ impl DatabaseManager {
         let conn_arc = self.conn();
         let conn = conn_arc.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, workspace_id, title, note_type, content, file_path, tags, is_favorited, created_at, updated_at
+            "SELECT id, workspace_id, parent_id, title, note_type, content, summary, file_path, tags, word_count, sort_order, is_favorited, is_pinned, is_archived, last_viewed_at, created_at, updated_at
              FROM notes WHERE workspace_id = ?1 AND note_type = ?2 ORDER BY updated_at DESC",
         )?;
 
-        let rows = stmt.query_map(params![workspace_id, note_type.as_str()], |row| {
-            let note_type_str: String = row.get(3)?;
-            let tags_json: String = row.get(6)?;
-            let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
-            let is_favorited: i32 = row.get(7)?;
-
-            Ok(Note {
-                id: row.get(0)?,
-                workspace_id: row.get(1)?,
-                parent_id: None,
-                title: row.get(2)?,
-                note_type: NoteType::parse(&note_type_str).unwrap_or(NoteType::RichText),
-                content: row.get(4)?,
-                summary: None,
-                file_path: PathBuf::from(row.get::<_, String>(5)?),
-                tags,
-                word_count: 0,
-                sort_order: 0,
-                is_favorited: is_favorited != 0,
-                is_pinned: false,
-                is_archived: false,
-                last_viewed_at: None,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
-            })
-        })?;
+        let rows = stmt.query_map(params![workspace_id, note_type.as_str()], row_to_note)?;
 
         let mut notes = Vec::new();
         for note in rows {
@@ -267,10 +248,11 @@ impl DatabaseManager {
     pub fn update_note(&self, note: &Note) -> SqliteResult<()> {
         let conn_arc = self.conn();
         let conn = conn_arc.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
         let updated_at = Utc::now().timestamp_millis();
         let tags_json = serde_json::to_string(&note.tags).unwrap();
 
-        conn.execute(
+        tx.execute(
             "UPDATE notes
              SET title = ?1, note_type = ?2, content = ?3, file_path = ?4, tags = ?5, is_favorited = ?6, updated_at = ?7
              WHERE id = ?8",
@@ -285,7 +267,21 @@ impl DatabaseManager {
                 note.id,
             ],
         )?;
-        Ok(())
+        set_entity_tags_conn(
+            &tx,
+            &TagEntityType::Note,
+            &note.id,
+            &note.workspace_id,
+            &note.tags,
+        )?;
+        sync_note_todos_conn(
+            &tx,
+            &note.id,
+            &note.workspace_id,
+            &note.note_type,
+            &note.content,
+        )?;
+        tx.commit()
     }
 
     /// Delete note
@@ -296,53 +292,6 @@ impl DatabaseManager {
         Ok(())
     }
 
-    /// Search notes by title or content
-    pub fn search_notes(&self, workspace_id: &str, query: &str) -> SqliteResult<Vec<Note>> {
-        let conn_arc = self.conn();
-        let conn = conn_arc.lock().unwrap();
-        let search_pattern = format!("%{}%", query);
-
-        let mut stmt = conn.prepare(
-            "SELECT id, workspace_id, title, note_type, content, file_path, tags, is_favorited, created_at, updated_at
-             FROM notes
-             WHERE workspace_id = ?1 AND (title LIKE ?2 OR content LIKE ?2)
-             ORDER BY updated_at DESC",
-        )?;
-
-        let rows = stmt.query_map(params![workspace_id, search_pattern], |row| {
-            let note_type_str: String = row.get(3)?;
-            let tags_json: String = row.get(6)?;
-            let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
-            let is_favorited: i32 = row.get(7)?;
-
-            Ok(Note {
-                id: row.get(0)?,
-                workspace_id: row.get(1)?,
-                parent_id: None,
-                title: row.get(2)?,
-                note_type: NoteType::parse(&note_type_str).unwrap_or(NoteType::RichText),
-                content: row.get(4)?,
-                summary: None,
-                file_path: PathBuf::from(row.get::<_, String>(5)?),
-                tags,
-                word_count: 0,
-                sort_order: 0,
-                is_favorited: is_favorited != 0,
-                is_pinned: false,
-                is_archived: false,
-                last_viewed_at: None,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
-            })
-        })?;
-
-        let mut notes = Vec::new();
-        for note in rows {
-            notes.push(note?);
-        }
-        Ok(notes)
-    }
-
     /// Add tag to note
     pub fn add_note_tag(&self, note_id: &str, tag: &str) -> SqliteResult<()> {
         let mut note = self
@@ -399,36 +348,11 @@ impl DatabaseManager {
         let conn_arc = self.conn();
         let conn = conn_arc.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, workspace_id, title, note_type, content, file_path, tags, is_favorited, created_at, updated_at
+            "SELECT id, workspace_id, parent_id, title, note_type, content, summary, file_path, tags, word_count, sort_order, is_favorited, is_pinned, is_archived, last_viewed_at, created_at, updated_at
              FROM notes WHERE workspace_id = ?1 AND is_favorited = 1 ORDER BY updated_at DESC",
         )?;
 
-        let rows = stmt.query_map(params![workspace_id], |row| {
-            let note_type_str: String = row.get(3)?;
-            let tags_json: String = row.get(6)?;
-            let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
-            let is_favorited: i32 = row.get(7)?;
-
-            Ok(Note {
-                id: row.get(0)?,
-                workspace_id: row.get(1)?,
-                parent_id: None,
-                title: row.get(2)?,
-                note_type: NoteType::parse(&note_type_str).unwrap_or(NoteType::RichText),
-                content: row.get(4)?,
-                summary: None,
-                file_path: PathBuf::from(row.get::<_, String>(5)?),
-                tags,
-                word_count: 0,
-                sort_order: 0,
-                is_favorited: is_favorited != 0,
-                is_pinned: false,
-                is_archived: false,
-                last_viewed_at: None,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
-            })
-        })?;
+        let rows = stmt.query_map(params![workspace_id], row_to_note)?;
 
         let mut notes = Vec::new();
         for note in rows {
@@ -0,0 +1,89 @@
+//! 笔记日历查询模块
+//!
+//! 按 created_at / updated_at 查询时间范围内创建或修改过的笔记，按天分组由
+//! app_note_daily 服务在本地时区下完成。
+
+use rusqlite::{Result as SqliteResult, params};
+
+use crate::app_state::{DatabaseManager, Note, row_to_note};
+
+impl DatabaseManager {
+    /// `[start, end)` 内创建或修改过的笔记的 (created_at, updated_at)
+    pub fn list_note_timestamps(
+        &self,
+        workspace_id: &str,
+        start: i64,
+        end: i64,
+    ) -> SqliteResult<Vec<(i64, i64)>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT created_at, updated_at FROM notes
+             WHERE workspace_id = ?1
+               AND ((created_at >= ?2 AND created_at < ?3) OR (updated_at >= ?2 AND updated_at < ?3))",
+        )?;
+        let rows = stmt.query_map(params![workspace_id, start, end], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+        rows.collect()
+    }
+
+    /// `[start, end)` 内创建或修改过的笔记，按 updated_at 倒序
+    pub fn list_notes_active_between(
+        &self,
+        workspace_id: &str,
+        start: i64,
+        end: i64,
+    ) -> SqliteResult<Vec<Note>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, workspace_id, parent_id, title, note_type, content, summary, file_path, tags, word_count, sort_order, is_favorited, is_pinned, is_archived, last_viewed_at, created_at, updated_at
+             FROM notes
+             WHERE workspace_id = ?1
+               AND ((created_at >= ?2 AND created_at < ?3) OR (updated_at >= ?2 AND updated_at < ?3))
+             ORDER BY updated_at DESC",
+        )?;
+        let rows = stmt.query_map(params![workspace_id, start, end], row_to_note)?;
+        rows.collect()
+    }
+
+    /// 查找同一父节点下指定标题的笔记，`parent_id` 为 None 时查找根级笔记
+    pub fn find_child_note_by_title(
+        &self,
+        workspace_id: &str,
+        parent_id: Option<&str>,
+        title: &str,
+    ) -> SqliteResult<Option<Note>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, workspace_id, parent_id, title, note_type, content, summary, file_path, tags, word_count, sort_order, is_favorited, is_pinned, is_archived, last_viewed_at, created_at, updated_at
+             FROM notes
+             WHERE workspace_id = ?1 AND parent_id IS ?2 AND title = ?3
+             ORDER BY created_at
+             LIMIT 1",
+        )?;
+        let mut rows = stmt.query(params![workspace_id, parent_id, title])?;
+        match rows.next()? {
+            Some(row) => Ok(Some(row_to_note(row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 同级笔记末尾的 sort_order
+    pub fn next_note_sort_order(
+        &self,
+        workspace_id: &str,
+        parent_id: Option<&str>,
+    ) -> SqliteResult<i32> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        conn.query_row(
+            "SELECT COALESCE(MAX(sort_order) + 1, 0) FROM notes
+             WHERE workspace_id = ?1 AND parent_id IS ?2",
+            params![workspace_id, parent_id],
+            |row| row.get(0),
+        )
+    }
+}
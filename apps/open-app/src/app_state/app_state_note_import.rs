@@ -0,0 +1,213 @@
+//! 笔记导入来源模块
+//!
+//! 记录每个导入的源文件对应的笔记或附件，重复导入同一来源时据此更新已有记录。
+//! 附件导入为 imported_files，并通过 note_attachments 关联到引用它的笔记。
+
+use chrono::Utc;
+use rusqlite::{Result as SqliteResult, Row, params};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::app_state::{DatabaseManager, ImportedFile};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportSourceType {
+    /// Obsidian 仓库或普通 Markdown 目录
+    Obsidian,
+    /// Notion 导出的 Markdown & CSV 压缩包
+    Notion,
+}
+
+impl ImportSourceType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ImportSourceType::Obsidian => "obsidian",
+            ImportSourceType::Notion => "notion",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "obsidian" => Some(ImportSourceType::Obsidian),
+            "notion" => Some(ImportSourceType::Notion),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportTargetType {
+    Note,
+    /// 由目录生成的父笔记
+    Folder,
+    Attachment,
+}
+
+impl ImportTargetType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ImportTargetType::Note => "note",
+            ImportTargetType::Folder => "folder",
+            ImportTargetType::Attachment => "attachment",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "note" => Some(ImportTargetType::Note),
+            "folder" => Some(ImportTargetType::Folder),
+            "attachment" => Some(ImportTargetType::Attachment),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSource {
+    pub workspace_id: String,
+    /// 源文件或目录的绝对路径，Notion 导入为 `notion:` 开头的页面标识
+    pub source_path: String,
+    pub source_type: ImportSourceType,
+    pub target_type: ImportTargetType,
+    /// 笔记 ID 或导入文件 ID
+    pub target_id: String,
+    pub content_hash: String,
+    pub imported_at: i64,
+}
+
+impl ImportSource {
+    pub fn new(
+        workspace_id: String,
+        source_path: String,
+        source_type: ImportSourceType,
+        target_type: ImportTargetType,
+        target_id: String,
+        content_hash: String,
+    ) -> Self {
+        Self {
+            workspace_id,
+            source_path,
+            source_type,
+            target_type,
+            target_id,
+            content_hash,
+            imported_at: Utc::now().timestamp_millis(),
+        }
+    }
+}
+
+fn row_to_import_source(row: &Row) -> SqliteResult<ImportSource> {
+    let source_type_str: String = row.get(2)?;
+    let target_type_str: String = row.get(3)?;
+    Ok(ImportSource {
+        workspace_id: row.get(0)?,
+        source_path: row.get(1)?,
+        source_type: ImportSourceType::parse(&source_type_str)
+            .unwrap_or(ImportSourceType::Obsidian),
+        target_type: ImportTargetType::parse(&target_type_str).unwrap_or(ImportTargetType::Note),
+        target_id: row.get(4)?,
+        content_hash: row.get(5)?,
+        imported_at: row.get(6)?,
+    })
+}
+
+impl DatabaseManager {
+    pub fn get_import_source(
+        &self,
+        workspace_id: &str,
+        source_path: &str,
+    ) -> SqliteResult<Option<ImportSource>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT workspace_id, source_path, source_type, target_type, target_id, content_hash, imported_at
+             FROM note_import_sources WHERE workspace_id = ?1 AND source_path = ?2",
+        )?;
+
+        let mut rows = stmt.query(params![workspace_id, source_path])?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(row_to_import_source(row)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn save_import_source(&self, source: &ImportSource) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO note_import_sources (workspace_id, source_path, source_type, target_type, target_id, content_hash, imported_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                source.workspace_id,
+                source.source_path,
+                source.source_type.as_str(),
+                source.target_type.as_str(),
+                source.target_id,
+                source.content_hash,
+                source.imported_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 用新的附件列表替换笔记原有的附件关联
+    pub fn replace_note_attachments(&self, note_id: &str, file_ids: &[String]) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let now = Utc::now().timestamp_millis();
+
+        tx.execute(
+            "DELETE FROM note_attachments WHERE note_id = ?1",
+            params![note_id],
+        )?;
+        for file_id in file_ids {
+            tx.execute(
+                "INSERT OR IGNORE INTO note_attachments (note_id, file_id, created_at) VALUES (?1, ?2, ?3)",
+                params![note_id, file_id, now],
+            )?;
+        }
+
+        tx.commit()
+    }
+
+    pub fn list_note_attachments(&self, note_id: &str) -> SqliteResult<Vec<ImportedFile>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT f.id, f.workspace_id, f.name, f.original_path, f.stored_path, f.file_type, f.size_bytes, f.mime_type, f.created_at, f.updated_at
+             FROM note_attachments a
+             JOIN imported_files f ON f.id = a.file_id
+             WHERE a.note_id = ?1
+             ORDER BY f.name",
+        )?;
+
+        let rows = stmt.query_map(params![note_id], |row| {
+            Ok(ImportedFile {
+                id: row.get(0)?,
+                workspace_id: row.get(1)?,
+                parent_directory_id: None,
+                name: row.get(2)?,
+                original_path: PathBuf::from(row.get::<_, String>(3)?),
+                stored_path: PathBuf::from(row.get::<_, String>(4)?),
+                file_type: row.get(5)?,
+                size_bytes: row.get(6)?,
+                mime_type: row.get(7)?,
+                checksum: None,
+                is_archived: false,
+                created_at: row.get(8)?,
+                updated_at: row.get(9)?,
+            })
+        })?;
+
+        let mut files = Vec::new();
+        for file in rows {
+            files.push(file?);
+        }
+        Ok(files)
+    }
+}
@@ -0,0 +1,279 @@
+//! 笔记分析结果模块
+//!
+//! note_insights 保存后台计算的大纲、字数和摘要，并回写到 notes 的 word_count 和
+//! summary 列（不修改 updated_at）。LLM 摘要只在生成时的内容哈希与当前一致时生效，
+//! 否则 summary 使用抽取式摘要。
+
+use chrono::Utc;
+use rusqlite::{Result as SqliteResult, Row, params};
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::{DatabaseManager, Note, row_to_note};
+
+/// 大纲中的一个标题
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteOutlineItem {
+    pub level: u8,
+    pub text: String,
+    /// 同一篇笔记内唯一的锚点
+    pub anchor: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteInsight {
+    pub note_id: String,
+    pub outline: Vec<NoteOutlineItem>,
+    pub word_count: i32,
+    pub extractive_summary: String,
+    pub content_hash: String,
+    /// 计算时笔记的 updated_at
+    pub source_updated_at: i64,
+    pub llm_summary: Option<String>,
+    pub llm_model: Option<String>,
+    /// 生成 LLM 摘要时的内容哈希，与 content_hash 不同说明摘要已过期
+    pub llm_content_hash: Option<String>,
+    pub llm_generated_at: Option<i64>,
+    pub computed_at: i64,
+}
+
+fn row_to_note_insight(row: &Row) -> SqliteResult<NoteInsight> {
+    let outline_json: String = row.get(1)?;
+    Ok(NoteInsight {
+        note_id: row.get(0)?,
+        outline: serde_json::from_str(&outline_json).unwrap_or_default(),
+        word_count: row.get(2)?,
+        extractive_summary: row.get(3)?,
+        content_hash: row.get(4)?,
+        source_updated_at: row.get(5)?,
+        llm_summary: row.get(6)?,
+        llm_model: row.get(7)?,
+        llm_content_hash: row.get(8)?,
+        llm_generated_at: row.get(9)?,
+        computed_at: row.get(10)?,
+    })
+}
+
+/// 将当前有效的摘要和字数回写到 notes
+const SYNC_NOTE_SUMMARY_SQL: &str = "UPDATE notes SET
+        word_count = (SELECT word_count FROM note_insights WHERE note_id = ?1),
+        summary = (SELECT CASE WHEN llm_summary IS NOT NULL AND llm_content_hash = content_hash
+                          THEN llm_summary ELSE extractive_summary END
+                   FROM note_insights WHERE note_id = ?1)
+     WHERE id = ?1";
+
+impl DatabaseManager {
+    pub fn get_note_insight(&self, note_id: &str) -> SqliteResult<Option<NoteInsight>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT note_id, outline, word_count, extractive_summary, content_hash, source_updated_at,
+                llm_summary, llm_model, llm_content_hash, llm_generated_at, computed_at
+             FROM note_insights WHERE note_id = ?1",
+        )?;
+        let mut rows = stmt.query(params![note_id])?;
+        match rows.next()? {
+            Some(row) => Ok(Some(row_to_note_insight(row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 保存抽取式分析结果，保留已有的 LLM 摘要
+    pub fn save_note_insight(&self, insight: &NoteInsight) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO note_insights (note_id, outline, word_count, extractive_summary, content_hash,
+                source_updated_at, computed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(note_id) DO UPDATE SET
+                outline = excluded.outline,
+                word_count = excluded.word_count,
+                extractive_summary = excluded.extractive_summary,
+                content_hash = excluded.content_hash,
+                source_updated_at = excluded.source_updated_at,
+                computed_at = excluded.computed_at",
+            params![
+                insight.note_id,
+                serde_json::to_string(&insight.outline).unwrap(),
+                insight.word_count,
+                insight.extractive_summary,
+                insight.content_hash,
+                insight.source_updated_at,
+                insight.computed_at,
+            ],
+        )?;
+        tx.execute(SYNC_NOTE_SUMMARY_SQL, params![insight.note_id])?;
+        tx.commit()
+    }
+
+    /// 还没有分析结果或分析后又被修改过的笔记
+    pub fn list_notes_with_stale_insight(&self, limit: usize) -> SqliteResult<Vec<Note>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT n.id, n.workspace_id, n.parent_id, n.title, n.note_type, n.content, n.summary, n.file_path, n.tags, n.word_count, n.sort_order, n.is_favorited, n.is_pinned, n.is_archived, n.last_viewed_at, n.created_at, n.updated_at
+             FROM notes n LEFT JOIN note_insights i ON i.note_id = n.id
+             WHERE i.note_id IS NULL OR i.source_updated_at IS NOT n.updated_at
+             ORDER BY n.updated_at DESC
+             LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit as i64], row_to_note)?;
+        rows.collect()
+    }
+
+    /// 需要生成 LLM 摘要的笔记 (note_id, content_hash)，`attempted_before` 之后尝试过的跳过
+    pub fn list_llm_summary_candidates(
+        &self,
+        min_words: i32,
+        attempted_before: i64,
+        limit: usize,
+    ) -> SqliteResult<Vec<(String, String)>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT note_id, content_hash FROM note_insights
+             WHERE llm_content_hash IS NOT content_hash
+               AND word_count >= ?1
+               AND (llm_generated_at IS NULL OR llm_generated_at < ?2)
+             ORDER BY source_updated_at DESC
+             LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(params![min_words, attempted_before, limit as i64], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+        rows.collect()
+    }
+
+    /// 保存 LLM 摘要，内容在生成期间被修改时只记录尝试时间，返回摘要是否生效
+    pub fn save_note_llm_summary(
+        &self,
+        note_id: &str,
+        content_hash: &str,
+        summary: &str,
+        model: &str,
+    ) -> SqliteResult<bool> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let now = Utc::now().timestamp_millis();
+        let updated = tx.execute(
+            "UPDATE note_insights
+             SET llm_summary = ?1, llm_model = ?2, llm_content_hash = ?3, llm_generated_at = ?4
+             WHERE note_id = ?5 AND content_hash = ?3",
+            params![summary, model, content_hash, now, note_id],
+        )?;
+        if updated == 0 {
+            tx.execute(
+                "UPDATE note_insights SET llm_generated_at = ?1 WHERE note_id = ?2",
+                params![now, note_id],
+            )?;
+        } else {
+            tx.execute(SYNC_NOTE_SUMMARY_SQL, params![note_id])?;
+        }
+        tx.commit()?;
+        Ok(updated > 0)
+    }
+
+    /// 记录失败的 LLM 摘要请求，避免每次后台扫描都重试
+    pub fn mark_note_llm_attempt(&self, note_id: &str) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        conn.execute(
+            "UPDATE note_insights SET llm_generated_at = ?1 WHERE note_id = ?2",
+            params![Utc::now().timestamp_millis(), note_id],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::{NoteType, Workspace};
+    use std::env;
+    use std::path::PathBuf;
+
+    fn insight_for(note: &Note, hash: &str, summary: &str) -> NoteInsight {
+        NoteInsight {
+            note_id: note.id.clone(),
+            outline: Vec::new(),
+            word_count: 200,
+            extractive_summary: summary.to_string(),
+            content_hash: hash.to_string(),
+            source_updated_at: note.updated_at,
+            llm_summary: None,
+            llm_model: None,
+            llm_content_hash: None,
+            llm_generated_at: None,
+            computed_at: Utc::now().timestamp_millis(),
+        }
+    }
+
+    #[test]
+    fn test_note_insight_summary_selection() {
+        let test_db_path =
+            env::temp_dir().join(format!("test_note_insight_{}.db", uuid::Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+
+        let note = Note::new(
+            workspace.id.clone(),
+            "Draft".to_string(),
+            NoteType::Markdown,
+            "v1".to_string(),
+            PathBuf::from("/tmp/draft.md"),
+        );
+        db.create_note(&note).unwrap();
+        assert_eq!(db.list_notes_with_stale_insight(10).unwrap().len(), 1);
+
+        db.save_note_insight(&insight_for(&note, "h1", "first"))
+            .unwrap();
+        assert!(db.list_notes_with_stale_insight(10).unwrap().is_empty());
+        let stored = db.get_note(&note.id).unwrap().unwrap();
+        assert_eq!(stored.summary.as_deref(), Some("first"));
+        assert_eq!(stored.word_count, 200);
+        assert_eq!(stored.updated_at, note.updated_at);
+
+        let candidates = db.list_llm_summary_candidates(100, i64::MAX, 10).unwrap();
+        assert_eq!(candidates, [(note.id.clone(), "h1".to_string())]);
+        // 生成期间内容已变化的摘要不生效
+        assert!(
+            !db.save_note_llm_summary(&note.id, "old", "stale", "m")
+                .unwrap()
+        );
+        assert!(
+            db.save_note_llm_summary(&note.id, "h1", "llm", "m")
+                .unwrap()
+        );
+        let stored = db.get_note(&note.id).unwrap().unwrap();
+        assert_eq!(stored.summary.as_deref(), Some("llm"));
+        assert!(
+            db.list_llm_summary_candidates(100, i64::MAX, 10)
+                .unwrap()
+                .is_empty()
+        );
+
+        // 内容变化后回退到抽取式摘要，LLM 摘要重新进入候选
+        db.save_note_insight(&insight_for(&note, "h2", "second"))
+            .unwrap();
+        let stored = db.get_note(&note.id).unwrap().unwrap();
+        assert_eq!(stored.summary.as_deref(), Some("second"));
+        let insight = db.get_note_insight(&note.id).unwrap().unwrap();
+        assert_eq!(insight.llm_summary.as_deref(), Some("llm"));
+        assert_eq!(
+            db.list_llm_summary_candidates(100, i64::MAX, 10)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(
+            db.list_llm_summary_candidates(100, 0, 10)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! 笔记双链管理模块
+//!
+//! 保存笔记正文中解析出的 `[[标题]]` 链接。目标按标题（大小写不敏感）在同一
+//! 工作空间内解析，找不到时 target_id 为空，目标笔记创建后再补全。
+
+use chrono::Utc;
+use rusqlite::{Result as SqliteResult, Row, params};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::app_state::DatabaseManager;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteLink {
+    pub id: String,
+    pub source_id: String,
+    pub workspace_id: String,
+    pub target_title: String,
+    pub target_id: Option<String>,
+    /// `[[标题|别名]]` 中的显示文本
+    pub alias: Option<String>,
+    /// `[[标题#小节]]` 中的小节
+    pub heading: Option<String>,
+    /// 链接所在的行，用于展示反向链接上下文
+    pub context: String,
+    pub created_at: i64,
+}
+
+impl NoteLink {
+    pub fn new(source_id: String, workspace_id: String, target_title: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            source_id,
+            workspace_id,
+            target_title,
+            target_id: None,
+            alias: None,
+            heading: None,
+            context: String::new(),
+            created_at: Utc::now().timestamp_millis(),
+        }
+    }
+}
+
+/// 指向某篇笔记的链接及其来源笔记
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteBacklink {
+    pub source_title: String,
+    pub link: NoteLink,
+}
+
+fn row_to_link(row: &Row) -> SqliteResult<NoteLink> {
+    Ok(NoteLink {
+        id: row.get(0)?,
+        source_id: row.get(1)?,
+        workspace_id: row.get(2)?,
+        target_title: row.get(3)?,
+        target_id: row.get(4)?,
+        alias: row.get(5)?,
+        heading: row.get(6)?,
+        context: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+fn row_to_backlink(row: &Row) -> SqliteResult<NoteBacklink> {
+    Ok(NoteBacklink {
+        link: row_to_link(row)?,
+        source_title: row.get(9)?,
+    })
+}
+
+impl DatabaseManager {
+    /// 用新解析的链接替换来源笔记原有的链接
+    pub fn replace_note_links(&self, source_id: &str, links: &[NoteLink]) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+
+        tx.execute(
+            "DELETE FROM note_links WHERE source_id = ?1",
+            params![source_id],
+        )?;
+        for link in links {
+            tx.execute(
+                "INSERT INTO note_links (id, source_id, workspace_id, target_title, target_id, alias, heading, context, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    link.id,
+                    link.source_id,
+                    link.workspace_id,
+                    link.target_title,
+                    link.target_id,
+                    link.alias,
+                    link.heading,
+                    link.context,
+                    link.created_at,
+                ],
+            )?;
+        }
+
+        tx.commit()
+    }
+
+    /// 按标题查找笔记，重名时取最近更新的一篇
+    pub fn find_note_id_by_title(
+        &self,
+        workspace_id: &str,
+        title: &str,
+    ) -> SqliteResult<Option<String>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id FROM notes WHERE workspace_id = ?1 AND title = ?2 COLLATE NOCASE
+             ORDER BY updated_at DESC LIMIT 1",
+        )?;
+
+        let mut rows = stmt.query(params![workspace_id, title])?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(row.get(0)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// 将指向该标题的未解析链接关联到笔记，返回更新的链接数
+    pub fn resolve_note_links(
+        &self,
+        workspace_id: &str,
+        title: &str,
+        note_id: &str,
+    ) -> SqliteResult<usize> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        conn.execute(
+            "UPDATE note_links SET target_id = ?1
+             WHERE workspace_id = ?2 AND target_id IS NULL AND target_title = ?3 COLLATE NOCASE",
+            params![note_id, workspace_id, title],
+        )
+    }
+
+    pub fn list_note_links(&self, source_id: &str) -> SqliteResult<Vec<NoteLink>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, source_id, workspace_id, target_title, target_id, alias, heading, context, created_at
+             FROM note_links WHERE source_id = ?1 ORDER BY rowid",
+        )?;
+
+        let rows = stmt.query_map(params![source_id], row_to_link)?;
+        let mut links = Vec::new();
+        for link in rows {
+            links.push(link?);
+        }
+        Ok(links)
+    }
+
+    /// 列出链接到该笔记的其他笔记
+    pub fn list_backlinks(&self, note_id: &str) -> SqliteResult<Vec<NoteBacklink>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT l.id, l.source_id, l.workspace_id, l.target_title, l.target_id, l.alias, l.heading, l.context, l.created_at, n.title
+             FROM note_links l
+             JOIN notes n ON n.id = l.source_id
+             WHERE l.target_id = ?1
+             ORDER BY n.updated_at DESC",
+        )?;
+
+        let rows = stmt.query_map(params![note_id], row_to_backlink)?;
+        let mut backlinks = Vec::new();
+        for backlink in rows {
+            backlinks.push(backlink?);
+        }
+        Ok(backlinks)
+    }
+
+    /// 列出工作空间内目标笔记不存在的链接
+    pub fn list_unresolved_links(&self, workspace_id: &str) -> SqliteResult<Vec<NoteBacklink>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT l.id, l.source_id, l.workspace_id, l.target_title, l.target_id, l.alias, l.heading, l.context, l.created_at, n.title
+             FROM note_links l
+             JOIN notes n ON n.id = l.source_id
+             WHERE l.workspace_id = ?1 AND l.target_id IS NULL
+             ORDER BY l.target_title COLLATE NOCASE, n.title",
+        )?;
+
+        let rows = stmt.query_map(params![workspace_id], row_to_backlink)?;
+        let mut links = Vec::new();
+        for link in rows {
+            links.push(link?);
+        }
+        Ok(links)
+    }
+}
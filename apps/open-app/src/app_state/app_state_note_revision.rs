@@ -0,0 +1,274 @@
+//! 笔记修订历史模块
+//!
+//! 每次保存笔记时记录快照，间隔内的连续保存合并到同一个版本，
+//! 超出保留数量的旧版本会被清理。最早的版本不参与合并，保证笔记的原始内容可以找回。
+
+use chrono::Utc;
+use rusqlite::{Connection, Result as SqliteResult, Row, params};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::app_state::{DatabaseManager, Note};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteRevision {
+    pub id: String,
+    pub note_id: String,
+    pub title: String,
+    pub content: String,
+    pub created_at: i64,
+    /// 合并保存时更新，等于最后一次写入的时间
+    pub updated_at: i64,
+}
+
+impl NoteRevision {
+    pub fn new(note: &Note) -> Self {
+        let now = Utc::now().timestamp_millis();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            note_id: note.id.clone(),
+            title: note.title.clone(),
+            content: note.content.clone(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+fn row_to_revision(row: &Row) -> SqliteResult<NoteRevision> {
+    Ok(NoteRevision {
+        id: row.get(0)?,
+        note_id: row.get(1)?,
+        title: row.get(2)?,
+        content: row.get(3)?,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+/// 为还没有修订版本的笔记保存当前内容作为第一个版本
+///
+/// 覆盖升级前创建的笔记，以及没有经过保存流程写入的笔记，使首次编辑前的内容可以恢复。
+pub(crate) fn migrate_note_revisions(conn: &Connection) -> SqliteResult<()> {
+    let tx = conn.unchecked_transaction()?;
+    let notes: Vec<(String, String, String, i64)> = {
+        let mut stmt = tx.prepare(
+            "SELECT n.id, n.title, n.content, n.updated_at FROM notes n
+             WHERE NOT EXISTS (SELECT 1 FROM note_revisions r WHERE r.note_id = n.id)",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?;
+        rows.collect::<SqliteResult<_>>()?
+    };
+    for (note_id, title, content, updated_at) in notes {
+        tx.execute(
+            "INSERT INTO note_revisions (id, note_id, title, content, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+            params![
+                Uuid::new_v4().to_string(),
+                note_id,
+                title,
+                content,
+                updated_at
+            ],
+        )?;
+    }
+    tx.commit()
+}
+
+impl DatabaseManager {
+    /// 记录笔记当前内容的快照
+    ///
+    /// 内容未变化时不生成版本；距最新版本创建不足 `coalesce_ms` 且该版本不是最早的版本时
+    /// 覆盖该版本。
+    /// `max_revisions` 大于 0 时只保留最新的若干个版本。
+    pub fn save_note_revision(
+        &self,
+        note: &Note,
+        coalesce_ms: i64,
+        max_revisions: usize,
+    ) -> SqliteResult<NoteRevision> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let now = Utc::now().timestamp_millis();
+
+        let latest = {
+            let mut stmt = conn.prepare(
+                "SELECT id, note_id, title, content, created_at, updated_at
+                 FROM note_revisions WHERE note_id = ?1
+                 ORDER BY created_at DESC, rowid DESC LIMIT 1",
+            )?;
+            let mut rows = stmt.query(params![note.id])?;
+            match rows.next()? {
+                Some(row) => Some(row_to_revision(row)?),
+                None => None,
+            }
+        };
+
+        let revision_count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM note_revisions WHERE note_id = ?1",
+            params![note.id],
+            |row| row.get(0),
+        )?;
+
+        let revision = match latest {
+            Some(latest) if latest.title == note.title && latest.content == note.content => {
+                return Ok(latest);
+            }
+            Some(mut latest)
+                if coalesce_ms > 0
+                    && revision_count > 1
+                    && now - latest.created_at < coalesce_ms =>
+            {
+                conn.execute(
+                    "UPDATE note_revisions SET title = ?1, content = ?2, updated_at = ?3 WHERE id = ?4",
+                    params![note.title, note.content, now, latest.id],
+                )?;
+                latest.title = note.title.clone();
+                latest.content = note.content.clone();
+                latest.updated_at = now;
+                latest
+            }
+            _ => {
+                let revision = NoteRevision::new(note);
+                conn.execute(
+                    "INSERT INTO note_revisions (id, note_id, title, content, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        revision.id,
+                        revision.note_id,
+                        revision.title,
+                        revision.content,
+                        revision.created_at,
+                        revision.updated_at,
+                    ],
+                )?;
+                revision
+            }
+        };
+
+        if max_revisions > 0 {
+            conn.execute(
+                "DELETE FROM note_revisions WHERE note_id = ?1 AND id NOT IN (
+                    SELECT id FROM note_revisions WHERE note_id = ?1
+                    ORDER BY created_at DESC, rowid DESC LIMIT ?2
+                 )",
+                params![note.id, max_revisions as i64],
+            )?;
+        }
+
+        Ok(revision)
+    }
+
+    pub fn get_note_revision(&self, id: &str) -> SqliteResult<Option<NoteRevision>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, note_id, title, content, created_at, updated_at
+             FROM note_revisions WHERE id = ?1",
+        )?;
+
+        let mut rows = stmt.query(params![id])?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(row_to_revision(row)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// 按时间倒序列出笔记的修订版本
+    pub fn list_note_revisions(&self, note_id: &str) -> SqliteResult<Vec<NoteRevision>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, note_id, title, content, created_at, updated_at
+             FROM note_revisions WHERE note_id = ?1
+             ORDER BY created_at DESC, rowid DESC",
+        )?;
+
+        let rows = stmt.query_map(params![note_id], row_to_revision)?;
+        let mut revisions = Vec::new();
+        for revision in rows {
+            revisions.push(revision?);
+        }
+        Ok(revisions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::{NoteType, Workspace};
+    use std::env;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_note_revisions_coalesce_and_retention() {
+        let test_db_path =
+            env::temp_dir().join(format!("test_note_revision_{}.db", Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+
+        let mut note = Note::new(
+            workspace.id.clone(),
+            "Draft".to_string(),
+            NoteType::Markdown,
+            "v1".to_string(),
+            PathBuf::from("/tmp/draft.md"),
+        );
+        db.create_note(&note).unwrap();
+        let first = db.save_note_revision(&note, 60_000, 3).unwrap();
+
+        // 最早的版本不参与合并，之后间隔内的保存合并到同一版本，内容不变时不生成版本
+        note.content = "v2".to_string();
+        let second = db.save_note_revision(&note, 60_000, 3).unwrap();
+        assert_ne!(second.id, first.id);
+        note.content = "v3".to_string();
+        let merged = db.save_note_revision(&note, 60_000, 3).unwrap();
+        assert_eq!(merged.id, second.id);
+        db.save_note_revision(&note, 0, 3).unwrap();
+        let revisions = db.list_note_revisions(&note.id).unwrap();
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[1].content, "v1");
+
+        for i in 4..=6 {
+            note.content = format!("v{}", i);
+            db.save_note_revision(&note, 0, 3).unwrap();
+        }
+        let revisions = db.list_note_revisions(&note.id).unwrap();
+        assert_eq!(revisions.len(), 3);
+        assert_eq!(revisions[0].content, "v6");
+        assert!(db.get_note_revision(&first.id).unwrap().is_none());
+
+        db.delete_note(&note.id).unwrap();
+        assert!(db.list_note_revisions(&note.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_migrate_note_revisions_snapshots_existing_notes() {
+        let test_db_path =
+            env::temp_dir().join(format!("test_note_revision_{}.db", Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+
+        let note = Note::new(
+            workspace.id.clone(),
+            "Legacy".to_string(),
+            NoteType::Markdown,
+            "original".to_string(),
+            PathBuf::from("/tmp/legacy.md"),
+        );
+        db.create_note(&note).unwrap();
+        assert!(db.list_note_revisions(&note.id).unwrap().is_empty());
+
+        migrate_note_revisions(&db.conn().lock().unwrap()).unwrap();
+        migrate_note_revisions(&db.conn().lock().unwrap()).unwrap();
+        let revisions = db.list_note_revisions(&note.id).unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].content, "original");
+    }
+}
@@ -0,0 +1,372 @@
+//! 笔记全文检索模块
+//!
+//! 基于 notes_fts（FTS5 + trigram 分词）按 BM25 排序并返回高亮片段。
+//! trigram 无法匹配少于 3 个字符的词（如两个汉字的“异步”），这类词改用 LIKE 过滤。
+
+use rusqlite::{Result as SqliteResult, params_from_iter};
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::{DatabaseManager, Note, row_to_note};
+
+const HIGHLIGHT_START: &str = "<mark>";
+const HIGHLIGHT_END: &str = "</mark>";
+/// trigram 分词可检索的最短词长（字符数）
+const MIN_TRIGRAM_CHARS: usize = 3;
+/// LIKE 回退时片段在命中位置前后保留的字符数
+const SNIPPET_CONTEXT_CHARS: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteSearchResult {
+    pub note: Note,
+    /// 带 `<mark>` 高亮的标题
+    pub title_highlight: String,
+    /// 带 `<mark>` 高亮的正文片段
+    pub snippet: String,
+    /// BM25 得分，越小越相关；仅 LIKE 匹配时为 0
+    pub score: f64,
+}
+
+/// 解析检索语句：空白分隔的词，双引号包裹短语，词尾 `*` 表示前缀
+///
+/// trigram 本身按子串匹配，前缀查询与普通词等价，因此只去掉 `*`。
+pub fn parse_note_query(query: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut current = String::new();
+    let mut in_phrase = false;
+
+    let mut flush = |current: &mut String| {
+        let term = current.trim().trim_end_matches('*').trim().to_string();
+        if !term.is_empty() {
+            terms.push(term);
+        }
+        current.clear();
+    };
+
+    for c in query.chars() {
+        match c {
+            '"' => {
+                flush(&mut current);
+                in_phrase = !in_phrase;
+            }
+            c if c.is_whitespace() && !in_phrase => flush(&mut current),
+            c => current.push(c),
+        }
+    }
+    flush(&mut current);
+    terms
+}
+
+fn fts_phrase(term: &str) -> String {
+    format!("\"{}\"", term.replace('"', "\"\""))
+}
+
+fn like_pattern(term: &str) -> String {
+    let escaped = term
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// 在文本中为命中的词加上高亮标记（ASCII 忽略大小写）
+fn highlight_terms(text: &str, terms: &[String]) -> String {
+    let lower = text.to_ascii_lowercase();
+    let needles: Vec<String> = terms.iter().map(|t| t.to_ascii_lowercase()).collect();
+    let mut out = String::with_capacity(text.len());
+    let mut pos = 0;
+
+    while pos < text.len() {
+        let found = needles
+            .iter()
+            .filter(|n| !n.is_empty() && lower[pos..].starts_with(n.as_str()))
+            .map(|n| n.len())
+            .max();
+        match found {
+            Some(len) => {
+                out.push_str(HIGHLIGHT_START);
+                out.push_str(&text[pos..pos + len]);
+                out.push_str(HIGHLIGHT_END);
+                pos += len;
+            }
+            None => {
+                let ch = text[pos..].chars().next().unwrap();
+                out.push(ch);
+                pos += ch.len_utf8();
+            }
+        }
+    }
+    out
+}
+
+/// 截取第一个命中位置附近的正文作为片段
+fn like_snippet(content: &str, terms: &[String]) -> String {
+    let lower = content.to_ascii_lowercase();
+    let hit = terms
+        .iter()
+        .filter_map(|t| lower.find(&t.to_ascii_lowercase()))
+        .min()
+        .unwrap_or(0);
+
+    let before: Vec<(usize, char)> = content[..hit].char_indices().collect();
+    let start = before
+        .len()
+        .checked_sub(SNIPPET_CONTEXT_CHARS)
+        .map(|i| before[i].0)
+        .unwrap_or(0);
+    let end = content[hit..]
+        .char_indices()
+        .nth(SNIPPET_CONTEXT_CHARS * 2)
+        .map(|(i, _)| hit + i)
+        .unwrap_or(content.len());
+
+    let mut snippet = highlight_terms(&content[start..end], terms);
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if end < content.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+impl DatabaseManager {
+    /// 全文检索笔记，按 BM25 排序（标题权重高于正文）
+    pub fn search_notes(
+        &self,
+        workspace_id: &str,
+        query: &str,
+        limit: usize,
+    ) -> SqliteResult<Vec<NoteSearchResult>> {
+        let terms = parse_note_query(query);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (fts_terms, like_terms): (Vec<&String>, Vec<&String>) = terms
+            .iter()
+            .partition(|t| t.chars().count() >= MIN_TRIGRAM_CHARS);
+
+        let mut values = vec![workspace_id.to_string()];
+        let mut conditions = Vec::new();
+        for term in &like_terms {
+            values.push(like_pattern(term));
+            conditions.push(format!(
+                "(n.title LIKE ?{0} ESCAPE '\\' OR n.content LIKE ?{0} ESCAPE '\\')",
+                values.len()
+            ));
+        }
+
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+
+        if fts_terms.is_empty() {
+            let sql = format!(
+                "SELECT n.id, n.workspace_id, n.parent_id, n.title, n.note_type, n.content, n.summary, n.file_path, n.tags, n.word_count, n.sort_order, n.is_favorited, n.is_pinned, n.is_archived, n.last_viewed_at, n.created_at, n.updated_at
+                 FROM notes n
+                 WHERE n.workspace_id = ?1 AND {}
+                 ORDER BY n.updated_at DESC LIMIT {}",
+                conditions.join(" AND "),
+                limit
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params_from_iter(values.iter()), row_to_note)?;
+
+            let mut results = Vec::new();
+            for note in rows {
+                let note = note?;
+                results.push(NoteSearchResult {
+                    title_highlight: highlight_terms(&note.title, &terms),
+                    snippet: like_snippet(&note.content, &terms),
+                    score: 0.0,
+                    note,
+                });
+            }
+            return Ok(results);
+        }
+
+        let match_expr = fts_terms
+            .iter()
+            .map(|t| fts_phrase(t))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        values.push(match_expr);
+        conditions.push(format!("notes_fts MATCH ?{}", values.len()));
+
+        let sql = format!(
+            "SELECT n.id, n.workspace_id, n.parent_id, n.title, n.note_type, n.content, n.summary, n.file_path, n.tags, n.word_count, n.sort_order, n.is_favorited, n.is_pinned, n.is_archived, n.last_viewed_at, n.created_at, n.updated_at,
+                    highlight(notes_fts, 1, '{start}', '{end}'),
+                    snippet(notes_fts, 2, '{start}', '{end}', '…', 32),
+                    bm25(notes_fts, 0.0, 10.0, 1.0) AS score
+             FROM notes_fts
+             JOIN notes n ON n.id = notes_fts.note_id
+             WHERE n.workspace_id = ?1 AND {conditions}
+             ORDER BY score LIMIT {limit}",
+            start = HIGHLIGHT_START,
+            end = HIGHLIGHT_END,
+            conditions = conditions.join(" AND "),
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+            Ok(NoteSearchResult {
+                note: row_to_note(row)?,
+                title_highlight: row.get(17)?,
+                snippet: row.get(18)?,
+                score: row.get(19)?,
+            })
+        })?;
+
+        let mut results = Vec::new();
+        for result in rows {
+            results.push(result?);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::{NoteType, Workspace};
+    use std::env;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_note_query() {
+        assert_eq!(
+            parse_note_query(r#"tokio "async await"  run* 异步"#),
+            vec!["tokio", "async await", "run", "异步"]
+        );
+        assert!(parse_note_query("  \"\" * ").is_empty());
+    }
+
+    #[test]
+    fn test_search_notes_ranking_and_cjk() {
+        let test_db_path =
+            env::temp_dir().join(format!("test_note_search_{}.db", uuid::Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+
+        let mut titled = Note::new(
+            workspace.id.clone(),
+            "Tokio 运行时笔记".to_string(),
+            NoteType::Markdown,
+            "介绍异步任务调度".to_string(),
+            PathBuf::from("/tmp/a.md"),
+        );
+        let body = Note::new(
+            workspace.id.clone(),
+            "杂记".to_string(),
+            NoteType::Markdown,
+            "今天读了 tokio 的源码，运行时的设计很清晰".to_string(),
+            PathBuf::from("/tmp/b.md"),
+        );
+        db.create_note(&titled).unwrap();
+        db.create_note(&body).unwrap();
+
+        let results = db.search_notes(&workspace.id, "tokio", 10).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].note.id, titled.id);
+        assert!(results[1].snippet.contains("<mark>tokio</mark>"));
+
+        let results = db
+            .search_notes(&workspace.id, "\"运行时的设计\"", 10)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].note.id, body.id);
+
+        // 两个汉字的词走 LIKE 回退
+        let results = db.search_notes(&workspace.id, "异步", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].snippet.contains("<mark>异步</mark>"));
+        let results = db.search_notes(&workspace.id, "tokio 异步", 10).unwrap();
+        assert_eq!(results.len(), 1);
+
+        // 更新和删除通过触发器同步到索引
+        titled.content = "改为讲同步代码".to_string();
+        db.update_note(&titled).unwrap();
+        assert!(
+            db.search_notes(&workspace.id, "异步", 10)
+                .unwrap()
+                .is_empty()
+        );
+        db.delete_note(&body.id).unwrap();
+        assert_eq!(
+            db.search_notes(&workspace.id, "tokio", 10).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_legacy_fts_table_is_rebuilt() {
+        let test_db_path =
+            env::temp_dir().join(format!("test_note_search_{}.db", uuid::Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path.clone()).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+        let note = Note::new(
+            workspace.id.clone(),
+            "Legacy".to_string(),
+            NoteType::Markdown,
+            "written before the upgrade".to_string(),
+            PathBuf::from("/tmp/legacy.md"),
+        );
+        db.create_note(&note).unwrap();
+        // 模拟旧版本的独立 FTS 表
+        db.conn()
+            .lock()
+            .unwrap()
+            .execute_batch(
+                "DROP TRIGGER notes_fts_insert;
+                 DROP TRIGGER notes_fts_delete;
+                 DROP TRIGGER notes_fts_update;
+                 DROP TABLE notes_fts;
+                 CREATE VIRTUAL TABLE notes_fts USING fts5(
+                     note_id UNINDEXED, workspace_id UNINDEXED, title, content, tokenize = 'trigram'
+                 );",
+            )
+            .unwrap();
+        drop(db);
+
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let results = db.search_notes(&workspace.id, "upgrade", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].note.id, note.id);
+    }
+
+    #[test]
+    fn test_search_survives_vacuum() {
+        let test_db_path =
+            env::temp_dir().join(format!("test_note_search_{}.db", uuid::Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+        let notes: Vec<Note> = ["first draft", "second thoughts", "third option"]
+            .into_iter()
+            .map(|content| {
+                let note = Note::new(
+                    workspace.id.clone(),
+                    content.to_string(),
+                    NoteType::Markdown,
+                    content.to_string(),
+                    PathBuf::from(format!("/tmp/{}.md", content)),
+                );
+                db.create_note(&note).unwrap();
+                note
+            })
+            .collect();
+
+        // VACUUM 可能重新编号 notes 的隐式 rowid
+        db.delete_note(&notes[0].id).unwrap();
+        db.conn().lock().unwrap().execute_batch("VACUUM").unwrap();
+
+        let results = db.search_notes(&workspace.id, "thoughts", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].note.id, notes[1].id);
+        assert!(
+            db.search_notes(&workspace.id, "draft", 10)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
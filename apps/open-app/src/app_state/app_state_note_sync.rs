@@ -0,0 +1,167 @@
+//! 笔记文件同步状态模块
+//!
+//! 记录每篇笔记最近一次与 Markdown 文件同步时两侧内容的哈希。
+//! 下次同步时分别与当前哈希比较，即可判断是笔记、文件还是两侧都发生了修改。
+
+use chrono::Utc;
+use rusqlite::{Result as SqliteResult, Row, params};
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::DatabaseManager;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteSyncStatus {
+    Synced,
+    /// 笔记和文件都在上次同步后被修改，等待用户选择保留哪一侧
+    Conflict,
+}
+
+impl NoteSyncStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            NoteSyncStatus::Synced => "synced",
+            NoteSyncStatus::Conflict => "conflict",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "synced" => Some(NoteSyncStatus::Synced),
+            "conflict" => Some(NoteSyncStatus::Conflict),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteSyncState {
+    pub note_id: String,
+    pub workspace_id: String,
+    pub file_path: String,
+    /// 上次同步时笔记渲染为 Markdown 后的哈希
+    pub note_hash: String,
+    /// 上次同步时文件内容的哈希
+    pub file_hash: String,
+    pub status: NoteSyncStatus,
+    pub synced_at: i64,
+}
+
+impl NoteSyncState {
+    pub fn new(
+        note_id: String,
+        workspace_id: String,
+        file_path: String,
+        note_hash: String,
+        file_hash: String,
+    ) -> Self {
+        Self {
+            note_id,
+            workspace_id,
+            file_path,
+            note_hash,
+            file_hash,
+            status: NoteSyncStatus::Synced,
+            synced_at: Utc::now().timestamp_millis(),
+        }
+    }
+}
+
+fn row_to_sync_state(row: &Row) -> SqliteResult<NoteSyncState> {
+    let status_str: String = row.get(5)?;
+    Ok(NoteSyncState {
+        note_id: row.get(0)?,
+        workspace_id: row.get(1)?,
+        file_path: row.get(2)?,
+        note_hash: row.get(3)?,
+        file_hash: row.get(4)?,
+        status: NoteSyncStatus::parse(&status_str).unwrap_or(NoteSyncStatus::Synced),
+        synced_at: row.get(6)?,
+    })
+}
+
+impl DatabaseManager {
+    pub fn save_note_sync_state(&self, state: &NoteSyncState) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO note_sync_state (note_id, workspace_id, file_path, note_hash, file_hash, status, synced_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                state.note_id,
+                state.workspace_id,
+                state.file_path,
+                state.note_hash,
+                state.file_hash,
+                state.status.as_str(),
+                state.synced_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_note_sync_state(&self, note_id: &str) -> SqliteResult<Option<NoteSyncState>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT note_id, workspace_id, file_path, note_hash, file_hash, status, synced_at
+             FROM note_sync_state WHERE note_id = ?1",
+        )?;
+
+        let mut rows = stmt.query(params![note_id])?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(row_to_sync_state(row)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// 按文件路径查找同步状态，用于处理文件监听事件
+    pub fn find_note_sync_state_by_path(
+        &self,
+        file_path: &str,
+    ) -> SqliteResult<Option<NoteSyncState>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT note_id, workspace_id, file_path, note_hash, file_hash, status, synced_at
+             FROM note_sync_state WHERE file_path = ?1",
+        )?;
+
+        let mut rows = stmt.query(params![file_path])?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(row_to_sync_state(row)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn list_note_sync_conflicts(&self, workspace_id: &str) -> SqliteResult<Vec<NoteSyncState>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT note_id, workspace_id, file_path, note_hash, file_hash, status, synced_at
+             FROM note_sync_state WHERE workspace_id = ?1 AND status = 'conflict'
+             ORDER BY synced_at DESC",
+        )?;
+
+        let rows = stmt.query_map(params![workspace_id], row_to_sync_state)?;
+        let mut states = Vec::new();
+        for state in rows {
+            states.push(state?);
+        }
+        Ok(states)
+    }
+
+    /// 只更新笔记的文件路径，不修改 updated_at
+    pub fn set_note_file_path(&self, note_id: &str, file_path: &str) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        conn.execute(
+            "UPDATE notes SET file_path = ?1 WHERE id = ?2",
+            params![file_path, note_id],
+        )?;
+        Ok(())
+    }
+}
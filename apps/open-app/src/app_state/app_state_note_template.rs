@@ -0,0 +1,182 @@
+//! 笔记模板模块
+//!
+//! 工作空间内的模板保存在 note_templates 表中；`templates_dir` 下的模板文件由
+//! app_note_template 服务读取，两者使用同一个结构。
+
+use chrono::Utc;
+use rusqlite::{Result as SqliteResult, Row, params};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::app_state::{DatabaseManager, NoteType};
+
+/// 创建笔记时需要用户填写的字段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateField {
+    pub name: String,
+    pub label: Option<String>,
+    pub default_value: Option<String>,
+}
+
+impl TemplateField {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            label: None,
+            default_value: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteTemplate {
+    /// 文件模板为 `file:<文件名>`
+    pub id: String,
+    /// 文件模板为 None，对所有工作空间可用
+    pub workspace_id: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    pub note_type: NoteType,
+    /// 新笔记的标题，可以包含变量
+    pub title: String,
+    pub content: String,
+    pub tags: Vec<String>,
+    pub fields: Vec<TemplateField>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl NoteTemplate {
+    pub fn new(
+        workspace_id: String,
+        name: String,
+        note_type: NoteType,
+        title: String,
+        content: String,
+    ) -> Self {
+        let now = Utc::now().timestamp_millis();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            workspace_id: Some(workspace_id),
+            name,
+            description: None,
+            note_type,
+            title,
+            content,
+            tags: Vec::new(),
+            fields: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+fn row_to_note_template(row: &Row) -> SqliteResult<NoteTemplate> {
+    let note_type_str: String = row.get(4)?;
+    let tags_json: Option<String> = row.get(7)?;
+    let fields_json: Option<String> = row.get(8)?;
+    Ok(NoteTemplate {
+        id: row.get(0)?,
+        workspace_id: row.get(1)?,
+        name: row.get(2)?,
+        description: row.get(3)?,
+        note_type: NoteType::parse(&note_type_str).unwrap_or(NoteType::Markdown),
+        title: row.get(5)?,
+        content: row.get(6)?,
+        tags: tags_json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+        fields: fields_json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    })
+}
+
+impl DatabaseManager {
+    pub fn create_note_template(&self, template: &NoteTemplate) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        conn.execute(
+            "INSERT INTO note_templates (id, workspace_id, name, description, note_type, title,
+                content, tags, fields, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                template.id,
+                template.workspace_id,
+                template.name,
+                template.description,
+                template.note_type.as_str(),
+                template.title,
+                template.content,
+                serde_json::to_string(&template.tags).unwrap(),
+                serde_json::to_string(&template.fields).unwrap(),
+                template.created_at,
+                template.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_note_template(&self, template: &NoteTemplate) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        conn.execute(
+            "UPDATE note_templates SET name = ?1, description = ?2, note_type = ?3, title = ?4,
+                content = ?5, tags = ?6, fields = ?7, updated_at = ?8
+             WHERE id = ?9",
+            params![
+                template.name,
+                template.description,
+                template.note_type.as_str(),
+                template.title,
+                template.content,
+                serde_json::to_string(&template.tags).unwrap(),
+                serde_json::to_string(&template.fields).unwrap(),
+                template.updated_at,
+                template.id,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_note_template(&self, id: &str) -> SqliteResult<Option<NoteTemplate>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, workspace_id, name, description, note_type, title, content, tags, fields,
+                created_at, updated_at
+             FROM note_templates WHERE id = ?1",
+        )?;
+        let mut rows = stmt.query(params![id])?;
+        match rows.next()? {
+            Some(row) => Ok(Some(row_to_note_template(row)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn list_note_templates(&self, workspace_id: &str) -> SqliteResult<Vec<NoteTemplate>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, workspace_id, name, description, note_type, title, content, tags, fields,
+                created_at, updated_at
+             FROM note_templates WHERE workspace_id = ?1
+             ORDER BY name COLLATE NOCASE",
+        )?;
+        let templates = stmt
+            .query_map(params![workspace_id], row_to_note_template)?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(templates)
+    }
+
+    pub fn delete_note_template(&self, id: &str) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        conn.execute("DELETE FROM note_templates WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+}
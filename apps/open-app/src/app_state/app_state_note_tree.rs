@@ -0,0 +1,139 @@
+//! 笔记树管理模块
+//!
+//! 笔记通过 parent_id 组成层级，同级之间按 sort_order 排序。
+//! 移动操作在一个事务中完成，并重新编号新旧两组同级笔记的 sort_order。
+
+use rusqlite::{Connection, Result as SqliteResult, Row, params};
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::{DatabaseManager, NoteType};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteTreeNode {
+    pub id: String,
+    pub parent_id: Option<String>,
+    pub title: String,
+    pub note_type: NoteType,
+    pub sort_order: i32,
+    pub is_favorited: bool,
+    pub is_pinned: bool,
+    pub is_archived: bool,
+    pub updated_at: i64,
+    pub children: Vec<NoteTreeNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteMove {
+    pub note_id: String,
+    /// 为空时移动到根级
+    pub parent_id: Option<String>,
+    /// 在新的同级笔记中的位置，超出范围时放到末尾
+    pub index: usize,
+}
+
+fn row_to_tree_node(row: &Row) -> SqliteResult<NoteTreeNode> {
+    let note_type_str: String = row.get(3)?;
+    Ok(NoteTreeNode {
+        id: row.get(0)?,
+        parent_id: row.get(1)?,
+        title: row.get(2)?,
+        note_type: NoteType::parse(&note_type_str).unwrap_or(NoteType::RichText),
+        sort_order: row.get(4)?,
+        is_favorited: row.get::<_, i32>(5)? != 0,
+        is_pinned: row.get::<_, i32>(6)? != 0,
+        is_archived: row.get::<_, i32>(7)? != 0,
+        updated_at: row.get(8)?,
+        children: Vec::new(),
+    })
+}
+
+/// 按当前顺序列出同级笔记，`exclude` 为正在移动的笔记
+fn sibling_ids(
+    conn: &Connection,
+    workspace_id: &str,
+    parent_id: Option<&str>,
+    exclude: &str,
+) -> SqliteResult<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT id FROM notes
+         WHERE workspace_id = ?1 AND parent_id IS ?2 AND id != ?3
+         ORDER BY sort_order, created_at",
+    )?;
+    let rows = stmt.query_map(params![workspace_id, parent_id, exclude], |row| row.get(0))?;
+    let mut ids = Vec::new();
+    for id in rows {
+        ids.push(id?);
+    }
+    Ok(ids)
+}
+
+fn renumber(conn: &Connection, ids: &[String]) -> SqliteResult<()> {
+    for (order, id) in ids.iter().enumerate() {
+        conn.execute(
+            "UPDATE notes SET sort_order = ?1 WHERE id = ?2",
+            params![order as i32, id],
+        )?;
+    }
+    Ok(())
+}
+
+impl DatabaseManager {
+    /// 工作空间内所有笔记的平铺列表（不含正文），按同级顺序排列
+    pub fn list_note_tree_nodes(&self, workspace_id: &str) -> SqliteResult<Vec<NoteTreeNode>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, parent_id, title, note_type, sort_order, is_favorited, is_pinned, is_archived, updated_at
+             FROM notes WHERE workspace_id = ?1 ORDER BY sort_order, created_at",
+        )?;
+
+        let rows = stmt.query_map(params![workspace_id], row_to_tree_node)?;
+        let mut nodes = Vec::new();
+        for node in rows {
+            nodes.push(node?);
+        }
+        Ok(nodes)
+    }
+
+    /// 在一个事务中依次执行移动，任一步失败时全部回滚
+    ///
+    /// 调用方负责校验目标父节点存在且不会形成环。
+    pub fn apply_note_moves(&self, moves: &[NoteMove]) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+
+        for note_move in moves {
+            let (workspace_id, old_parent): (String, Option<String>) = tx.query_row(
+                "SELECT workspace_id, parent_id FROM notes WHERE id = ?1",
+                params![note_move.note_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+            let new_parent = note_move.parent_id.as_deref();
+
+            let mut siblings = sibling_ids(&tx, &workspace_id, new_parent, &note_move.note_id)?;
+            let index = note_move.index.min(siblings.len());
+            siblings.insert(index, note_move.note_id.clone());
+
+            tx.execute(
+                "UPDATE notes SET parent_id = ?1 WHERE id = ?2",
+                params![new_parent, note_move.note_id],
+            )?;
+            renumber(&tx, &siblings)?;
+
+            if old_parent.as_deref() != new_parent {
+                let old_siblings = sibling_ids(
+                    &tx,
+                    &workspace_id,
+                    old_parent.as_deref(),
+                    &note_move.note_id,
+                )?;
+                renumber(&tx, &old_siblings)?;
+            }
+        }
+
+        tx.commit()
+    }
+}
@@ -1,5 +1,5 @@
 use chrono::Utc;
-use rusqlite::{Result as SqliteResult, params};
+use rusqlite::{Result as SqliteResult, Row, params};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use uuid::Uuid;
@@ -86,6 +86,8 @@ pub struct GitRepository {
     pub symbol_count: i32,
     pub vector_count: i32,
     pub is_archived: bool,
+    /// 子模块所属的父仓库
+    pub parent_id: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -117,6 +119,7 @@ impl GitRepository {
             symbol_count: 0,
             vector_count: 0,
             is_archived: false,
+            parent_id: None,
             created_at: now,
             updated_at: now,
         }
@@ -124,6 +127,37 @@ impl GitRepository {
 }
 
 /// Git repository management operations
+/// 按 `SELECT id, workspace_id, name, remote_url, local_path, branch, default_branch, last_commit_hash,
+/// last_synced_at, clone_status, clone_progress, index_status, indexed_at, file_count, symbol_count,
+/// vector_count, is_archived, created_at, updated_at, parent_id` 的列顺序读取仓库
+fn row_to_git_repository(row: &Row) -> SqliteResult<GitRepository> {
+    let clone_status_str: String = row.get(9)?;
+    let index_status_str: String = row.get(11)?;
+
+    Ok(GitRepository {
+        id: row.get(0)?,
+        workspace_id: row.get(1)?,
+        name: row.get(2)?,
+        remote_url: row.get(3)?,
+        local_path: std::path::PathBuf::from(row.get::<_, String>(4)?),
+        branch: row.get(5)?,
+        default_branch: row.get(6)?,
+        last_commit_hash: row.get(7)?,
+        last_synced_at: row.get(8)?,
+        clone_status: CloneStatus::parse(&clone_status_str).unwrap_or(CloneStatus::Pending),
+        clone_progress: row.get(10)?,
+        index_status: IndexStatus::parse(&index_status_str).unwrap_or(IndexStatus::NotIndexed),
+        indexed_at: row.get(12)?,
+        file_count: row.get(13)?,
+        symbol_count: row.get(14)?,
+        vector_count: row.get(15)?,
+        is_archived: row.get::<_, i32>(16)? != 0,
+        parent_id: row.get(19)?,
+        created_at: row.get(17)?,
+        updated_at: row.get(18)?,
+    })
+}
+
 impl DatabaseManager {
     /// Create Git repository record
     pub fn create_git_repository(&self, repo: &GitRepository) -> SqliteResult<()> {
@@ -131,8 +165,8 @@ impl DatabaseManager {
         let conn = conn_arc.lock().unwrap();
         conn.execute(
             "INSERT INTO git_repositories
-             (id, workspace_id, name, remote_url, local_path, branch, default_branch, last_commit_hash, last_synced_at, clone_status, clone_progress, index_status, indexed_at, file_count, symbol_count, vector_count, is_archived, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
+             (id, workspace_id, name, remote_url, local_path, branch, default_branch, last_commit_hash, last_synced_at, clone_status, clone_progress, index_status, indexed_at, file_count, symbol_count, vector_count, is_archived, created_at, updated_at, parent_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
             params![
                 repo.id,
                 repo.workspace_id,
@@ -153,6 +187,7 @@ impl DatabaseManager {
                 repo.is_archived as i32,
                 repo.created_at,
                 repo.updated_at,
+                repo.parent_id,
             ],
         )?;
         Ok(())
@@ -163,39 +198,14 @@ impl DatabaseManager {
         let conn_arc = self.conn();
         let conn = conn_arc.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, workspace_id, name, remote_url, local_path, branch, default_branch, last_commit_hash, last_synced_at, clone_status, clone_progress, index_status, indexed_at, file_count, symbol_count, vector_count, is_archived, created_at, updated_at
+            "SELECT id, workspace_id, name, remote_url, local_path, branch, default_branch, last_commit_hash, last_synced_at, clone_status, clone_progress, index_status, indexed_at, file_count, symbol_count, vector_count, is_archived, created_at, updated_at, parent_id
              FROM git_repositories WHERE id = ?1",
         )?;
 
         let mut rows = stmt.query(params![id])?;
-        if let Some(row) = rows.next()? {
-            let clone_status_str: String = row.get(9)?;
-            let index_status_str: String = row.get(11)?;
-
-            Ok(Some(GitRepository {
-                id: row.get(0)?,
-                workspace_id: row.get(1)?,
-                name: row.get(2)?,
-                remote_url: row.get(3)?,
-                local_path: std::path::PathBuf::from(row.get::<_, String>(4)?),
-                branch: row.get(5)?,
-                default_branch: row.get(6)?,
-                last_commit_hash: row.get(7)?,
-                last_synced_at: row.get(8)?,
-                clone_status: CloneStatus::parse(&clone_status_str).unwrap_or(CloneStatus::Pending),
-                clone_progress: row.get(10)?,
-                index_status: IndexStatus::parse(&index_status_str)
-                    .unwrap_or(IndexStatus::NotIndexed),
-                indexed_at: row.get(12)?,
-                file_count: row.get(13)?,
-                symbol_count: row.get(14)?,
-                vector_count: row.get(15)?,
-                is_archived: row.get::<_, i32>(16)? != 0,
-                created_at: row.get(17)?,
-                updated_at: row.get(18)?,
-            }))
-        } else {
-            Ok(None)
+        match rows.next()? {
+            Some(row) => Ok(Some(row_to_git_repository(row)?)),
+            None => Ok(None),
         }
     }
 
@@ -204,37 +214,11 @@ impl DatabaseManager {
         let conn_arc = self.conn();
         let conn = conn_arc.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, workspace_id, name, remote_url, local_path, branch, default_branch, last_commit_hash, last_synced_at, clone_status, clone_progress, index_status, indexed_at, file_count, symbol_count, vector_count, is_archived, created_at, updated_at
+            "SELECT id, workspace_id, name, remote_url, local_path, branch, default_branch, last_commit_hash, last_synced_at, clone_status, clone_progress, index_status, indexed_at, file_count, symbol_count, vector_count, is_archived, created_at, updated_at, parent_id
              FROM git_repositories WHERE workspace_id = ?1 ORDER BY updated_at DESC",
         )?;
 
-        let rows = stmt.query_map(params![workspace_id], |row| {
-            let clone_status_str: String = row.get(9)?;
-            let index_status_str: String = row.get(11)?;
-
-            Ok(GitRepository {
-                id: row.get(0)?,
-                workspace_id: row.get(1)?,
-                name: row.get(2)?,
-                remote_url: row.get(3)?,
-                local_path: std::path::PathBuf::from(row.get::<_, String>(4)?),
-                branch: row.get(5)?,
-                default_branch: row.get(6)?,
-                last_commit_hash: row.get(7)?,
-                last_synced_at: row.get(8)?,
-                clone_status: CloneStatus::parse(&clone_status_str).unwrap_or(CloneStatus::Pending),
-                clone_progress: row.get(10)?,
-                index_status: IndexStatus::parse(&index_status_str)
-                    .unwrap_or(IndexStatus::NotIndexed),
-                indexed_at: row.get(12)?,
-                file_count: row.get(13)?,
-                symbol_count: row.get(14)?,
-                vector_count: row.get(15)?,
-                is_archived: row.get::<_, i32>(16)? != 0,
-                created_at: row.get(17)?,
-                updated_at: row.get(18)?,
-            })
-        })?;
+        let rows = stmt.query_map(params![workspace_id], row_to_git_repository)?;
 
         let mut repositories = Vec::new();
         for repo in rows {
@@ -243,6 +227,18 @@ impl DatabaseManager {
         Ok(repositories)
     }
 
+    /// List submodule repositories registered under a parent repository
+    pub fn list_child_repositories(&self, parent_id: &str) -> SqliteResult<Vec<GitRepository>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, workspace_id, name, remote_url, local_path, branch, default_branch, last_commit_hash, last_synced_at, clone_status, clone_progress, index_status, indexed_at, file_count, symbol_count, vector_count, is_archived, created_at, updated_at, parent_id
+             FROM git_repositories WHERE parent_id = ?1 ORDER BY updated_at DESC",
+        )?;
+        let rows = stmt.query_map(params![parent_id], row_to_git_repository)?;
+        rows.collect()
+    }
+
     /// Update Git repository sync status
     pub fn update_git_repository_sync(&self, repo_id: &str, commit_hash: &str) -> SqliteResult<()> {
         let conn_arc = self.conn();
@@ -266,9 +262,16 @@ impl DatabaseManager {
 
         conn.execute(
             "UPDATE git_repositories
-             SET name = ?1, remote_url = ?2, branch = ?3, updated_at = ?4
-             WHERE id = ?5",
-            params![repo.name, repo.remote_url, repo.branch, updated_at, repo.id,],
+             SET name = ?1, remote_url = ?2, branch = ?3, default_branch = ?4, updated_at = ?5
+             WHERE id = ?6",
+            params![
+                repo.name,
+                repo.remote_url,
+                repo.branch,
+                repo.default_branch,
+                updated_at,
+                repo.id,
+            ],
         )?;
         Ok(())
     }
@@ -334,6 +337,48 @@ impl DatabaseManager {
         Ok(())
     }
 
+    /// Get cached analytics JSON for the given HEAD commit and options
+    pub fn get_repository_analytics(
+        &self,
+        repo_id: &str,
+        commit_hash: &str,
+        options: &str,
+    ) -> SqliteResult<Option<String>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT data FROM repository_analytics
+             WHERE repo_id = ?1 AND commit_hash = ?2 AND options = ?3",
+        )?;
+
+        let mut rows = stmt.query(params![repo_id, commit_hash, options])?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(row.get(0)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Replace cached analytics of a repository
+    pub fn save_repository_analytics(
+        &self,
+        repo_id: &str,
+        commit_hash: &str,
+        options: &str,
+        data: &str,
+    ) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let now = Utc::now().timestamp_millis();
+
+        conn.execute(
+            "INSERT OR REPLACE INTO repository_analytics (repo_id, commit_hash, options, data, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![repo_id, commit_hash, options, data, now],
+        )?;
+        Ok(())
+    }
+
     /// Delete Git repository record
     pub fn delete_git_repository(&self, id: &str) -> SqliteResult<()> {
         let conn_arc = self.conn();
@@ -0,0 +1,666 @@
+//! 标签管理模块
+//!
+//! 标签按工作空间存储在 tags 表中，`lang/rust` 形式的名称组成层级，父标签自动创建。
+//! entity_tags 关联笔记、网页链接和导入文件。notes 和 web_links 上的 tags JSON 列保留为
+//! 冗余副本，写入实体、改名、合并和删除标签时一并改写。
+
+use chrono::Utc;
+use rusqlite::{Connection, Result as SqliteResult, Row, params};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::app_state::DatabaseManager;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagEntityType {
+    Note,
+    Link,
+    File,
+}
+
+impl TagEntityType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            TagEntityType::Note => "note",
+            TagEntityType::Link => "link",
+            TagEntityType::File => "file",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "note" => Some(TagEntityType::Note),
+            "link" => Some(TagEntityType::Link),
+            "file" => Some(TagEntityType::File),
+            _ => None,
+        }
+    }
+
+    /// 带 tags JSON 列的表，导入文件没有
+    fn json_table(&self) -> Option<&str> {
+        match self {
+            TagEntityType::Note => Some("notes"),
+            TagEntityType::Link => Some("web_links"),
+            TagEntityType::File => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub id: String,
+    pub workspace_id: String,
+    /// 完整路径，如 `lang/rust`
+    pub name: String,
+    pub parent_id: Option<String>,
+    pub color: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagUsage {
+    #[serde(flatten)]
+    pub tag: Tag,
+    pub note_count: i64,
+    pub link_count: i64,
+    pub file_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaggedEntity {
+    pub entity_type: TagEntityType,
+    pub entity_id: String,
+    /// 笔记和链接的标题，文件的文件名
+    pub title: String,
+    pub updated_at: i64,
+}
+
+/// 规范化标签名：去掉 `#` 前缀和各级首尾空白，忽略空层级
+pub fn normalize_tag_name(name: &str) -> Option<String> {
+    let name = name.trim().trim_start_matches('#');
+    let segments: Vec<&str> = name
+        .split('/')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    (!segments.is_empty()).then(|| segments.join("/"))
+}
+
+/// 是否为 `ancestor` 本身或其子标签，与 `COLLATE NOCASE` 一致只忽略 ASCII 大小写
+pub fn is_tag_or_descendant(name: &str, ancestor: &str) -> bool {
+    name.get(..ancestor.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(ancestor))
+        && matches!(name.as_bytes().get(ancestor.len()), None | Some(b'/'))
+}
+
+/// 按 `SELECT id, workspace_id, name, parent_id, color, created_at, updated_at` 的列顺序读取
+fn row_to_tag(row: &Row) -> SqliteResult<Tag> {
+    Ok(Tag {
+        id: row.get(0)?,
+        workspace_id: row.get(1)?,
+        name: row.get(2)?,
+        parent_id: row.get(3)?,
+        color: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn get_tag_conn(conn: &Connection, id: &str) -> SqliteResult<Option<Tag>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, workspace_id, name, parent_id, color, created_at, updated_at FROM tags WHERE id = ?1",
+    )?;
+    let mut rows = stmt.query(params![id])?;
+    if let Some(row) = rows.next()? {
+        Ok(Some(row_to_tag(row)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// 按名称查找标签（不区分大小写），返回 (ID, 已保存的名称)
+fn find_tag(
+    conn: &Connection,
+    workspace_id: &str,
+    name: &str,
+) -> SqliteResult<Option<(String, String)>> {
+    let mut stmt =
+        conn.prepare("SELECT id, name FROM tags WHERE workspace_id = ?1 AND name = ?2")?;
+    let mut rows = stmt.query(params![workspace_id, name])?;
+    if let Some(row) = rows.next()? {
+        Ok(Some((row.get(0)?, row.get(1)?)))
+    } else {
+        Ok(None)
+    }
+}
+
+/// 查找或创建规范化后的标签及其各级父标签，返回标签 ID
+fn ensure_tag(conn: &Connection, workspace_id: &str, name: &str) -> SqliteResult<String> {
+    let now = Utc::now().timestamp_millis();
+    let mut path = String::new();
+    let mut tag_id: Option<String> = None;
+    for segment in name.split('/') {
+        if !path.is_empty() {
+            path.push('/');
+        }
+        path.push_str(segment);
+        let id = match find_tag(conn, workspace_id, &path)? {
+            // 沿用已有标签的大小写，子标签名称以父标签名称为前缀
+            Some((id, name)) => {
+                path = name;
+                id
+            }
+            None => {
+                let id = Uuid::new_v4().to_string();
+                conn.execute(
+                    "INSERT INTO tags (id, workspace_id, name, parent_id, color, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, NULL, ?5, ?5)",
+                    params![id, workspace_id, path, tag_id, now],
+                )?;
+                id
+            }
+        };
+        tag_id = Some(id);
+    }
+    Ok(tag_id.unwrap_or_default())
+}
+
+fn entity_tag_names(
+    conn: &Connection,
+    entity_type: &TagEntityType,
+    entity_id: &str,
+) -> SqliteResult<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT t.name FROM entity_tags e
+         JOIN tags t ON t.id = e.tag_id
+         WHERE e.entity_type = ?1 AND e.entity_id = ?2
+         ORDER BY e.rowid",
+    )?;
+    let rows = stmt.query_map(params![entity_type.as_str(), entity_id], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// 用 entity_tags 改写实体的 tags JSON 列
+fn refresh_entity_json(
+    conn: &Connection,
+    entity_type: &TagEntityType,
+    entity_id: &str,
+) -> SqliteResult<Vec<String>> {
+    let names = entity_tag_names(conn, entity_type, entity_id)?;
+    if let Some(table) = entity_type.json_table() {
+        let tags_json = serde_json::to_string(&names).unwrap_or_else(|_| "[]".to_string());
+        conn.execute(
+            &format!("UPDATE {} SET tags = ?1 WHERE id = ?2", table),
+            params![tags_json, entity_id],
+        )?;
+    }
+    Ok(names)
+}
+
+/// 替换实体的标签，返回规范化后的标签名。调用方持有连接锁
+pub(crate) fn set_entity_tags_conn(
+    conn: &Connection,
+    entity_type: &TagEntityType,
+    entity_id: &str,
+    workspace_id: &str,
+    names: &[String],
+) -> SqliteResult<Vec<String>> {
+    let current = entity_tag_names(conn, entity_type, entity_id)?;
+    // 内容保存时标签通常没变，跳过重写
+    if current == names {
+        return Ok(current);
+    }
+
+    let mut seen = HashSet::new();
+    let normalized: Vec<String> = names
+        .iter()
+        .filter_map(|name| normalize_tag_name(name))
+        .filter(|name| seen.insert(name.to_ascii_lowercase()))
+        .collect();
+
+    let now = Utc::now().timestamp_millis();
+    conn.execute(
+        "DELETE FROM entity_tags WHERE entity_type = ?1 AND entity_id = ?2",
+        params![entity_type.as_str(), entity_id],
+    )?;
+    for name in &normalized {
+        let tag_id = ensure_tag(conn, workspace_id, name)?;
+        conn.execute(
+            "INSERT OR IGNORE INTO entity_tags (tag_id, entity_type, entity_id, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![tag_id, entity_type.as_str(), entity_id, now],
+        )?;
+    }
+    refresh_entity_json(conn, entity_type, entity_id)
+}
+
+/// 首次升级时把 notes 和 web_links 中的 JSON 标签写入 tags 和 entity_tags
+pub(crate) fn migrate_json_tags(conn: &Connection) -> SqliteResult<()> {
+    let tag_count: i64 = conn.query_row("SELECT COUNT(*) FROM tags", [], |row| row.get(0))?;
+    if tag_count > 0 {
+        return Ok(());
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    for entity_type in [TagEntityType::Note, TagEntityType::Link] {
+        let Some(table) = entity_type.json_table() else {
+            continue;
+        };
+        let rows: Vec<(String, String, String)> = {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT id, workspace_id, tags FROM {} WHERE tags IS NOT NULL AND tags NOT IN ('', '[]')",
+                table
+            ))?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+            rows.collect::<SqliteResult<_>>()?
+        };
+        for (id, workspace_id, tags_json) in rows {
+            let names: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
+            set_entity_tags_conn(&tx, &entity_type, &id, &workspace_id, &names)?;
+        }
+    }
+    tx.commit()
+}
+
+impl DatabaseManager {
+    /// 工作空间内的全部标签及各类实体的使用次数，按名称排序
+    pub fn list_tags(&self, workspace_id: &str) -> SqliteResult<Vec<TagUsage>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.workspace_id, t.name, t.parent_id, t.color, t.created_at, t.updated_at,
+                    COUNT(CASE WHEN e.entity_type = 'note' THEN 1 END),
+                    COUNT(CASE WHEN e.entity_type = 'link' THEN 1 END),
+                    COUNT(CASE WHEN e.entity_type = 'file' THEN 1 END)
+             FROM tags t
+             LEFT JOIN entity_tags e ON e.tag_id = t.id
+             WHERE t.workspace_id = ?1
+             GROUP BY t.id
+             ORDER BY t.name",
+        )?;
+
+        let rows = stmt.query_map(params![workspace_id], |row| {
+            Ok(TagUsage {
+                tag: row_to_tag(row)?,
+                note_count: row.get(7)?,
+                link_count: row.get(8)?,
+                file_count: row.get(9)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    pub fn get_tag(&self, id: &str) -> SqliteResult<Option<Tag>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        get_tag_conn(&conn, id)
+    }
+
+    pub fn get_entity_tags(
+        &self,
+        entity_type: &TagEntityType,
+        entity_id: &str,
+    ) -> SqliteResult<Vec<Tag>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.workspace_id, t.name, t.parent_id, t.color, t.created_at, t.updated_at
+             FROM entity_tags e
+             JOIN tags t ON t.id = e.tag_id
+             WHERE e.entity_type = ?1 AND e.entity_id = ?2
+             ORDER BY e.rowid",
+        )?;
+        let rows = stmt.query_map(params![entity_type.as_str(), entity_id], row_to_tag)?;
+        rows.collect()
+    }
+
+    /// 替换实体的标签，返回规范化后的标签名
+    pub fn set_entity_tags(
+        &self,
+        entity_type: &TagEntityType,
+        entity_id: &str,
+        workspace_id: &str,
+        names: &[String],
+    ) -> SqliteResult<Vec<String>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let names = set_entity_tags_conn(&tx, entity_type, entity_id, workspace_id, names)?;
+        tx.commit()?;
+        Ok(names)
+    }
+
+    pub fn set_tag_color(&self, id: &str, color: Option<&str>) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        conn.execute(
+            "UPDATE tags SET color = ?1, updated_at = ?2 WHERE id = ?3",
+            params![color, Utc::now().timestamp_millis(), id],
+        )?;
+        Ok(())
+    }
+
+    /// 改名标签及其子标签，新名称已存在时合并到已有标签
+    ///
+    /// `new_name` 需已规范化，且不能是原标签的子标签。
+    pub fn rename_tag(&self, id: &str, new_name: &str) -> SqliteResult<Option<Tag>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let Some(tag) = get_tag_conn(&tx, id)? else {
+            return Ok(None);
+        };
+        let now = Utc::now().timestamp_millis();
+
+        let mut moving: Vec<Tag> = {
+            let mut stmt = tx.prepare(
+                "SELECT id, workspace_id, name, parent_id, color, created_at, updated_at FROM tags WHERE workspace_id = ?1",
+            )?;
+            let rows = stmt.query_map(params![tag.workspace_id], row_to_tag)?;
+            rows.collect::<SqliteResult<Vec<_>>>()?
+        };
+        moving.retain(|t| is_tag_or_descendant(&t.name, &tag.name));
+        moving.sort_by_key(|t| t.name.len());
+        let affected = tagged_entities_conn(&tx, &moving)?;
+        let moving_ids: HashSet<&str> = moving.iter().map(|t| t.id.as_str()).collect();
+
+        let mut renamed = Vec::new();
+        for old in &moving {
+            // 匹配的前缀与原标签名字节长度相同，子标签保留其余部分
+            let suffix = old.name.get(tag.name.len()..).unwrap_or_default();
+            let name = format!("{}{}", new_name, suffix);
+            let target = find_tag(&tx, &tag.workspace_id, &name)?
+                .map(|(target, _)| target)
+                .filter(|target| !moving_ids.contains(target.as_str()));
+            match target {
+                Some(target) => {
+                    // 已有同名标签：关联移到已有标签后删除原标签
+                    tx.execute(
+                        "UPDATE OR IGNORE entity_tags SET tag_id = ?1 WHERE tag_id = ?2",
+                        params![target, old.id],
+                    )?;
+                    tx.execute("DELETE FROM entity_tags WHERE tag_id = ?1", params![old.id])?;
+                    tx.execute(
+                        "UPDATE tags SET parent_id = ?1 WHERE parent_id = ?2",
+                        params![target, old.id],
+                    )?;
+                    tx.execute("DELETE FROM tags WHERE id = ?1", params![old.id])?;
+                    renamed.push(target);
+                }
+                None => {
+                    tx.execute(
+                        "UPDATE tags SET name = ?1, updated_at = ?2 WHERE id = ?3",
+                        params![name, now, old.id],
+                    )?;
+                    renamed.push(old.id.clone());
+                }
+            }
+        }
+
+        // 新路径上缺少的父标签按需创建
+        for tag_id in &renamed {
+            let Some(current) = get_tag_conn(&tx, tag_id)? else {
+                continue;
+            };
+            let parent_id = match current.name.rsplit_once('/') {
+                Some((parent, _)) => Some(ensure_tag(&tx, &current.workspace_id, parent)?),
+                None => None,
+            };
+            tx.execute(
+                "UPDATE tags SET parent_id = ?1 WHERE id = ?2",
+                params![parent_id, tag_id],
+            )?;
+        }
+        for (entity_type, entity_id) in &affected {
+            refresh_entity_json(&tx, entity_type, entity_id)?;
+        }
+
+        let result = match renamed.first() {
+            Some(tag_id) => get_tag_conn(&tx, tag_id)?,
+            None => None,
+        };
+        tx.commit()?;
+        Ok(result)
+    }
+
+    /// 删除标签及其子标签，并从实体上移除
+    pub fn delete_tag(&self, id: &str) -> SqliteResult<()> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let Some(tag) = get_tag_conn(&tx, id)? else {
+            return Ok(());
+        };
+
+        let removing: Vec<Tag> = {
+            let mut stmt = tx.prepare(
+                "SELECT id, workspace_id, name, parent_id, color, created_at, updated_at FROM tags WHERE workspace_id = ?1",
+            )?;
+            let rows = stmt.query_map(params![tag.workspace_id], row_to_tag)?;
+            rows.collect::<SqliteResult<Vec<_>>>()?
+        }
+        .into_iter()
+        .filter(|t| is_tag_or_descendant(&t.name, &tag.name))
+        .collect();
+        let affected = tagged_entities_conn(&tx, &removing)?;
+
+        for t in &removing {
+            tx.execute("DELETE FROM entity_tags WHERE tag_id = ?1", params![t.id])?;
+            tx.execute("DELETE FROM tags WHERE id = ?1", params![t.id])?;
+        }
+        for (entity_type, entity_id) in &affected {
+            refresh_entity_json(&tx, entity_type, entity_id)?;
+        }
+        tx.commit()
+    }
+
+    /// 使用标签的笔记、链接和文件，按更新时间倒序
+    pub fn list_tagged_entities(
+        &self,
+        tag_id: &str,
+        include_descendants: bool,
+    ) -> SqliteResult<Vec<TaggedEntity>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let Some(tag) = get_tag_conn(&conn, tag_id)? else {
+            return Ok(Vec::new());
+        };
+        let mut tag_ids = vec![tag.id.clone()];
+        if include_descendants {
+            let mut stmt = conn.prepare("SELECT id, name FROM tags WHERE workspace_id = ?1")?;
+            let rows = stmt.query_map(params![tag.workspace_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            for row in rows {
+                let (id, name) = row?;
+                if id != tag.id && is_tag_or_descendant(&name, &tag.name) {
+                    tag_ids.push(id);
+                }
+            }
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT e.entity_type, e.entity_id,
+                    COALESCE(n.title, l.title, f.name, ''),
+                    COALESCE(n.updated_at, l.updated_at, f.updated_at, e.created_at)
+             FROM entity_tags e
+             LEFT JOIN notes n ON e.entity_type = 'note' AND n.id = e.entity_id
+             LEFT JOIN web_links l ON e.entity_type = 'link' AND l.id = e.entity_id
+             LEFT JOIN imported_files f ON e.entity_type = 'file' AND f.id = e.entity_id
+             WHERE e.tag_id = ?1",
+        )?;
+        let mut seen = HashSet::new();
+        let mut entities = Vec::new();
+        for tag_id in &tag_ids {
+            let rows = stmt.query_map(params![tag_id], |row| {
+                let entity_type: String = row.get(0)?;
+                Ok(TaggedEntity {
+                    entity_type: TagEntityType::parse(&entity_type).unwrap_or(TagEntityType::Note),
+                    entity_id: row.get(1)?,
+                    title: row.get(2)?,
+                    updated_at: row.get(3)?,
+                })
+            })?;
+            for entity in rows {
+                let entity = entity?;
+                if seen.insert((entity.entity_type.clone(), entity.entity_id.clone())) {
+                    entities.push(entity);
+                }
+            }
+        }
+        entities.sort_by_key(|e| std::cmp::Reverse(e.updated_at));
+        Ok(entities)
+    }
+}
+
+/// 使用任一给定标签的实体
+fn tagged_entities_conn(
+    conn: &Connection,
+    tags: &[Tag],
+) -> SqliteResult<HashSet<(TagEntityType, String)>> {
+    let mut stmt =
+        conn.prepare("SELECT entity_type, entity_id FROM entity_tags WHERE tag_id = ?1")?;
+    let mut entities = HashSet::new();
+    for tag in tags {
+        let rows = stmt.query_map(params![tag.id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (entity_type, entity_id) = row?;
+            if let Some(entity_type) = TagEntityType::parse(&entity_type) {
+                entities.insert((entity_type, entity_id));
+            }
+        }
+    }
+    Ok(entities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::{Note, NoteType, WebLink, Workspace};
+    use std::env;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_tag_hierarchy_rename_merge_delete() {
+        let test_db_path = env::temp_dir().join(format!("test_tags_{}.db", Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+
+        let mut note = Note::new(
+            workspace.id.clone(),
+            "Note".to_string(),
+            NoteType::Markdown,
+            String::new(),
+            PathBuf::from("/tmp/note.md"),
+        );
+        note.tags = vec!["#lang / rust ".to_string(), "todo".to_string()];
+        db.create_note(&note).unwrap();
+        let mut link = WebLink::new(
+            workspace.id.clone(),
+            "Rust".to_string(),
+            "https://rust-lang.org".to_string(),
+        );
+        link.tags = vec!["Lang/Rust".to_string(), "js".to_string()];
+        db.create_web_link(&link).unwrap();
+
+        let tags = db.list_tags(&workspace.id).unwrap();
+        let names: Vec<&str> = tags.iter().map(|t| t.tag.name.as_str()).collect();
+        assert_eq!(names, vec!["js", "lang", "lang/rust", "todo"]);
+        let rust = tags.iter().find(|t| t.tag.name == "lang/rust").unwrap();
+        assert_eq!((rust.note_count, rust.link_count), (1, 1));
+        let lang = tags.iter().find(|t| t.tag.name == "lang").unwrap();
+        assert_eq!(rust.tag.parent_id.as_ref(), Some(&lang.tag.id));
+        assert_eq!(
+            db.get_note(&note.id).unwrap().unwrap().tags,
+            vec!["lang/rust", "todo"]
+        );
+
+        // 改名父标签时子标签一起改名
+        let renamed = db
+            .rename_tag(&lang.tag.id, "code/languages")
+            .unwrap()
+            .unwrap();
+        assert_eq!(renamed.name, "code/languages");
+        assert_eq!(
+            db.get_web_link(&link.id).unwrap().unwrap().tags,
+            vec!["code/languages/rust", "js"]
+        );
+        let entities = db.list_tagged_entities(&renamed.id, true).unwrap();
+        assert_eq!(entities.len(), 2);
+        assert!(
+            db.list_tagged_entities(&renamed.id, false)
+                .unwrap()
+                .is_empty()
+        );
+
+        // 合并 js 到 todo
+        let js = db
+            .list_tags(&workspace.id)
+            .unwrap()
+            .into_iter()
+            .find(|t| t.tag.name == "js")
+            .unwrap();
+        let merged = db.rename_tag(&js.tag.id, "todo").unwrap().unwrap();
+        assert_eq!(merged.name, "todo");
+        assert_eq!(db.list_tagged_entities(&merged.id, false).unwrap().len(), 2);
+        assert_eq!(
+            db.get_web_link(&link.id).unwrap().unwrap().tags,
+            vec!["code/languages/rust", "todo"]
+        );
+
+        db.delete_tag(&renamed.id).unwrap();
+        assert_eq!(db.get_note(&note.id).unwrap().unwrap().tags, vec!["todo"]);
+        let names: Vec<String> = db
+            .list_tags(&workspace.id)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.tag.name)
+            .collect();
+        assert_eq!(names, vec!["code", "todo"]);
+
+        db.delete_note(&note.id).unwrap();
+        assert_eq!(db.list_tagged_entities(&merged.id, false).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_rename_tag_folds_ascii_case_only() {
+        assert!(is_tag_or_descendant("Lang/Rust", "lang"));
+        assert!(!is_tag_or_descendant("language", "lang"));
+        assert!(!is_tag_or_descendant("ẞ/x", "ß"));
+
+        let test_db_path = env::temp_dir().join(format!("test_tags_{}.db", Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+
+        let mut note = Note::new(
+            workspace.id.clone(),
+            "Note".to_string(),
+            NoteType::Markdown,
+            String::new(),
+            PathBuf::from("/tmp/note.md"),
+        );
+        note.tags = vec!["ß".to_string(), "ẞ/x".to_string()];
+        db.create_note(&note).unwrap();
+
+        let tags = db.list_tags(&workspace.id).unwrap();
+        let sharp = tags.iter().find(|t| t.tag.name == "ß").unwrap();
+        db.rename_tag(&sharp.tag.id, "ss").unwrap().unwrap();
+        let mut names: Vec<String> = db
+            .list_tags(&workspace.id)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.tag.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["ss", "ẞ", "ẞ/x"]);
+    }
+}
@@ -0,0 +1,379 @@
+//! 待办事项模块
+//!
+//! Markdown 笔记中的 `- [ ]` / `- [x]` 列表项在保存笔记时解析到 todos 表，`@2026-10-20`
+//! 形式的标记作为截止日期。重新解析时按文本匹配已有待办以保留 ID，列表视图中的勾选
+//! 通过改写笔记内容中对应行的复选框完成。
+
+use chrono::{NaiveDate, Utc};
+use rusqlite::{Connection, Result as SqliteResult, Row, params};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use uuid::Uuid;
+
+use crate::app_state::{DatabaseManager, NoteType};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Todo {
+    pub id: String,
+    pub workspace_id: String,
+    pub note_id: String,
+    pub note_title: String,
+    /// 在笔记中的行号，从 1 开始
+    pub line: i32,
+    /// 去掉截止日期标记后的文本
+    pub text: String,
+    pub done: bool,
+    /// `YYYY-MM-DD`
+    pub due_date: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// 从笔记内容中解析出的待办
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedTodo {
+    pub line: i32,
+    pub text: String,
+    pub done: bool,
+    pub due_date: Option<String>,
+}
+
+/// 列表项复选框中状态字符的字节位置、是否完成和其后的文本
+fn checkbox(line: &str) -> Option<(usize, bool, &str)> {
+    let body = line.trim_start();
+    let indent = line.len() - body.len();
+    let after_marker = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| body.strip_prefix(marker))
+        .or_else(|| {
+            let digits = body.chars().take_while(|c| c.is_ascii_digit()).count();
+            let rest = &body[digits..];
+            (digits > 0)
+                .then(|| rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")))
+                .flatten()
+        })?;
+    let after_marker = after_marker.trim_start();
+    let state_offset = line.len() - after_marker.len() + 1;
+    let rest = after_marker.strip_prefix('[')?;
+    let done = match rest.chars().next()? {
+        ' ' => false,
+        'x' | 'X' => true,
+        _ => return None,
+    };
+    let text = rest[1..].strip_prefix(']')?;
+    if !(text.is_empty() || text.starts_with([' ', '\t'])) || state_offset <= indent {
+        return None;
+    }
+    Some((state_offset, done, text.trim()))
+}
+
+/// 拆出 `@YYYY-MM-DD` 截止日期，返回去掉标记后的文本
+fn split_due_date(text: &str) -> (String, Option<String>) {
+    let mut due_date = None;
+    let mut words = Vec::new();
+    for word in text.split_whitespace() {
+        let date = word
+            .strip_prefix('@')
+            .filter(|d| d.len() == 10)
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+        match date {
+            Some(date) if due_date.is_none() => {
+                due_date = Some(date.format("%Y-%m-%d").to_string())
+            }
+            _ => words.push(word),
+        }
+    }
+    (words.join(" "), due_date)
+}
+
+/// 解析 Markdown 内容中的待办，跳过围栏代码块和空待办
+pub fn parse_todos(content: &str) -> Vec<ParsedTodo> {
+    let mut todos = Vec::new();
+    let mut in_fence = false;
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let Some((_, done, text)) = checkbox(line) else {
+            continue;
+        };
+        let (text, due_date) = split_due_date(text);
+        if text.is_empty() {
+            continue;
+        }
+        todos.push(ParsedTodo {
+            line: index as i32 + 1,
+            text,
+            done,
+            due_date,
+        });
+    }
+    todos
+}
+
+/// 改写第 `line` 行（从 1 开始）的复选框，该行不是待办时返回 None
+pub fn set_todo_line_state(content: &str, line: i32, done: bool) -> Option<String> {
+    let mut out = String::with_capacity(content.len());
+    let mut found = false;
+    for (index, text) in content.split_inclusive('\n').enumerate() {
+        if index as i32 + 1 == line {
+            let (offset, _, _) = checkbox(text)?;
+            out.push_str(&text[..offset]);
+            out.push(if done { 'x' } else { ' ' });
+            out.push_str(&text[offset + 1..]);
+            found = true;
+        } else {
+            out.push_str(text);
+        }
+    }
+    found.then_some(out)
+}
+
+fn row_to_todo(row: &Row) -> SqliteResult<Todo> {
+    Ok(Todo {
+        id: row.get(0)?,
+        workspace_id: row.get(1)?,
+        note_id: row.get(2)?,
+        note_title: row.get(3)?,
+        line: row.get(4)?,
+        text: row.get(5)?,
+        done: row.get::<_, i32>(6)? != 0,
+        due_date: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+/// 重新解析笔记的待办，文本相同的待办按出现顺序沿用原来的 ID
+///
+/// 只解析 Markdown 笔记，其他类型的笔记没有待办。
+pub(crate) fn sync_note_todos_conn(
+    conn: &Connection,
+    note_id: &str,
+    workspace_id: &str,
+    note_type: &NoteType,
+    content: &str,
+) -> SqliteResult<()> {
+    let parsed = match note_type {
+        NoteType::Markdown => parse_todos(content),
+        _ => Vec::new(),
+    };
+    let existing: Vec<(String, ParsedTodo, i64, i64)> = {
+        let mut stmt = conn.prepare(
+            "SELECT id, line, text, done, due_date, created_at, updated_at
+             FROM todos WHERE note_id = ?1 ORDER BY line",
+        )?;
+        let rows = stmt.query_map(params![note_id], |row| {
+            let todo = ParsedTodo {
+                line: row.get(1)?,
+                text: row.get(2)?,
+                done: row.get::<_, i32>(3)? != 0,
+                due_date: row.get(4)?,
+            };
+            Ok((row.get(0)?, todo, row.get(5)?, row.get(6)?))
+        })?;
+        rows.collect::<SqliteResult<_>>()?
+    };
+    // 保存笔记时待办通常没变，跳过重写
+    if parsed.len() == existing.len() && parsed.iter().zip(&existing).all(|(p, e)| *p == e.1) {
+        return Ok(());
+    }
+
+    let mut by_text: HashMap<&str, VecDeque<&(String, ParsedTodo, i64, i64)>> = HashMap::new();
+    for entry in &existing {
+        by_text
+            .entry(entry.1.text.as_str())
+            .or_default()
+            .push_back(entry);
+    }
+    let now = Utc::now().timestamp_millis();
+    conn.execute("DELETE FROM todos WHERE note_id = ?1", params![note_id])?;
+    for todo in &parsed {
+        let previous = by_text
+            .get_mut(todo.text.as_str())
+            .and_then(|q| q.pop_front());
+        let (id, created_at, updated_at) = match previous {
+            Some((id, old, created_at, updated_at)) => {
+                // 只移动了位置的待办不算修改
+                let changed = old.done != todo.done || old.due_date != todo.due_date;
+                let updated_at = if changed { now } else { *updated_at };
+                (id.clone(), *created_at, updated_at)
+            }
+            None => (Uuid::new_v4().to_string(), now, now),
+        };
+        conn.execute(
+            "INSERT INTO todos (id, workspace_id, note_id, line, text, done, due_date, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                id,
+                workspace_id,
+                note_id,
+                todo.line,
+                todo.text,
+                todo.done as i32,
+                todo.due_date,
+                created_at,
+                updated_at,
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// 首次升级时解析已有 Markdown 笔记中的待办
+pub(crate) fn migrate_note_todos(conn: &Connection) -> SqliteResult<()> {
+    let todo_count: i64 = conn.query_row("SELECT COUNT(*) FROM todos", [], |row| row.get(0))?;
+    if todo_count > 0 {
+        return Ok(());
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    let rows: Vec<(String, String, String)> = {
+        let mut stmt = tx.prepare(
+            "SELECT id, workspace_id, content FROM notes
+             WHERE note_type = 'markdown' AND content LIKE '%[%]%'",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        rows.collect::<SqliteResult<_>>()?
+    };
+    for (id, workspace_id, content) in rows {
+        sync_note_todos_conn(&tx, &id, &workspace_id, &NoteType::Markdown, &content)?;
+    }
+    tx.commit()
+}
+
+impl DatabaseManager {
+    pub fn get_todo(&self, id: &str) -> SqliteResult<Option<Todo>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.workspace_id, t.note_id, n.title, t.line, t.text, t.done, t.due_date,
+                    t.created_at, t.updated_at
+             FROM todos t JOIN notes n ON n.id = t.note_id
+             WHERE t.id = ?1",
+        )?;
+        let mut rows = stmt.query(params![id])?;
+        match rows.next()? {
+            Some(row) => Ok(Some(row_to_todo(row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 工作空间内的待办，有截止日期的按日期在前，其余按笔记最近修改排序
+    ///
+    /// 默认不包含已完成的待办和归档笔记中的待办。
+    pub fn list_todos(&self, workspace_id: &str, include_done: bool) -> SqliteResult<Vec<Todo>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.workspace_id, t.note_id, n.title, t.line, t.text, t.done, t.due_date,
+                    t.created_at, t.updated_at
+             FROM todos t JOIN notes n ON n.id = t.note_id
+             WHERE t.workspace_id = ?1 AND n.is_archived = 0 AND (?2 OR t.done = 0)
+             ORDER BY t.due_date IS NULL, t.due_date, n.updated_at DESC, t.line",
+        )?;
+        let rows = stmt.query_map(params![workspace_id, include_done], row_to_todo)?;
+        rows.collect()
+    }
+
+    pub fn list_note_todos(&self, note_id: &str) -> SqliteResult<Vec<Todo>> {
+        let conn_arc = self.conn();
+        let conn = conn_arc.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.workspace_id, t.note_id, n.title, t.line, t.text, t.done, t.due_date,
+                    t.created_at, t.updated_at
+             FROM todos t JOIN notes n ON n.id = t.note_id
+             WHERE t.note_id = ?1
+             ORDER BY t.line",
+        )?;
+        let rows = stmt.query_map(params![note_id], row_to_todo)?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::{Note, Workspace};
+    use std::env;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_and_toggle_todos() {
+        let content = "# Plan\n- [ ] Write spec @2026-10-20\n  * [x] Review\n\
+                       ```\n- [ ] not a todo\n```\n1. [ ] Ship @tomorrow\n- [] nope\n- [ ]\n";
+        let todos = parse_todos(content);
+        assert_eq!(
+            todos,
+            [
+                ParsedTodo {
+                    line: 2,
+                    text: "Write spec".to_string(),
+                    done: false,
+                    due_date: Some("2026-10-20".to_string()),
+                },
+                ParsedTodo {
+                    line: 3,
+                    text: "Review".to_string(),
+                    done: true,
+                    due_date: None,
+                },
+                ParsedTodo {
+                    line: 7,
+                    text: "Ship @tomorrow".to_string(),
+                    done: false,
+                    due_date: None,
+                },
+            ]
+        );
+
+        let toggled = set_todo_line_state(content, 2, true).unwrap();
+        assert!(toggled.starts_with("# Plan\n- [x] Write spec @2026-10-20\n  * [x] Review\n"));
+        let toggled = set_todo_line_state(&toggled, 3, false).unwrap();
+        assert!(toggled.contains("\n  * [ ] Review\n"));
+        assert_eq!(toggled.len(), content.len());
+        assert_eq!(set_todo_line_state(content, 1, true), None);
+        assert_eq!(set_todo_line_state(content, 99, true), None);
+    }
+
+    #[test]
+    fn test_sync_note_todos_keeps_ids() {
+        let test_db_path = env::temp_dir().join(format!("test_todos_{}.db", Uuid::new_v4()));
+        let db = DatabaseManager::new(test_db_path).unwrap();
+        let workspace = Workspace::new("Test Workspace".to_string(), None);
+        db.create_workspace(&workspace).unwrap();
+
+        let mut note = Note::new(
+            workspace.id.clone(),
+            "Plan".to_string(),
+            NoteType::Markdown,
+            "- [ ] Write spec @2026-10-20\n- [ ] Review\n".to_string(),
+            PathBuf::from("/notes/Plan.md"),
+        );
+        db.create_note(&note).unwrap();
+        let before = db.list_todos(&workspace.id, false).unwrap();
+        assert_eq!(before.len(), 2);
+        assert_eq!(before[0].text, "Write spec");
+        assert_eq!(before[0].note_title, "Plan");
+
+        note.content = "Intro\n- [ ] Review\n- [x] Write spec @2026-10-20\n".to_string();
+        db.update_note(&note).unwrap();
+        let after = db.list_note_todos(&note.id).unwrap();
+        assert_eq!(after[0].id, before[1].id);
+        assert_eq!(after[0].line, 2);
+        assert_eq!(after[1].id, before[0].id);
+        assert!(after[1].done);
+        assert_eq!(db.list_todos(&workspace.id, false).unwrap().len(), 1);
+        assert_eq!(db.list_todos(&workspace.id, true).unwrap().len(), 2);
+
+        note.note_type = NoteType::RichText;
+        db.update_note(&note).unwrap();
+        assert!(db.list_note_todos(&note.id).unwrap().is_empty());
+    }
+}
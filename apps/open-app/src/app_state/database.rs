@@ -6,6 +6,8 @@ use rusqlite::{Connection, Result as SqliteResult};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
+use crate::app_state::{migrate_json_tags, migrate_note_revisions, migrate_note_todos};
+
 pub struct DatabaseManager {
     conn: Arc<Mutex<Connection>>,
 }
@@ -83,6 +85,111 @@ impl DatabaseManager {
             [],
         )?;
 
+        // notes_fts 全文索引
+        Self::ensure_notes_fts(&conn)?;
+
+        // note_revisions 表（笔记修订历史）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS note_revisions (
+                id TEXT PRIMARY KEY,
+                note_id TEXT NOT NULL,
+                title TEXT NOT NULL,
+                content TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // note_links 表（笔记间的 [[双链]]，target_id 为空表示未解析）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS note_links (
+                id TEXT PRIMARY KEY,
+                source_id TEXT NOT NULL,
+                workspace_id TEXT NOT NULL,
+                target_title TEXT NOT NULL,
+                target_id TEXT,
+                alias TEXT,
+                heading TEXT,
+                context TEXT NOT NULL DEFAULT '',
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (source_id) REFERENCES notes(id) ON DELETE CASCADE,
+                FOREIGN KEY (target_id) REFERENCES notes(id) ON DELETE SET NULL
+            )",
+            [],
+        )?;
+
+        // note_sync_state 表（笔记与 Markdown 文件的同步状态，哈希用于判断哪一侧有修改）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS note_sync_state (
+                note_id TEXT PRIMARY KEY,
+                workspace_id TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                note_hash TEXT NOT NULL,
+                file_hash TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'synced',
+                synced_at INTEGER NOT NULL,
+                FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // note_templates 表（工作空间内的笔记模板）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS note_templates (
+                id TEXT PRIMARY KEY,
+                workspace_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                note_type TEXT NOT NULL,
+                title TEXT NOT NULL,
+                content TEXT NOT NULL,
+                tags TEXT,
+                fields TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // note_insights 表（后台计算的大纲、字数和摘要，source_updated_at 用于判断是否过期）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS note_insights (
+                note_id TEXT PRIMARY KEY,
+                outline TEXT NOT NULL,
+                word_count INTEGER NOT NULL,
+                extractive_summary TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                source_updated_at INTEGER NOT NULL,
+                llm_summary TEXT,
+                llm_model TEXT,
+                llm_content_hash TEXT,
+                llm_generated_at INTEGER,
+                computed_at INTEGER NOT NULL,
+                FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // todos 表（从 Markdown 笔记的复选框列表项解析，保存笔记时重新生成）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS todos (
+                id TEXT PRIMARY KEY,
+                workspace_id TEXT NOT NULL,
+                note_id TEXT NOT NULL,
+                line INTEGER NOT NULL,
+                text TEXT NOT NULL,
+                done INTEGER NOT NULL DEFAULT 0,
+                due_date TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
         // imported_directories 表（需要先创建，因为 imported_files 引用它）
         conn.execute(
             "CREATE TABLE IF NOT EXISTS imported_directories (
@@ -125,6 +232,35 @@ impl DatabaseManager {
             [],
         )?;
 
+        // note_import_sources 表（导入来源与笔记/文件的对应关系，重复导入时据此更新而不是新建）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS note_import_sources (
+                workspace_id TEXT NOT NULL,
+                source_path TEXT NOT NULL,
+                source_type TEXT NOT NULL,
+                target_type TEXT NOT NULL,
+                target_id TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                imported_at INTEGER NOT NULL,
+                PRIMARY KEY (workspace_id, source_path),
+                FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // note_attachments 表（笔记引用的附件）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS note_attachments (
+                note_id TEXT NOT NULL,
+                file_id TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (note_id, file_id),
+                FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE,
+                FOREIGN KEY (file_id) REFERENCES imported_files(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
         // web_links 表
         conn.execute(
             "CREATE TABLE IF NOT EXISTS web_links (
@@ -148,6 +284,47 @@ impl DatabaseManager {
             [],
         )?;
 
+        // tags 表（按工作空间的标签，`a/b` 形式的名称通过 parent_id 组成层级）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tags (
+                id TEXT PRIMARY KEY,
+                workspace_id TEXT NOT NULL,
+                name TEXT NOT NULL COLLATE NOCASE,
+                parent_id TEXT,
+                color TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                UNIQUE (workspace_id, name),
+                FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+                FOREIGN KEY (parent_id) REFERENCES tags(id) ON DELETE SET NULL
+            )",
+            [],
+        )?;
+
+        // entity_tags 表（标签与笔记、网页链接、导入文件的关联，实体删除时由触发器清理）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS entity_tags (
+                tag_id TEXT NOT NULL,
+                entity_type TEXT NOT NULL,
+                entity_id TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (tag_id, entity_type, entity_id),
+                FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        conn.execute_batch(
+            "CREATE TRIGGER IF NOT EXISTS notes_entity_tags_delete AFTER DELETE ON notes BEGIN
+                DELETE FROM entity_tags WHERE entity_type = 'note' AND entity_id = old.id;
+            END;
+            CREATE TRIGGER IF NOT EXISTS web_links_entity_tags_delete AFTER DELETE ON web_links BEGIN
+                DELETE FROM entity_tags WHERE entity_type = 'link' AND entity_id = old.id;
+            END;
+            CREATE TRIGGER IF NOT EXISTS imported_files_entity_tags_delete AFTER DELETE ON imported_files BEGIN
+                DELETE FROM entity_tags WHERE entity_type = 'file' AND entity_id = old.id;
+            END;",
+        )?;
+
         // git_repositories 表
         conn.execute(
             "CREATE TABLE IF NOT EXISTS git_repositories (
@@ -168,6 +345,7 @@ impl DatabaseManager {
                 symbol_count INTEGER NOT NULL DEFAULT 0,
                 vector_count INTEGER NOT NULL DEFAULT 0,
                 is_archived INTEGER NOT NULL DEFAULT 0,
+                parent_id TEXT REFERENCES git_repositories(id) ON DELETE CASCADE,
                 created_at INTEGER NOT NULL,
                 updated_at INTEGER NOT NULL,
                 FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
@@ -322,12 +500,121 @@ impl DatabaseManager {
             [],
         )?;
 
+        // repository_analytics 表（仓库分析结果缓存）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS repository_analytics (
+                repo_id TEXT PRIMARY KEY,
+                commit_hash TEXT NOT NULL,
+                options TEXT NOT NULL,
+                data TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (repo_id) REFERENCES git_repositories(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // project_manifests 表（索引时识别的项目清单）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS project_manifests (
+                id TEXT PRIMARY KEY,
+                repo_id TEXT NOT NULL,
+                workspace_id TEXT NOT NULL,
+                path TEXT NOT NULL,
+                project_type TEXT NOT NULL,
+                name TEXT,
+                version TEXT,
+                workspace_members TEXT NOT NULL DEFAULT '[]',
+                scripts TEXT NOT NULL DEFAULT '{}',
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (repo_id) REFERENCES git_repositories(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // manifest_dependencies 表（清单中声明的依赖）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS manifest_dependencies (
+                manifest_id TEXT NOT NULL,
+                repo_id TEXT NOT NULL,
+                workspace_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                version TEXT,
+                kind TEXT NOT NULL,
+                FOREIGN KEY (manifest_id) REFERENCES project_manifests(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // git_credentials 表（远程仓库凭据，不写入 config.json）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS git_credentials (
+                id TEXT PRIMARY KEY,
+                remote_pattern TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                username TEXT,
+                ssh_key_path TEXT,
+                secret TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
         self.run_migrations(&conn)?;
         self.create_indexes(&conn)?;
 
         Ok(())
     }
 
+    /// 创建 notes_fts（trigram 分词以支持中文子串检索）
+    ///
+    /// 普通 FTS 表，按 `note_id` 关联 notes，由触发器同步。notes 的主键是 TEXT，其隐式 rowid
+    /// 可能被 VACUUM 重新编号，因此不使用外部内容表。列结构不同的旧表会被删除重建并回填。
+    fn ensure_notes_fts(conn: &Connection) -> SqliteResult<()> {
+        let columns: Vec<String> = {
+            let mut stmt = conn.prepare("SELECT name FROM pragma_table_info('notes_fts')")?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            rows.collect::<SqliteResult<_>>()?
+        };
+        let rebuild = columns != ["note_id", "title", "content"];
+        if rebuild {
+            conn.execute_batch(
+                "DROP TRIGGER IF EXISTS notes_fts_insert;
+                 DROP TRIGGER IF EXISTS notes_fts_delete;
+                 DROP TRIGGER IF EXISTS notes_fts_update;
+                 DROP TABLE IF EXISTS notes_fts;",
+            )?;
+        }
+
+        conn.execute_batch(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(
+                note_id UNINDEXED,
+                title,
+                content,
+                tokenize='trigram'
+            );
+            CREATE TRIGGER IF NOT EXISTS notes_fts_insert AFTER INSERT ON notes BEGIN
+                INSERT INTO notes_fts (note_id, title, content)
+                VALUES (new.id, new.title, new.content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS notes_fts_delete AFTER DELETE ON notes BEGIN
+                DELETE FROM notes_fts WHERE note_id = old.id;
+            END;
+            CREATE TRIGGER IF NOT EXISTS notes_fts_update AFTER UPDATE OF title, content ON notes BEGIN
+                DELETE FROM notes_fts WHERE note_id = old.id;
+                INSERT INTO notes_fts (note_id, title, content)
+                VALUES (new.id, new.title, new.content);
+            END;",
+        )?;
+        if rebuild {
+            conn.execute(
+                "INSERT INTO notes_fts (note_id, title, content) SELECT id, title, content FROM notes",
+                [],
+            )?;
+        }
+        Ok(())
+    }
+
     fn run_migrations(&self, conn: &Connection) -> SqliteResult<()> {
         // workspaces migrations
         let _ = conn.execute("ALTER TABLE workspaces ADD COLUMN settings TEXT", []);
@@ -335,6 +622,21 @@ impl DatabaseManager {
         // web_links migrations
         let _ = conn.execute("ALTER TABLE web_links ADD COLUMN content TEXT", []);
 
+        // git_repositories migrations
+        let _ = conn.execute(
+            "ALTER TABLE git_repositories ADD COLUMN parent_id TEXT REFERENCES git_repositories(id) ON DELETE CASCADE",
+            [],
+        );
+
+        // tags 回填：升级前的标签只保存在 notes 和 web_links 的 JSON 列中
+        migrate_json_tags(conn)?;
+
+        // todos 回填：升级前已有笔记中的待办
+        migrate_note_todos(conn)?;
+
+        // note_revisions 回填：没有修订版本的笔记保存当前内容，首次编辑前的内容可以恢复
+        migrate_note_revisions(conn)?;
+
         Ok(())
     }
 
@@ -387,6 +689,64 @@ impl DatabaseManager {
             [],
         )?;
 
+        // note_revisions 索引
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_note_revisions_note ON note_revisions(note_id, created_at DESC)",
+            [],
+        )?;
+
+        // note_templates 索引
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_note_templates_workspace ON note_templates(workspace_id, name)",
+            [],
+        )?;
+
+        // todos 索引
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_todos_workspace ON todos(workspace_id, done, due_date)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_todos_note ON todos(note_id)",
+            [],
+        )?;
+
+        // note_links 索引
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_note_links_source ON note_links(source_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_note_links_target ON note_links(target_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_note_links_unresolved ON note_links(workspace_id, target_title COLLATE NOCASE) WHERE target_id IS NULL",
+            [],
+        )?;
+
+        // note_sync_state 索引
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_note_sync_state_path ON note_sync_state(file_path)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_note_sync_state_status ON note_sync_state(workspace_id, status)",
+            [],
+        )?;
+
+        // entity_tags 索引
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_entity_tags_entity ON entity_tags(entity_type, entity_id)",
+            [],
+        )?;
+
+        // note_attachments 索引
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_note_attachments_file ON note_attachments(file_id)",
+            [],
+        )?;
+
         // imported_files 索引
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_files_workspace ON imported_files(workspace_id)",
@@ -462,6 +822,24 @@ impl DatabaseManager {
             "CREATE INDEX IF NOT EXISTS idx_repos_workspace_archived ON git_repositories(workspace_id, is_archived)",
             [],
         )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_repos_parent ON git_repositories(parent_id)",
+            [],
+        )?;
+
+        // project_manifests / manifest_dependencies 索引
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_manifests_repo ON project_manifests(repo_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_manifest_deps_manifest ON manifest_dependencies(manifest_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_manifest_deps_name ON manifest_dependencies(workspace_id, name COLLATE NOCASE)",
+            [],
+        )?;
 
         // chats 索引
         conn.execute(
@@ -565,6 +943,12 @@ impl DatabaseManager {
             [],
         )?;
 
+        // git_credentials 索引
+        conn.execute(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_git_credentials_pattern ON git_credentials(remote_pattern)",
+            [],
+        )?;
+
         Ok(())
     }
 
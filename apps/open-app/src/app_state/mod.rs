@@ -6,12 +6,25 @@ mod app_config;
 mod app_state_chat;
 mod app_state_file;
 mod app_state_folder;
+mod app_state_git_credential;
 mod app_state_index_job;
 mod app_state_link;
+mod app_state_manifest;
 mod app_state_note;
+mod app_state_note_calendar;
+mod app_state_note_import;
+mod app_state_note_insight;
+mod app_state_note_link;
+mod app_state_note_revision;
+mod app_state_note_search;
+mod app_state_note_sync;
+mod app_state_note_template;
+mod app_state_note_tree;
 mod app_state_repo;
+mod app_state_tag;
 mod app_state_task;
 mod app_state_terminal;
+mod app_state_todo;
 mod app_state_webview;
 mod app_state_workspace;
 mod database;
@@ -21,12 +34,24 @@ pub use app_config::*;
 pub use app_state_chat::*;
 pub use app_state_file::*;
 pub use app_state_folder::*;
+pub use app_state_git_credential::*;
 pub use app_state_index_job::*;
 pub use app_state_link::*;
+pub use app_state_manifest::*;
 pub use app_state_note::*;
+pub use app_state_note_import::*;
+pub use app_state_note_insight::*;
+pub use app_state_note_link::*;
+pub use app_state_note_revision::*;
+pub use app_state_note_search::*;
+pub use app_state_note_sync::*;
+pub use app_state_note_template::*;
+pub use app_state_note_tree::*;
 pub use app_state_repo::*;
+pub use app_state_tag::*;
 pub use app_state_task::*;
 pub use app_state_terminal::*;
+pub use app_state_todo::*;
 pub use app_state_webview::*;
 pub use app_state_workspace::*;
 pub use database::*;
@@ -18,6 +18,8 @@ pub fn run() {
     let app_state = AppState::new().expect("Failed to initialize app state");
     init_app_dirs().expect("Failed to initialize app dirs");
     let task_manager = TaskManager::new();
+    app_service::start_note_insight_worker(app_state.clone());
+    let sync_state = app_state.clone();
 
     let mut builder = tauri::Builder::default();
     // states
@@ -25,9 +27,25 @@ pub fn run() {
     builder = builder.manage(Mutex::new(task_manager));
 
     // plugins
-    builder = builder.setup(|app: &mut tauri::App| {
+    builder = builder.setup(move |app: &mut tauri::App| {
         #[cfg(desktop)]
         app_plugins::setup_desktop_plugins(app);
+
+        // 恢复上次开启的 Markdown 同步，否则重启后外部修改不会被导入
+        let config = sync_state.config();
+        if config
+            .notes
+            .as_ref()
+            .is_some_and(|notes| notes.markdown_sync)
+        {
+            let app_handle = app.handle().clone();
+            let db = sync_state.db();
+            tauri::async_runtime::spawn_blocking(move || {
+                if let Err(e) = app_service::start_markdown_sync(app_handle, db, &config) {
+                    log::warn!("Failed to resume note sync: {}", e);
+                }
+            });
+        }
         Ok(())
     });
     builder = app_plugins::setup_general_plugins(builder);
@@ -2,6 +2,16 @@ import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import path from 'path-browserify';
 
+export type GitFileStatus =
+  | 'added'
+  | 'modified'
+  | 'deleted'
+  | 'renamed'
+  | 'typechange'
+  | 'untracked'
+  | 'ignored'
+  | 'conflicted';
+
 export interface FileTreeNode {
   path: string;
   name: string;
@@ -9,6 +19,7 @@ export interface FileTreeNode {
   isHidden: boolean;
   size?: number;
   modified?: number;
+  gitStatus?: GitFileStatus;
   children?: FileTreeNode[];
   isExpanded?: boolean;
   isLoading?: boolean;